@@ -0,0 +1,22 @@
+CREATE TABLE IF NOT EXISTS quotes (
+    id TEXT PRIMARY KEY NOT NULL,
+    context TEXT NOT NULL,
+    sell_asset TEXT NOT NULL,
+    sell_amount REAL NOT NULL,
+    buy_asset TEXT NOT NULL,
+    buy_amount REAL NOT NULL,
+    price REAL NOT NULL,
+    total_price REAL NOT NULL,
+    fee_total REAL NOT NULL,
+    fee_asset TEXT NOT NULL,
+    usd_amount REAL NOT NULL,
+    rate_source TEXT NOT NULL,
+    created_at DATETIME NOT NULL,
+    expires_at DATETIME NOT NULL,
+    used_at DATETIME,
+    used_by TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_quotes_expires_at ON quotes(expires_at);
+
+ALTER TABLE bank_transfers ADD COLUMN quote_id TEXT REFERENCES quotes(id);
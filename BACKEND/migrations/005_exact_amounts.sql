@@ -0,0 +1,84 @@
+-- Money is stored as canonical decimal TEXT (see models::amount) instead of REAL.
+-- SQLite cannot change a column type in place, so each REAL column is copied
+-- into a TEXT column that then takes its name.
+--
+-- Nothing is converted through REAL and nothing defaults to zero: a row whose
+-- value does not parse as a non-negative decimal with at most the column's
+-- decimals is inserted into `unparseable_amounts`, whose CHECK fails and
+-- aborts the migration so it can be fixed by hand.
+
+CREATE TEMP TABLE unparseable_amounts (
+    source TEXT NOT NULL,
+    value TEXT,
+    CONSTRAINT amount_does_not_parse CHECK (0)
+);
+
+INSERT INTO unparseable_amounts
+SELECT 'transactions.amount:' || id, amount FROM transactions
+WHERE amount IS NULL
+   OR typeof(amount) <> 'text'
+   OR amount = ''
+   OR amount GLOB '*[^0-9.]*'
+   OR amount GLOB '*.*.*'
+   OR amount GLOB '.*'
+   OR amount GLOB '*.'
+   OR (instr(amount, '.') > 0 AND length(amount) - instr(amount, '.') > 7);
+
+-- REAL columns only hold what was already stored as a double; printf renders
+-- that double exactly at the column's scale. Text that never parsed as a
+-- number and negative values are refused instead.
+INSERT INTO unparseable_amounts
+SELECT 'bank_transfers.amount_fiat:' || id, amount_fiat FROM bank_transfers
+WHERE typeof(amount_fiat) NOT IN ('integer', 'real') OR amount_fiat < 0;
+
+INSERT INTO unparseable_amounts
+SELECT 'quotes:' || id, NULL FROM quotes
+WHERE typeof(sell_amount) NOT IN ('integer', 'real') OR sell_amount < 0
+   OR typeof(buy_amount) NOT IN ('integer', 'real') OR buy_amount < 0
+   OR typeof(price) NOT IN ('integer', 'real') OR price < 0
+   OR typeof(total_price) NOT IN ('integer', 'real') OR total_price < 0
+   OR typeof(fee_total) NOT IN ('integer', 'real') OR fee_total < 0
+   OR typeof(usd_amount) NOT IN ('integer', 'real') OR usd_amount < 0;
+
+DROP TABLE unparseable_amounts;
+
+-- Pads the decimal text to 7 places without going through a float.
+UPDATE transactions SET amount = CASE
+    WHEN instr(amount, '.') = 0 THEN amount || '.0000000'
+    ELSE amount || substr('0000000', 1, 7 - (length(amount) - instr(amount, '.')))
+END;
+
+ALTER TABLE bank_transfers ADD COLUMN amount_fiat_text TEXT NOT NULL DEFAULT '0.00';
+UPDATE bank_transfers SET amount_fiat_text = printf('%.2f', amount_fiat);
+ALTER TABLE bank_transfers DROP COLUMN amount_fiat;
+ALTER TABLE bank_transfers RENAME COLUMN amount_fiat_text TO amount_fiat;
+
+ALTER TABLE quotes ADD COLUMN sell_amount_text TEXT NOT NULL DEFAULT '0.0000000';
+UPDATE quotes SET sell_amount_text = printf('%.7f', sell_amount);
+ALTER TABLE quotes DROP COLUMN sell_amount;
+ALTER TABLE quotes RENAME COLUMN sell_amount_text TO sell_amount;
+
+ALTER TABLE quotes ADD COLUMN buy_amount_text TEXT NOT NULL DEFAULT '0.0000000';
+UPDATE quotes SET buy_amount_text = printf('%.7f', buy_amount);
+ALTER TABLE quotes DROP COLUMN buy_amount;
+ALTER TABLE quotes RENAME COLUMN buy_amount_text TO buy_amount;
+
+ALTER TABLE quotes ADD COLUMN price_text TEXT NOT NULL DEFAULT '0.000000000000';
+UPDATE quotes SET price_text = printf('%.12f', price);
+ALTER TABLE quotes DROP COLUMN price;
+ALTER TABLE quotes RENAME COLUMN price_text TO price;
+
+ALTER TABLE quotes ADD COLUMN total_price_text TEXT NOT NULL DEFAULT '0.000000000000';
+UPDATE quotes SET total_price_text = printf('%.12f', total_price);
+ALTER TABLE quotes DROP COLUMN total_price;
+ALTER TABLE quotes RENAME COLUMN total_price_text TO total_price;
+
+ALTER TABLE quotes ADD COLUMN fee_total_text TEXT NOT NULL DEFAULT '0.0000000';
+UPDATE quotes SET fee_total_text = printf('%.7f', fee_total);
+ALTER TABLE quotes DROP COLUMN fee_total;
+ALTER TABLE quotes RENAME COLUMN fee_total_text TO fee_total;
+
+ALTER TABLE quotes ADD COLUMN usd_amount_text TEXT NOT NULL DEFAULT '0.0000000';
+UPDATE quotes SET usd_amount_text = printf('%.7f', usd_amount);
+ALTER TABLE quotes DROP COLUMN usd_amount;
+ALTER TABLE quotes RENAME COLUMN usd_amount_text TO usd_amount;
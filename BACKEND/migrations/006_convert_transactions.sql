@@ -0,0 +1,3 @@
+-- Convert transactions record the received leg next to the sent one.
+ALTER TABLE transactions ADD COLUMN dest_amount TEXT;
+ALTER TABLE transactions ADD COLUMN dest_asset TEXT;
@@ -0,0 +1,24 @@
+-- Every rate the oracle returns, kept raw for disputes and rolled up into candles.
+CREATE TABLE IF NOT EXISTS rate_snapshots (
+    id TEXT PRIMARY KEY NOT NULL,
+    base TEXT NOT NULL,
+    quote TEXT NOT NULL,
+    rate TEXT NOT NULL,
+    source TEXT NOT NULL,
+    observed_at DATETIME NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_rate_snapshots_pair ON rate_snapshots(base, quote, observed_at);
+
+CREATE TABLE IF NOT EXISTS rate_candles (
+    base TEXT NOT NULL,
+    quote TEXT NOT NULL,
+    interval TEXT NOT NULL,
+    bucket_start DATETIME NOT NULL,
+    open TEXT NOT NULL,
+    high TEXT NOT NULL,
+    low TEXT NOT NULL,
+    close TEXT NOT NULL,
+    samples INTEGER NOT NULL,
+    PRIMARY KEY (base, quote, interval, bucket_start)
+);
@@ -0,0 +1,3 @@
+-- Whether a quote was priced from fallback or stale rates instead of live sources.
+ALTER TABLE quotes ADD COLUMN rate_fallback BOOLEAN NOT NULL DEFAULT 0;
+ALTER TABLE quotes ADD COLUMN rate_stale BOOLEAN NOT NULL DEFAULT 0;
@@ -0,0 +1,15 @@
+-- Transfers now move pending -> processing -> sent -> completed through a payout provider.
+ALTER TABLE bank_transfers ADD COLUMN provider_reference TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_bank_transfers_status ON bank_transfers(status, created_at);
+
+CREATE TABLE IF NOT EXISTS bank_transfer_transitions (
+    id TEXT PRIMARY KEY NOT NULL,
+    transfer_id TEXT NOT NULL REFERENCES bank_transfers(id),
+    from_status TEXT,
+    to_status TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    created_at DATETIME NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_bank_transfer_transitions_transfer ON bank_transfer_transitions(transfer_id, created_at);
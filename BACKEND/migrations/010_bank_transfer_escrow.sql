@@ -0,0 +1,4 @@
+-- Escrow and release transactions point at the bank transfer they fund.
+ALTER TABLE transactions ADD COLUMN bank_transfer_id TEXT REFERENCES bank_transfers(id);
+
+CREATE INDEX IF NOT EXISTS idx_transactions_bank_transfer ON transactions(bank_transfer_id);
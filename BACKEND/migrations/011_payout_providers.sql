@@ -0,0 +1,3 @@
+-- Provider a transfer is routed to and its beneficiary details, encrypted.
+ALTER TABLE bank_transfers ADD COLUMN payout_provider TEXT;
+ALTER TABLE bank_transfers ADD COLUMN payout_destination TEXT;
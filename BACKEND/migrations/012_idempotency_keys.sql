@@ -0,0 +1,12 @@
+-- Idempotency-Key replay cache. A row without response_status is still in flight.
+CREATE TABLE IF NOT EXISTS idempotency_keys (
+    key TEXT PRIMARY KEY NOT NULL,
+    fingerprint TEXT NOT NULL,
+    response_status INTEGER,
+    response_content_type TEXT,
+    response_body BLOB,
+    created_at DATETIME NOT NULL,
+    completed_at DATETIME
+);
+
+CREATE INDEX IF NOT EXISTS idx_idempotency_keys_created_at ON idempotency_keys(created_at);
@@ -0,0 +1,19 @@
+-- Outcome of the risk rules for every bank transfer and send, with the rules that fired.
+CREATE TABLE IF NOT EXISTS risk_decisions (
+    id TEXT PRIMARY KEY NOT NULL,
+    operation TEXT NOT NULL,
+    wallet_id TEXT NOT NULL REFERENCES wallets(id),
+    public_key TEXT NOT NULL,
+    reference TEXT,
+    amount_usd TEXT,
+    bank_account_hash TEXT,
+    ip_address TEXT,
+    action TEXT NOT NULL,
+    rules TEXT NOT NULL,
+    created_at DATETIME NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_risk_decisions_wallet ON risk_decisions(wallet_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_risk_decisions_bank_account ON risk_decisions(bank_account_hash, created_at);
+CREATE INDEX IF NOT EXISTS idx_risk_decisions_ip ON risk_decisions(ip_address, created_at);
+CREATE INDEX IF NOT EXISTS idx_risk_decisions_reference ON risk_decisions(reference);
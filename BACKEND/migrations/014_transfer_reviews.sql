@@ -0,0 +1,30 @@
+-- Manual review queue for bank transfers held in the `review` status.
+CREATE TABLE IF NOT EXISTS transfer_reviews (
+    id TEXT PRIMARY KEY NOT NULL,
+    transfer_id TEXT NOT NULL UNIQUE REFERENCES bank_transfers(id),
+    status TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    amount_usd TEXT,
+    requires_second_approval BOOLEAN NOT NULL,
+    assigned_to TEXT,
+    claimed_at DATETIME,
+    first_approved_by TEXT,
+    first_approved_at DATETIME,
+    decided_by TEXT,
+    decided_at DATETIME,
+    sla_due_at DATETIME NOT NULL,
+    created_at DATETIME NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_transfer_reviews_status ON transfer_reviews(status, sla_due_at);
+
+CREATE TABLE IF NOT EXISTS transfer_review_notes (
+    id TEXT PRIMARY KEY NOT NULL,
+    review_id TEXT NOT NULL REFERENCES transfer_reviews(id),
+    reviewer TEXT NOT NULL,
+    action TEXT NOT NULL,
+    note TEXT,
+    created_at DATETIME NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_transfer_review_notes_review ON transfer_review_notes(review_id, created_at);
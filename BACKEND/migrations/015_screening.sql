@@ -0,0 +1,27 @@
+-- Every version of a sanctions or internal watchlist that was loaded.
+CREATE TABLE IF NOT EXISTS screening_lists (
+    id TEXT PRIMARY KEY NOT NULL,
+    source TEXT NOT NULL,
+    path TEXT NOT NULL,
+    version TEXT NOT NULL,
+    entry_count INTEGER NOT NULL,
+    loaded_at DATETIME NOT NULL,
+    UNIQUE (source, version)
+);
+
+-- Outcome of screening a transfer or send, with the matches and list versions used.
+CREATE TABLE IF NOT EXISTS screening_results (
+    id TEXT PRIMARY KEY NOT NULL,
+    operation TEXT NOT NULL,
+    wallet_id TEXT NOT NULL REFERENCES wallets(id),
+    reference TEXT,
+    action TEXT NOT NULL,
+    subject TEXT NOT NULL,
+    matches TEXT NOT NULL,
+    list_ids TEXT NOT NULL,
+    created_at DATETIME NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_screening_results_wallet ON screening_results(wallet_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_screening_results_reference ON screening_results(reference);
+CREATE INDEX IF NOT EXISTS idx_screening_results_action ON screening_results(action, created_at);
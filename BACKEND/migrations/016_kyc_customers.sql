@@ -0,0 +1,13 @@
+-- SEP-12 customers, one per wallet. The SEP-9 fields are stored encrypted.
+CREATE TABLE IF NOT EXISTS kyc_customers (
+    id TEXT PRIMARY KEY NOT NULL,
+    wallet_id TEXT NOT NULL UNIQUE REFERENCES wallets(id),
+    account TEXT NOT NULL UNIQUE,
+    status TEXT NOT NULL,
+    message TEXT,
+    fields TEXT NOT NULL,
+    created_at DATETIME NOT NULL,
+    updated_at DATETIME NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_kyc_customers_status ON kyc_customers(status, updated_at);
@@ -0,0 +1,24 @@
+-- Saved bank accounts per wallet. The full account and holder name are stored encrypted.
+CREATE TABLE IF NOT EXISTS beneficiaries (
+    id TEXT PRIMARY KEY NOT NULL,
+    wallet_id TEXT NOT NULL REFERENCES wallets(id),
+    nickname TEXT NOT NULL,
+    currency TEXT NOT NULL,
+    country TEXT NOT NULL,
+    scheme TEXT NOT NULL,
+    bank_name TEXT,
+    account_masked TEXT NOT NULL,
+    account_hash TEXT NOT NULL,
+    details TEXT NOT NULL,
+    verification_status TEXT NOT NULL DEFAULT 'unverified',
+    verification_note TEXT,
+    available_at DATETIME NOT NULL,
+    created_at DATETIME NOT NULL,
+    updated_at DATETIME NOT NULL,
+    UNIQUE (wallet_id, account_hash)
+);
+
+CREATE INDEX IF NOT EXISTS idx_beneficiaries_wallet ON beneficiaries(wallet_id, created_at);
+
+-- Beneficiary a transfer was sent to, when it used a saved one.
+ALTER TABLE bank_transfers ADD COLUMN beneficiary_id TEXT;
@@ -0,0 +1,53 @@
+-- Provider settlement files and the reconciliation runs comparing them with bank transfers.
+CREATE TABLE IF NOT EXISTS settlement_statements (
+    id TEXT PRIMARY KEY NOT NULL,
+    provider TEXT NOT NULL,
+    filename TEXT,
+    -- SHA-256 of the file; the same file is only imported once per provider.
+    checksum TEXT NOT NULL,
+    row_count INTEGER NOT NULL,
+    imported_at DATETIME NOT NULL,
+    UNIQUE (provider, checksum)
+);
+
+CREATE TABLE IF NOT EXISTS settlement_rows (
+    id TEXT PRIMARY KEY NOT NULL,
+    statement_id TEXT NOT NULL REFERENCES settlement_statements(id),
+    provider TEXT NOT NULL,
+    line INTEGER NOT NULL,
+    reference TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    currency TEXT NOT NULL,
+    settled_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_settlement_rows_reference ON settlement_rows(provider, reference);
+
+CREATE TABLE IF NOT EXISTS reconciliation_runs (
+    id TEXT PRIMARY KEY NOT NULL,
+    provider TEXT NOT NULL,
+    -- Statement whose import started the run, if any.
+    statement_id TEXT REFERENCES settlement_statements(id),
+    matched INTEGER NOT NULL,
+    missing INTEGER NOT NULL,
+    duplicate INTEGER NOT NULL,
+    amount_mismatch INTEGER NOT NULL,
+    unmatched INTEGER NOT NULL,
+    created_at DATETIME NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_reconciliation_runs_provider ON reconciliation_runs(provider, created_at);
+
+CREATE TABLE IF NOT EXISTS reconciliation_items (
+    id TEXT PRIMARY KEY NOT NULL,
+    run_id TEXT NOT NULL REFERENCES reconciliation_runs(id),
+    status TEXT NOT NULL,
+    transfer_id TEXT,
+    reference TEXT,
+    expected_amount TEXT,
+    settled_amount TEXT,
+    currency TEXT,
+    detail TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_reconciliation_items_run ON reconciliation_items(run_id, status);
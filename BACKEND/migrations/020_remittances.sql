@@ -0,0 +1,37 @@
+-- USDC -> fiat remittances run as a saga over a quote, a bank transfer and a
+-- DEX conversion, with one row per step taken.
+CREATE TABLE IF NOT EXISTS remittances (
+    id TEXT PRIMARY KEY NOT NULL,
+    wallet_id TEXT NOT NULL REFERENCES wallets(id),
+    public_key TEXT NOT NULL,
+    amount_fiat TEXT NOT NULL,
+    currency TEXT NOT NULL,
+    source_asset TEXT NOT NULL,
+    -- Escrowed from the wallet, fee included; known once quoted.
+    source_amount TEXT,
+    -- Stellar asset the escrow is converted into on the DEX.
+    dex_asset TEXT NOT NULL,
+    dex_amount TEXT,
+    quote_id TEXT,
+    transfer_id TEXT REFERENCES bank_transfers(id),
+    status TEXT NOT NULL,
+    failure_reason TEXT,
+    created_at DATETIME NOT NULL,
+    updated_at DATETIME NOT NULL,
+    completed_at DATETIME
+);
+
+CREATE INDEX IF NOT EXISTS idx_remittances_wallet ON remittances(wallet_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_remittances_status ON remittances(status, updated_at);
+
+CREATE TABLE IF NOT EXISTS remittance_steps (
+    id TEXT PRIMARY KEY NOT NULL,
+    remittance_id TEXT NOT NULL REFERENCES remittances(id),
+    step TEXT NOT NULL,
+    status TEXT NOT NULL,
+    detail TEXT NOT NULL,
+    tx_hash TEXT,
+    created_at DATETIME NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_remittance_steps_remittance ON remittance_steps(remittance_id, created_at);
@@ -0,0 +1,2 @@
+-- Wallet a quote was issued to; only that wallet may redeem it.
+ALTER TABLE quotes ADD COLUMN requested_by TEXT;
@@ -0,0 +1,15 @@
+-- The market legs a quote was priced from (sell asset -> USD, then USD -> buy
+-- asset), kept as rows so the quote's provenance does not have to be parsed
+-- back out of `quotes.rate_source`.
+CREATE TABLE IF NOT EXISTS quote_legs (
+    quote_id TEXT NOT NULL REFERENCES quotes(id),
+    position INTEGER NOT NULL,
+    from_asset TEXT NOT NULL,
+    to_asset TEXT NOT NULL,
+    rate TEXT NOT NULL,
+    source TEXT NOT NULL,
+    fetched_at DATETIME NOT NULL,
+    fallback BOOLEAN NOT NULL DEFAULT 0,
+    stale BOOLEAN NOT NULL DEFAULT 0,
+    PRIMARY KEY (quote_id, position)
+);
@@ -0,0 +1,5 @@
+-- Platform fee charged on a transaction, and whether the row records a
+-- submission that never reached the network (its tx_hash is made up).
+ALTER TABLE transactions ADD COLUMN fee_amount TEXT;
+ALTER TABLE transactions ADD COLUMN fee_asset TEXT;
+ALTER TABLE transactions ADD COLUMN simulated BOOLEAN NOT NULL DEFAULT 0;
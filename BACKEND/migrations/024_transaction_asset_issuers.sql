@@ -0,0 +1,4 @@
+-- Issuers of the assets a transaction moved; NULL for XLM. The code alone does
+-- not identify a credit asset, anyone can issue their own USDC.
+ALTER TABLE transactions ADD COLUMN asset_issuer TEXT;
+ALTER TABLE transactions ADD COLUMN dest_asset_issuer TEXT;
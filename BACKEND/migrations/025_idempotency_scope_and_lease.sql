@@ -0,0 +1,19 @@
+-- Keys are scoped to the route and the caller, and an in-flight key holds a
+-- lease: once locked_until passes, a retry may take the key over.
+-- Stored replays are short lived, so keys from before the scope existed are dropped.
+DROP TABLE IF EXISTS idempotency_keys;
+
+CREATE TABLE idempotency_keys (
+    scope TEXT NOT NULL,
+    key TEXT NOT NULL,
+    fingerprint TEXT NOT NULL,
+    response_status INTEGER,
+    response_content_type TEXT,
+    response_body BLOB,
+    created_at DATETIME NOT NULL,
+    locked_until DATETIME NOT NULL,
+    completed_at DATETIME,
+    PRIMARY KEY (scope, key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_idempotency_keys_created_at ON idempotency_keys(created_at);
@@ -0,0 +1,3 @@
+-- Deleting a beneficiary only hides it, so its verification state and
+-- cooling-off still apply if the account is used again.
+ALTER TABLE beneficiaries ADD COLUMN deleted_at DATETIME;
@@ -0,0 +1,2 @@
+-- Reversed transfers that a settlement file still reports as paid.
+ALTER TABLE reconciliation_runs ADD COLUMN reversed_settled INTEGER NOT NULL DEFAULT 0;
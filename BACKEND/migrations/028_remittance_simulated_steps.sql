@@ -0,0 +1,2 @@
+-- DEX swaps are not submitted to the network yet; their steps say so.
+ALTER TABLE remittance_steps ADD COLUMN simulated BOOLEAN NOT NULL DEFAULT 0;
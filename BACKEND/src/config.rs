@@ -2,6 +2,9 @@ use config::{Config as ConfigBuilder, ConfigError, Environment};
 use serde::Deserialize;
 use std::env;
 
+use crate::modules::models::amount::Amount;
+use crate::modules::models::risk::RiskAction;
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct Config {
     pub server: ServerConfig,
@@ -10,6 +13,25 @@ pub struct Config {
     pub aa: AccountAbstractionConfig,
     pub reputation: ReputationConfig,
     pub external_apis: ExternalApisConfig,
+    pub oracle: OracleConfig,
+    pub quotes: QuotesConfig,
+    pub bank: BankConfig,
+    pub convert: ConvertConfig,
+    pub rate_history: RateHistoryConfig,
+    pub rate_stream: RateStreamConfig,
+    pub idempotency: IdempotencyConfig,
+    pub review: ReviewConfig,
+    pub screening: ScreeningConfig,
+    pub kyc: KycConfig,
+    pub beneficiaries: BeneficiariesConfig,
+    pub webhooks: WebhooksConfig,
+    pub remittances: RemittancesConfig,
+    #[serde(default)]
+    pub tokens: TokensConfig,
+    #[serde(default)]
+    pub security: SecurityConfig,
+    #[serde(default)]
+    pub risk: RiskConfig,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -40,15 +62,253 @@ pub struct AccountAbstractionConfig {
 #[derive(Debug, Clone, Deserialize)]
 pub struct ReputationConfig {
     pub threshold: u8,
+    /// Points taken off the score per reversed bank transfer.
+    pub reversal_penalty: u8,
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct ExternalApisConfig {
     pub circle_api_key: Option<String>,
+    pub circle_api_url: String,
     pub stripe_secret_key: Option<String>,
+    pub stripe_api_url: String,
+    /// Stripe Treasury financial account ACH payouts are sent from.
+    pub stripe_financial_account: Option<String>,
+    pub spei_api_url: Option<String>,
+    pub spei_api_key: Option<String>,
     pub coingecko_api_url: String,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct OracleConfig {
+    /// `live` queries the configured sources; `offline` serves scripted local rates only.
+    pub mode: String,
+    pub offline_rates_file: Option<String>,
+    /// Comma separated list of enabled price sources: coingecko, stellar_dex, static.
+    pub sources: String,
+    /// Maximum relative distance from the median before a sample is dropped.
+    pub max_deviation: f64,
+    /// Age after which a cached live rate served as fallback is flagged stale.
+    pub stale_after_seconds: i64,
+    pub static_rates_file: Option<String>,
+    pub usdc_issuer: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct QuotesConfig {
+    pub ttl_seconds: i64,
+    /// Fee charged on the sell amount, in basis points.
+    pub fee_bps: u32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BankConfig {
+    /// Refuse quotes priced from fallback or stale rates for bank transfers.
+    pub reject_fallback_rates: bool,
+    /// How often the payout worker advances pending and in-flight transfers.
+    pub worker_interval_seconds: u64,
+    /// Time the mock payout provider takes to settle a transfer.
+    pub mock_settle_seconds: u64,
+    /// Stellar account escrowed transfer funds are sent to. Unset, funds are
+    /// locked in the wallet instead.
+    pub settlement_account: Option<String>,
+    /// Comma separated payout providers in priority order (spei, circle, stripe, mock).
+    pub payout_providers: String,
+    /// Local stand-in server that drives the mock provider.
+    pub mock_payout_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConvertConfig {
+    /// Comma separated ISO 4217 codes conversions can target.
+    pub fiat_currencies: String,
+    pub default_fiat: String,
+    /// Upper bound for the slippage a wallet conversion may accept, in basis points.
+    pub max_slippage_bps: u32,
+    /// Record wallet conversions as simulated instead of refusing them; the
+    /// path payment is never submitted to the network.
+    pub simulate_wallet_conversions: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RateHistoryConfig {
+    /// How often raw snapshots are rolled up into OHLC candles.
+    pub downsample_interval_seconds: u64,
+    /// Raw snapshots older than this are deleted once downsampled.
+    pub snapshot_retention_days: i64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RateStreamConfig {
+    /// How often every pair with at least one WebSocket subscriber is refreshed.
+    pub poll_interval_seconds: u64,
+    pub max_pairs_per_client: usize,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReviewConfig {
+    /// Time a held transfer's review should be decided within.
+    pub sla_minutes: i64,
+    /// Reviews for at least this many USD need two different approvers.
+    pub second_approval_usd: Option<Amount>,
+    /// Transfers scoring less than this above the reputation threshold are held for review.
+    pub reputation_margin: u8,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScreeningConfig {
+    /// OFAC SDN-style list, `.xml` or CSV; not screened when unset.
+    pub sdn_file: Option<String>,
+    /// JSON blocklist of Stellar addresses, bank accounts and names.
+    pub internal_list_file: Option<String>,
+    /// Jaro-Winkler similarity, 0-1, from which a name counts as a fuzzy match.
+    pub fuzzy_threshold: f64,
+    pub exact_match_action: RiskAction,
+    pub fuzzy_match_action: RiskAction,
+    /// How often the list files are checked for a new version.
+    pub reload_interval_seconds: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct KycConfig {
+    /// Most a wallet without an ACCEPTED KYC customer can transfer in USD per
+    /// `limit_window_hours`; unlimited when unset.
+    pub unverified_limit_usd: Option<Amount>,
+    /// Most an ACCEPTED customer can transfer in USD per `limit_window_hours`;
+    /// unlimited when unset.
+    pub accepted_limit_usd: Option<Amount>,
+    /// Rolling window the limits apply over.
+    pub limit_window_hours: i64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BeneficiariesConfig {
+    /// Hours before a new, unverified beneficiary can receive transfers.
+    pub cooling_off_hours: i64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WebhooksConfig {
+    /// How often due deliveries are sent.
+    pub worker_interval_seconds: u64,
+    /// Attempts before a delivery goes to the dead-letter queue.
+    pub max_attempts: u32,
+    /// Wait after the first failure; doubled after each further one.
+    pub initial_backoff_seconds: i64,
+    pub max_backoff_seconds: i64,
+    pub timeout_seconds: u64,
+    /// Accept plain `http://` endpoints, for local development.
+    pub allow_http: bool,
+    /// Accept endpoints on loopback, private or link-local addresses, for
+    /// local development.
+    pub allow_private_hosts: bool,
+    /// Deliveries sent at the same time on each worker pass.
+    pub concurrency: usize,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RemittancesConfig {
+    /// How often in-flight remittances are advanced.
+    pub worker_interval_seconds: u64,
+    /// Registry symbol remittances are funded from.
+    pub source_asset: String,
+    /// Fiat currency paid out.
+    pub currency: String,
+    /// Registry symbol of the Stellar asset the escrow is converted into on
+    /// the DEX. Remittances are disabled while unset.
+    pub dex_asset: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdempotencyConfig {
+    /// How long a stored response is replayed for its Idempotency-Key.
+    pub ttl_hours: i64,
+    /// How long an in-flight key is held before a retry may take it over;
+    /// renewed while the request is still running.
+    pub lease_seconds: i64,
+    pub cleanup_interval_seconds: u64,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TokensConfig {
+    /// JSON list of registry entries; the built-in XLM/USDC/ETH/BTC set is used when unset.
+    pub registry_file: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SecurityConfig {
+    /// Hex encoded 32-byte AES-256-GCM key for sensitive columns. Required
+    /// unless `allow_ephemeral_key` is set.
+    pub encryption_key: Option<String>,
+    /// Development only: use a random per-process key when `encryption_key`
+    /// is unset, so stored values do not survive a restart.
+    pub allow_ephemeral_key: bool,
+    /// Operators allowed on `/admin`, as comma separated `name:token` pairs.
+    /// Every admin route answers 401 while unset.
+    pub admin_api_keys: Option<String>,
+}
+
+impl SecurityConfig {
+    /// `(name, token)` of each configured operator.
+    pub fn admin_keys(&self) -> Result<Vec<(String, String)>, String> {
+        let Some(keys) = &self.admin_api_keys else {
+            return Ok(Vec::new());
+        };
+        keys.split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let (name, token) = entry
+                    .split_once(':')
+                    .map(|(name, token)| (name.trim(), token.trim()))
+                    .filter(|(name, token)| !name.is_empty() && token.len() >= 16)
+                    .ok_or_else(|| {
+                        "Admin API keys must be name:token pairs with tokens of at least 16 characters".to_string()
+                    })?;
+                Ok((name.to_string(), token.to_string()))
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RiskConfig {
+    /// JSON rule set for transfers and sends; the built-in rules are used when unset.
+    pub rules_file: Option<String>,
+    /// Take the client IP from `X-Forwarded-For`; only safe behind a proxy that sets it.
+    pub trust_forwarded_for: bool,
+}
+
+impl OracleConfig {
+    pub fn enabled_sources(&self) -> Vec<String> {
+        self.sources
+            .split(',')
+            .map(|s| s.trim().to_lowercase())
+            .filter(|s| !s.is_empty())
+            .collect()
+    }
+}
+
+impl BankConfig {
+    pub fn enabled_payout_providers(&self) -> Vec<String> {
+        self.payout_providers
+            .split(',')
+            .map(|s| s.trim().to_lowercase())
+            .filter(|s| !s.is_empty())
+            .collect()
+    }
+}
+
+impl ConvertConfig {
+    pub fn supported_fiats(&self) -> Vec<String> {
+        self.fiat_currencies
+            .split(',')
+            .map(|s| s.trim().to_uppercase())
+            .filter(|s| !s.is_empty())
+            .collect()
+    }
+}
+
 impl Config {
     pub fn load() -> Result<Self, ConfigError> {
         dotenvy::dotenv().ok();
@@ -64,7 +324,53 @@ impl Config {
             .set_default("aa.bundler_url", "http://localhost:4100")?
             .set_default("aa.signer_memory", true)?
             .set_default("reputation.threshold", 50)?
+            .set_default("reputation.reversal_penalty", 15)?
             .set_default("external_apis.coingecko_api_url", "https://api.coingecko.com/api/v3")?
+            .set_default("external_apis.circle_api_url", "https://api-sandbox.circle.com")?
+            .set_default("external_apis.stripe_api_url", "https://api.stripe.com")?
+            .set_default("oracle.mode", "live")?
+            .set_default("oracle.sources", "coingecko,stellar_dex,static")?
+            .set_default("oracle.max_deviation", 0.05)?
+            .set_default("oracle.stale_after_seconds", 300)?
+            .set_default("oracle.usdc_issuer", "GBBD47IF6LWK7P7MDEVSCWR7DPUWV3NY3DTQEVFL4NAT4AQH3ZLLFLA5")?
+            .set_default("quotes.ttl_seconds", 60)?
+            .set_default("quotes.fee_bps", 50)?
+            .set_default("bank.reject_fallback_rates", false)?
+            .set_default("bank.worker_interval_seconds", 5)?
+            .set_default("bank.mock_settle_seconds", 10)?
+            .set_default("bank.payout_providers", "mock")?
+            .set_default("convert.fiat_currencies", "MXN,USD,EUR,BRL,ARS")?
+            .set_default("convert.default_fiat", "MXN")?
+            .set_default("convert.max_slippage_bps", 100)?
+            .set_default("convert.simulate_wallet_conversions", false)?
+            .set_default("rate_history.downsample_interval_seconds", 60)?
+            .set_default("rate_history.snapshot_retention_days", 90)?
+            .set_default("rate_stream.poll_interval_seconds", 10)?
+            .set_default("rate_stream.max_pairs_per_client", 20)?
+            .set_default("idempotency.ttl_hours", 24)?
+            .set_default("idempotency.lease_seconds", 60)?
+            .set_default("idempotency.cleanup_interval_seconds", 3600)?
+            .set_default("review.sla_minutes", 240)?
+            .set_default("review.reputation_margin", 0)?
+            .set_default("screening.fuzzy_threshold", 0.9)?
+            .set_default("screening.exact_match_action", "block")?
+            .set_default("screening.fuzzy_match_action", "review")?
+            .set_default("screening.reload_interval_seconds", 300)?
+            .set_default("kyc.unverified_limit_usd", "1000")?
+            .set_default("kyc.limit_window_hours", 24)?
+            .set_default("beneficiaries.cooling_off_hours", 24)?
+            .set_default("webhooks.worker_interval_seconds", 5)?
+            .set_default("webhooks.max_attempts", 10)?
+            .set_default("webhooks.initial_backoff_seconds", 30)?
+            .set_default("webhooks.max_backoff_seconds", 21600)?
+            .set_default("webhooks.timeout_seconds", 10)?
+            .set_default("webhooks.allow_http", false)?
+            .set_default("webhooks.allow_private_hosts", false)?
+            .set_default("webhooks.concurrency", 8)?
+            .set_default("security.allow_ephemeral_key", false)?
+            .set_default("remittances.worker_interval_seconds", 10)?
+            .set_default("remittances.source_asset", "usdc")?
+            .set_default("remittances.currency", "MXN")?
             .add_source(Environment::default().try_parsing(true))
             .build()?;
 
@@ -84,6 +390,165 @@ impl Config {
             return Err("Reputation threshold must be between 0-100".to_string());
         }
 
+        if self.reputation.reversal_penalty > 100 {
+            return Err("Reputation reversal penalty must be between 0-100".to_string());
+        }
+
+        if !matches!(self.oracle.mode.as_str(), "live" | "offline") {
+            return Err(format!("Unknown oracle mode: {} (use live or offline)", self.oracle.mode));
+        }
+
+        if self.oracle.enabled_sources().is_empty() {
+            return Err("At least one price oracle source must be enabled".to_string());
+        }
+
+        if self.oracle.max_deviation <= 0.0 {
+            return Err("Oracle max deviation must be positive".to_string());
+        }
+
+        if self.oracle.stale_after_seconds <= 0 {
+            return Err("Oracle staleness limit must be positive".to_string());
+        }
+
+        if self.quotes.ttl_seconds <= 0 {
+            return Err("Quote TTL must be positive".to_string());
+        }
+
+        if self.quotes.fee_bps >= 10_000 {
+            return Err("Quote fee must be below 10000 bps".to_string());
+        }
+
+        let fiats = self.convert.supported_fiats();
+        if let Some(code) = fiats.iter().find(|c| c.len() != 3 || !c.chars().all(|ch| ch.is_ascii_alphabetic())) {
+            return Err(format!("Invalid ISO 4217 currency code: {}", code));
+        }
+
+        if !fiats.contains(&self.convert.default_fiat.to_uppercase()) {
+            return Err("Default fiat currency must be one of the supported fiat currencies".to_string());
+        }
+
+        if self.convert.max_slippage_bps >= 10_000 {
+            return Err("Max slippage must be below 10000 bps".to_string());
+        }
+
+        if let Some(account) = &self.bank.settlement_account {
+            if !crate::utils::crypto::validate_stellar_address(account).unwrap_or(false) {
+                return Err(format!("Invalid settlement account: {}", account));
+            }
+        }
+
+        let payout_providers = self.bank.enabled_payout_providers();
+        if payout_providers.is_empty() {
+            return Err("At least one payout provider must be enabled".to_string());
+        }
+
+        for provider in &payout_providers {
+            let configured = match provider.as_str() {
+                "mock" => true,
+                "spei" => self.external_apis.spei_api_url.is_some() && self.external_apis.spei_api_key.is_some(),
+                "circle" => self.external_apis.circle_api_key.is_some(),
+                "stripe" => {
+                    self.external_apis.stripe_secret_key.is_some()
+                        && self.external_apis.stripe_financial_account.is_some()
+                }
+                other => return Err(format!("Unknown payout provider: {}", other)),
+            };
+            if !configured {
+                return Err(format!("Payout provider {} is enabled but its credentials are missing", provider));
+            }
+        }
+
+        match &self.security.encryption_key {
+            Some(key) if hex::decode(key.trim()).map(|k| k.len() != 32).unwrap_or(true) => {
+                return Err("Encryption key must be 32 bytes, hex encoded".to_string());
+            }
+            Some(_) => {}
+            None if !self.security.allow_ephemeral_key => {
+                return Err(
+                    "SECURITY_ENCRYPTION_KEY is required (set SECURITY_ALLOW_EPHEMERAL_KEY=true for local development only)"
+                        .to_string(),
+                );
+            }
+            None => {}
+        }
+        self.security.admin_keys()?;
+
+        if self.bank.worker_interval_seconds == 0 {
+            return Err("Bank worker interval must be positive".to_string());
+        }
+
+        if self.rate_history.downsample_interval_seconds == 0 {
+            return Err("Rate history downsample interval must be positive".to_string());
+        }
+
+        if self.rate_history.snapshot_retention_days <= 0 {
+            return Err("Rate snapshot retention must be positive".to_string());
+        }
+
+        if self.rate_stream.poll_interval_seconds == 0 {
+            return Err("Rate stream poll interval must be positive".to_string());
+        }
+
+        if self.rate_stream.max_pairs_per_client == 0 {
+            return Err("Rate stream must allow at least one pair per client".to_string());
+        }
+
+        if self.review.sla_minutes <= 0 {
+            return Err("Review SLA must be positive".to_string());
+        }
+
+        if !(self.screening.fuzzy_threshold > 0.0 && self.screening.fuzzy_threshold <= 1.0) {
+            return Err("Screening fuzzy threshold must be between 0 and 1".to_string());
+        }
+
+        if self.screening.exact_match_action == RiskAction::Allow
+            || self.screening.fuzzy_match_action == RiskAction::Allow
+        {
+            return Err("Screening match actions must be review or block".to_string());
+        }
+
+        if self.screening.reload_interval_seconds == 0 {
+            return Err("Screening reload interval must be positive".to_string());
+        }
+
+        if self.beneficiaries.cooling_off_hours < 0 {
+            return Err("Beneficiary cooling-off period must not be negative".to_string());
+        }
+
+        if self.webhooks.worker_interval_seconds == 0 || self.webhooks.timeout_seconds == 0 {
+            return Err("Webhook worker interval and timeout must be positive".to_string());
+        }
+
+        if self.webhooks.concurrency == 0 {
+            return Err("Webhook concurrency must be positive".to_string());
+        }
+
+        if self.webhooks.max_attempts == 0 {
+            return Err("Webhooks need at least one delivery attempt".to_string());
+        }
+
+        if self.webhooks.initial_backoff_seconds <= 0
+            || self.webhooks.max_backoff_seconds < self.webhooks.initial_backoff_seconds
+        {
+            return Err("Webhook backoff must be positive, with the maximum at least the initial one".to_string());
+        }
+
+        if self.remittances.worker_interval_seconds == 0 {
+            return Err("Remittance worker interval must be positive".to_string());
+        }
+
+        if self.kyc.limit_window_hours <= 0 {
+            return Err("KYC limit window must be positive".to_string());
+        }
+
+        if self.idempotency.ttl_hours <= 0 {
+            return Err("Idempotency key TTL must be positive".to_string());
+        }
+
+        if self.idempotency.lease_seconds <= 0 || self.idempotency.cleanup_interval_seconds == 0 {
+            return Err("Idempotency key lease and cleanup interval must be positive".to_string());
+        }
+
         Ok(())
     }
 }
\ No newline at end of file
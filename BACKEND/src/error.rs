@@ -3,9 +3,40 @@ use axum::{
     response::{IntoResponse, Response},
     Json,
 };
+use serde::Serialize;
 use serde_json::json;
 use thiserror::Error;
 
+use crate::modules::models::amount::AmountError;
+
+/// A problem with one request field, returned under `error.fields`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldError {
+    pub field: String,
+    pub code: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: &str, code: &str, message: impl Into<String>) -> Self {
+        Self {
+            field: field.to_string(),
+            code: code.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for FieldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+fn describe_fields(fields: &[FieldError]) -> String {
+    fields.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
+}
+
 #[derive(Error, Debug)]
 pub enum AppError {
     #[error("Wallet not found: {0}")]
@@ -23,6 +54,90 @@ pub enum AppError {
     #[error("Invalid request: {0}")]
     BadRequest(String),
 
+    #[error("Invalid fields: {}", describe_fields(.0))]
+    Validation(Vec<FieldError>),
+
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+
+    #[error("Unsupported currency: {0}")]
+    UnsupportedCurrency(String),
+
+    #[error("Unsupported asset: {0}")]
+    UnsupportedAsset(String),
+
+    #[error("Quote not found: {0}")]
+    QuoteNotFound(String),
+
+    #[error("Quote unavailable: {0}")]
+    QuoteUnavailable(String),
+
+    #[error("Rate unavailable: {0}")]
+    RateUnavailable(String),
+
+    #[error("Bank transfer not found: {0}")]
+    TransferNotFound(String),
+
+    #[error("Invalid transfer transition: {0}")]
+    InvalidTransition(String),
+
+    #[error("A request with Idempotency-Key {0} is still in progress")]
+    IdempotencyKeyInUse(String),
+
+    #[error("Idempotency-Key {0} was already used for a different request")]
+    IdempotencyKeyReused(String),
+
+    #[error("Blocked by risk controls (decision {0})")]
+    RiskBlocked(String),
+
+    #[error("Blocked by compliance screening (result {0})")]
+    ScreeningBlocked(String),
+
+    #[error("Review not found: {0}")]
+    ReviewNotFound(String),
+
+    #[error("Review conflict: {0}")]
+    ReviewConflict(String),
+
+    #[error("KYC customer not found: {0}")]
+    CustomerNotFound(String),
+
+    #[error("KYC conflict: {0}")]
+    KycConflict(String),
+
+    #[error("Transfers above {limit} USD per {window_hours} hours need an ACCEPTED KYC customer (current: {status})")]
+    KycLimitExceeded { status: String, limit: String, window_hours: i64 },
+
+    #[error("KYC was rejected for wallet {0}")]
+    KycRejected(String),
+
+    #[error("Beneficiary not found: {0}")]
+    BeneficiaryNotFound(String),
+
+    #[error("Account {0} is already saved as a beneficiary")]
+    BeneficiaryExists(String),
+
+    #[error("Beneficiary unavailable: {0}")]
+    BeneficiaryUnavailable(String),
+
+    #[error("Beneficiary conflict: {0}")]
+    BeneficiaryConflict(String),
+
+    #[error("Webhook subscription not found: {0}")]
+    WebhookNotFound(String),
+
+    #[error("Webhook event not found: {0}")]
+    WebhookEventNotFound(String),
+
+    #[error("This settlement file was already imported as statement {0}")]
+    StatementExists(String),
+
+    #[error("Reconciliation run not found: {0}")]
+    ReconciliationRunNotFound(String),
+
+    #[error("Remittance not found: {0}")]
+    RemittanceNotFound(String),
+
     #[error("Account abstraction error: {0}")]
     AccountAbstractionError(String),
 
@@ -63,6 +178,90 @@ impl IntoResponse for AppError {
             AppError::BadRequest(_) => {
                 (StatusCode::BAD_REQUEST, "BAD_REQUEST", self.to_string())
             }
+            AppError::Validation(_) => {
+                (StatusCode::UNPROCESSABLE_ENTITY, "VALIDATION_ERROR", self.to_string())
+            }
+            AppError::Unauthorized(_) => {
+                (StatusCode::UNAUTHORIZED, "UNAUTHORIZED", self.to_string())
+            }
+            AppError::UnsupportedCurrency(_) => {
+                (StatusCode::BAD_REQUEST, "UNSUPPORTED_CURRENCY", self.to_string())
+            }
+            AppError::UnsupportedAsset(_) => {
+                (StatusCode::BAD_REQUEST, "UNSUPPORTED_ASSET", self.to_string())
+            }
+            AppError::QuoteNotFound(_) => {
+                (StatusCode::NOT_FOUND, "QUOTE_NOT_FOUND", self.to_string())
+            }
+            AppError::QuoteUnavailable(_) => {
+                (StatusCode::CONFLICT, "QUOTE_UNAVAILABLE", self.to_string())
+            }
+            AppError::RateUnavailable(_) => {
+                (StatusCode::SERVICE_UNAVAILABLE, "RATE_UNAVAILABLE", self.to_string())
+            }
+            AppError::TransferNotFound(_) => {
+                (StatusCode::NOT_FOUND, "TRANSFER_NOT_FOUND", self.to_string())
+            }
+            AppError::InvalidTransition(_) => {
+                (StatusCode::CONFLICT, "INVALID_TRANSITION", self.to_string())
+            }
+            AppError::IdempotencyKeyInUse(_) => {
+                (StatusCode::CONFLICT, "IDEMPOTENCY_KEY_IN_USE", self.to_string())
+            }
+            AppError::IdempotencyKeyReused(_) => {
+                (StatusCode::UNPROCESSABLE_ENTITY, "IDEMPOTENCY_KEY_REUSED", self.to_string())
+            }
+            AppError::RiskBlocked(_) => {
+                (StatusCode::FORBIDDEN, "RISK_BLOCKED", self.to_string())
+            }
+            AppError::ScreeningBlocked(_) => {
+                (StatusCode::FORBIDDEN, "SCREENING_BLOCKED", self.to_string())
+            }
+            AppError::ReviewNotFound(_) => {
+                (StatusCode::NOT_FOUND, "REVIEW_NOT_FOUND", self.to_string())
+            }
+            AppError::ReviewConflict(_) => {
+                (StatusCode::CONFLICT, "REVIEW_CONFLICT", self.to_string())
+            }
+            AppError::CustomerNotFound(_) => {
+                (StatusCode::NOT_FOUND, "CUSTOMER_NOT_FOUND", self.to_string())
+            }
+            AppError::KycConflict(_) => {
+                (StatusCode::CONFLICT, "KYC_CONFLICT", self.to_string())
+            }
+            AppError::KycLimitExceeded { .. } => {
+                (StatusCode::FORBIDDEN, "KYC_LIMIT_EXCEEDED", self.to_string())
+            }
+            AppError::KycRejected(_) => {
+                (StatusCode::FORBIDDEN, "KYC_REJECTED", self.to_string())
+            }
+            AppError::BeneficiaryNotFound(_) => {
+                (StatusCode::NOT_FOUND, "BENEFICIARY_NOT_FOUND", self.to_string())
+            }
+            AppError::BeneficiaryExists(_) => {
+                (StatusCode::CONFLICT, "BENEFICIARY_EXISTS", self.to_string())
+            }
+            AppError::BeneficiaryUnavailable(_) => {
+                (StatusCode::CONFLICT, "BENEFICIARY_UNAVAILABLE", self.to_string())
+            }
+            AppError::BeneficiaryConflict(_) => {
+                (StatusCode::CONFLICT, "BENEFICIARY_CONFLICT", self.to_string())
+            }
+            AppError::WebhookNotFound(_) => {
+                (StatusCode::NOT_FOUND, "WEBHOOK_NOT_FOUND", self.to_string())
+            }
+            AppError::WebhookEventNotFound(_) => {
+                (StatusCode::NOT_FOUND, "WEBHOOK_EVENT_NOT_FOUND", self.to_string())
+            }
+            AppError::StatementExists(_) => {
+                (StatusCode::CONFLICT, "STATEMENT_EXISTS", self.to_string())
+            }
+            AppError::ReconciliationRunNotFound(_) => {
+                (StatusCode::NOT_FOUND, "RECONCILIATION_RUN_NOT_FOUND", self.to_string())
+            }
+            AppError::RemittanceNotFound(_) => {
+                (StatusCode::NOT_FOUND, "REMITTANCE_NOT_FOUND", self.to_string())
+            }
             AppError::AccountAbstractionError(_) => {
                 (StatusCode::BAD_REQUEST, "AA_ERROR", self.to_string())
             }
@@ -86,12 +285,15 @@ impl IntoResponse for AppError {
             }
         };
 
-        let body = Json(json!({
-            "error": {
-                "code": error_code,
-                "message": message,
-            }
-        }));
+        let mut error = json!({
+            "code": error_code,
+            "message": message,
+        });
+        if let AppError::Validation(fields) = &self {
+            error["fields"] = json!(fields);
+        }
+
+        let body = Json(json!({ "error": error }));
 
         (status, body).into_response()
     }
@@ -115,6 +317,12 @@ impl From<config::ConfigError> for AppError {
     }
 }
 
+impl From<AmountError> for AppError {
+    fn from(err: AmountError) -> Self {
+        AppError::BadRequest(err.to_string())
+    }
+}
+
 impl From<anyhow::Error> for AppError {
     fn from(err: anyhow::Error) -> Self {
         AppError::InternalError(err.to_string())
@@ -47,10 +47,32 @@ async fn main() -> Result<()> {
 
     tracing::info!("Database migrations completed");
 
-    let state = AppState::new(config.clone(), db_pool).await;
+    let state = AppState::new(config.clone(), db_pool)
+        .await
+        .context("Failed to initialize application state")?;
     
     tracing::info!("Application state initialized");
 
+    state.rate_history_service.clone().spawn_downsampler(std::time::Duration::from_secs(
+        config.rate_history.downsample_interval_seconds,
+    ));
+    state.rate_stream_service.clone().spawn_poller();
+    state.screening_service.clone().spawn_reloader(std::time::Duration::from_secs(
+        config.screening.reload_interval_seconds,
+    ));
+    state.bank_service.clone().spawn_worker(std::time::Duration::from_secs(
+        config.bank.worker_interval_seconds,
+    ));
+    state.webhook_service.clone().spawn_worker(std::time::Duration::from_secs(
+        config.webhooks.worker_interval_seconds,
+    ));
+    state.remittance_service.clone().spawn_worker(std::time::Duration::from_secs(
+        config.remittances.worker_interval_seconds,
+    ));
+    state.idempotency_service.clone().spawn_cleaner(std::time::Duration::from_secs(
+        config.idempotency.cleanup_interval_seconds,
+    ));
+
     let app = routes::create_router(state);
 
     let addr = format!("{}:{}", config.server.host, config.server.port);
@@ -62,7 +84,7 @@ async fn main() -> Result<()> {
     tracing::info!("API available at http://{}/api", addr);
     tracing::info!("Health check at http://{}/api/health", addr);
 
-    axum::serve(listener, app)
+    axum::serve(listener, app.into_make_service_with_connect_info::<std::net::SocketAddr>())
         .await
         .context("Server error")?;
 
@@ -0,0 +1,86 @@
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Request, State},
+    http::{header, request::Parts},
+    middleware::Next,
+    response::Response,
+};
+use sha2::{Digest, Sha256};
+
+use crate::error::AppError;
+use crate::state::AppState;
+
+/// Operator that authenticated an `/admin` request, by the name its key was
+/// configured under. Reviewers and other admin actors are recorded as this
+/// name, never as something taken from the request body.
+#[derive(Debug, Clone)]
+pub struct AdminPrincipal(pub String);
+
+/// Requires `Authorization: Bearer <token>` with one of the tokens in
+/// `SECURITY_ADMIN_API_KEYS`. Handlers read the operator with the
+/// [`AdminPrincipal`] extractor.
+pub async fn admin_auth(State(state): State<AppState>, mut req: Request, next: Next) -> Result<Response, AppError> {
+    let token = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+        .ok_or_else(|| AppError::Unauthorized("Missing admin bearer token".to_string()))?;
+
+    let keys = state.config.security.admin_keys().map_err(AppError::ConfigError)?;
+    let name = authenticate(&keys, token)
+        .ok_or_else(|| AppError::Unauthorized("Unknown admin token".to_string()))?;
+
+    req.extensions_mut().insert(AdminPrincipal(name));
+    Ok(next.run(req).await)
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for AdminPrincipal {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<AdminPrincipal>()
+            .cloned()
+            .ok_or_else(|| AppError::Unauthorized("Request is not authenticated as an operator".to_string()))
+    }
+}
+
+/// Name of the key matching `token`. Digests are compared in constant time,
+/// and every key is checked so the timing does not tell which one matched.
+fn authenticate(keys: &[(String, String)], token: &str) -> Option<String> {
+    let presented = Sha256::digest(token.as_bytes());
+    let mut found = None;
+    for (name, key) in keys {
+        let expected = Sha256::digest(key.as_bytes());
+        let diff = presented
+            .iter()
+            .zip(expected.iter())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b));
+        if diff == 0 && found.is_none() {
+            found = Some(name.clone());
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_authenticate() {
+        let keys = vec![
+            ("alice".to_string(), "alice-token-0123456789".to_string()),
+            ("bob".to_string(), "bob-token-0123456789".to_string()),
+        ];
+
+        assert_eq!(authenticate(&keys, "bob-token-0123456789").as_deref(), Some("bob"));
+        assert_eq!(authenticate(&keys, "bob-token-012345678"), None);
+        assert_eq!(authenticate(&[], "bob-token-0123456789"), None);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,38 @@
+use axum::{
+    async_trait,
+    extract::{ConnectInfo, FromRequestParts},
+    http::request::Parts,
+};
+use std::{convert::Infallible, net::SocketAddr};
+
+use crate::state::AppState;
+
+/// Address of the caller, used by per-IP risk rules. `None` when the server
+/// was not started with connection info (e.g. in tests).
+pub struct ClientIp(pub Option<String>);
+
+#[async_trait]
+impl FromRequestParts<AppState> for ClientIp {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        if state.config.risk.trust_forwarded_for {
+            let forwarded = parts
+                .headers
+                .get("x-forwarded-for")
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.split(',').next())
+                .map(|ip| ip.trim().to_string())
+                .filter(|ip| !ip.is_empty());
+            if forwarded.is_some() {
+                return Ok(Self(forwarded));
+            }
+        }
+
+        let peer = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip().to_string());
+        Ok(Self(peer))
+    }
+}
\ No newline at end of file
@@ -0,0 +1,198 @@
+use axum::{
+    body::{to_bytes, Body, Bytes},
+    extract::{Request, State},
+    http::{header, request::Parts, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::error::AppError;
+use crate::middleware::wallet_auth::WalletAuth;
+use crate::modules::models::idempotency::IdempotencyRecord;
+use crate::modules::services::idempotency_service::IdempotencyClaim;
+use crate::state::AppState;
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "idempotent-replayed";
+
+const MAX_KEY_LENGTH: usize = 255;
+// Matches axum's default request body limit.
+const MAX_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+/// Makes a POST safe to retry when it carries an `Idempotency-Key` header.
+///
+/// Keys are scoped to the path and the calling wallet ([`caller`]). The first
+/// request with a key runs and its response is stored; a retry with the same
+/// method, path and body gets that response back, a retry while the first is
+/// still running gets 409 and a different request under the same key gets 422.
+/// Server errors are not stored, so the client can retry them. The lease on a
+/// running request is renewed until it finishes, so only a key left in flight
+/// by a crashed process frees up once its lease runs out.
+/// Requests without the header pass straight through.
+pub async fn idempotency(
+    State(state): State<AppState>,
+    req: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let Some(key) = req.headers().get(IDEMPOTENCY_KEY_HEADER) else {
+        return Ok(next.run(req).await);
+    };
+    let key = key
+        .to_str()
+        .ok()
+        .filter(|key| !key.is_empty() && key.len() <= MAX_KEY_LENGTH)
+        .ok_or_else(|| {
+            AppError::BadRequest(format!(
+                "Idempotency-Key must be 1 to {} visible ASCII characters",
+                MAX_KEY_LENGTH
+            ))
+        })?
+        .to_string();
+
+    let (parts, body) = req.into_parts();
+    let body = to_bytes(body, MAX_BODY_BYTES)
+        .await
+        .map_err(|e| AppError::BadRequest(format!("Could not read request body: {}", e)))?;
+    let fingerprint = fingerprint(parts.method.as_str(), parts.uri.path(), &body);
+    let scope = format!("{}\n{}", parts.uri.path(), caller(&parts, &body));
+
+    match state.idempotency_service.begin(&scope, &key, &fingerprint).await? {
+        IdempotencyClaim::Replay(record) => Ok(replay(record)),
+        IdempotencyClaim::Started => {
+            let req = Request::from_parts(parts, Body::from(body));
+            // Run detached so a client that times out and disconnects does not
+            // leave the key in flight; the retry then finds the stored response.
+            let result = tokio::spawn(execute(state.clone(), scope.clone(), key.clone(), req, next)).await;
+            match result {
+                Ok(response) => Ok(response),
+                Err(e) => {
+                    release(&state, &scope, &key).await;
+                    Err(AppError::InternalError(format!("Idempotent request {} failed: {}", key, e)))
+                }
+            }
+        }
+    }
+}
+
+/// The wallet behind the request: the signer when the route is signed,
+/// otherwise the `public_key` of the JSON body. Routes that carry the wallet in
+/// the path are already told apart by the path.
+fn caller(parts: &Parts, body: &[u8]) -> String {
+    if let Some(WalletAuth(account)) = parts.extensions.get::<WalletAuth>() {
+        return account.clone();
+    }
+    serde_json::from_slice::<Value>(body)
+        .ok()
+        .and_then(|body| body.get("public_key")?.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+async fn execute(state: AppState, scope: String, key: String, req: Request, next: Next) -> Response {
+    let run = next.run(req);
+    tokio::pin!(run);
+    let mut heartbeat = tokio::time::interval(state.idempotency_service.renew_every());
+    // The first tick completes immediately; the claim just set the lease.
+    heartbeat.tick().await;
+    let response = loop {
+        tokio::select! {
+            response = &mut run => break response,
+            _ = heartbeat.tick() => {
+                if let Err(e) = state.idempotency_service.renew(&scope, &key).await {
+                    tracing::warn!("Failed to renew lease of idempotency key {}: {}", key, e);
+                }
+            }
+        }
+    };
+
+    if response.status().is_server_error() {
+        release(&state, &scope, &key).await;
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let body = match to_bytes(body, usize::MAX).await {
+        Ok(body) => body,
+        Err(e) => {
+            release(&state, &scope, &key).await;
+            return AppError::InternalError(format!("Could not buffer response: {}", e)).into_response();
+        }
+    };
+
+    let content_type = parts
+        .headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok());
+    if let Err(e) = state
+        .idempotency_service
+        .complete(&scope, &key, parts.status.as_u16(), content_type, &body)
+        .await
+    {
+        tracing::error!("Failed to store response for idempotency key {}: {}", key, e);
+        release(&state, &scope, &key).await;
+    }
+
+    Response::from_parts(parts, Body::from(body))
+}
+
+async fn release(state: &AppState, scope: &str, key: &str) {
+    if let Err(e) = state.idempotency_service.release(scope, key).await {
+        tracing::error!("Failed to release idempotency key {}: {}", key, e);
+    }
+}
+
+fn replay(record: IdempotencyRecord) -> Response {
+    let status = record
+        .response_status
+        .and_then(|status| u16::try_from(status).ok())
+        .and_then(|status| StatusCode::from_u16(status).ok())
+        .unwrap_or(StatusCode::OK);
+
+    let mut response = (status, Bytes::from(record.response_body.unwrap_or_default())).into_response();
+    let headers = response.headers_mut();
+    headers.remove(header::CONTENT_TYPE);
+    if let Some(value) = record
+        .response_content_type
+        .and_then(|content_type| HeaderValue::from_str(&content_type).ok())
+    {
+        headers.insert(header::CONTENT_TYPE, value);
+    }
+    headers.insert(IDEMPOTENT_REPLAYED_HEADER, HeaderValue::from_static("true"));
+    response
+}
+
+fn fingerprint(method: &str, path: &str, body: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(method.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(path.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(body);
+    hex::encode(hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprint_covers_method_path_and_body() {
+        let base = fingerprint("POST", "/bank/transfer", br#"{"amount":"10"}"#);
+
+        assert_eq!(base, fingerprint("POST", "/bank/transfer", br#"{"amount":"10"}"#));
+        assert_ne!(base, fingerprint("POST", "/bank/transfer", br#"{"amount":"11"}"#));
+        assert_ne!(base, fingerprint("POST", "/aa/relayer", br#"{"amount":"10"}"#));
+        assert_ne!(base, fingerprint("PUT", "/bank/transfer", br#"{"amount":"10"}"#));
+    }
+
+    #[test]
+    fn test_caller_prefers_the_signer() {
+        let (mut parts, _) = Request::new(()).into_parts();
+        assert_eq!(caller(&parts, br#"{"public_key":"GA"}"#), "GA");
+        assert_eq!(caller(&parts, b"not json"), "");
+
+        parts.extensions.insert(WalletAuth("GB".to_string()));
+        assert_eq!(caller(&parts, br#"{"public_key":"GA"}"#), "GB");
+    }
+}
\ No newline at end of file
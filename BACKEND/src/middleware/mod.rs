@@ -1 +1,5 @@
-pub mod logging;
\ No newline at end of file
+pub mod admin_auth;
+pub mod client_ip;
+pub mod idempotency;
+pub mod logging;
+pub mod wallet_auth;
\ No newline at end of file
@@ -0,0 +1,192 @@
+use axum::{
+    async_trait,
+    body::{to_bytes, Body},
+    extract::{FromRequestParts, OriginalUri, Request},
+    http::request::Parts,
+    middleware::Next,
+    response::Response,
+};
+use chrono::Utc;
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use sha2::{Digest, Sha256};
+
+use crate::error::AppError;
+use crate::utils::crypto::decode_stellar_public;
+
+pub const ACCOUNT_HEADER: &str = "x-stellar-account";
+pub const TIMESTAMP_HEADER: &str = "x-stellar-timestamp";
+pub const SIGNATURE_HEADER: &str = "x-stellar-signature";
+
+/// How far a request's timestamp may be from the server clock.
+const MAX_CLOCK_SKEW_SECONDS: i64 = 300;
+// Matches axum's default request body limit.
+const MAX_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+/// Stellar account that signed the request.
+#[derive(Debug, Clone)]
+pub struct WalletAuth(pub String);
+
+/// Requires the request to be signed with the key of the Stellar account in
+/// `X-Stellar-Account`. `X-Stellar-Signature` is the hex ed25519 signature of
+/// [`signing_payload`] over `X-Stellar-Timestamp` (Unix seconds), the method,
+/// the path with its query and the SHA-256 of the body. Handlers read the
+/// account with the [`WalletAuth`] extractor.
+pub async fn wallet_auth(req: Request, next: Next) -> Result<Response, AppError> {
+    let (mut parts, body) = req.into_parts();
+    let body = to_bytes(body, MAX_BODY_BYTES)
+        .await
+        .map_err(|e| AppError::BadRequest(format!("Could not read request body: {}", e)))?;
+
+    let account = verify(&parts, &body, Utc::now().timestamp())?;
+    parts.extensions.insert(WalletAuth(account));
+
+    Ok(next.run(Request::from_parts(parts, Body::from(body))).await)
+}
+
+/// Like [`wallet_auth`], for routes that also serve anonymous callers: a
+/// request without `X-Stellar-Account` goes through unsigned, one with it
+/// must be signed. Handlers read the account with `Option<WalletAuth>`.
+pub async fn optional_wallet_auth(req: Request, next: Next) -> Result<Response, AppError> {
+    if !req.headers().contains_key(ACCOUNT_HEADER) {
+        return Ok(next.run(req).await);
+    }
+    wallet_auth(req, next).await
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for WalletAuth {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<WalletAuth>()
+            .cloned()
+            .ok_or_else(|| AppError::Unauthorized("Request is not signed by a wallet".to_string()))
+    }
+}
+
+fn verify(parts: &Parts, body: &[u8], now: i64) -> Result<String, AppError> {
+    let header = |name: &str| {
+        parts
+            .headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .ok_or_else(|| AppError::Unauthorized(format!("Missing {} header", name)))
+    };
+
+    let account = header(ACCOUNT_HEADER)?;
+    let timestamp = header(TIMESTAMP_HEADER)?
+        .parse::<i64>()
+        .map_err(|_| AppError::Unauthorized(format!("{} must be Unix seconds", TIMESTAMP_HEADER)))?;
+    if (now - timestamp).abs() > MAX_CLOCK_SKEW_SECONDS {
+        return Err(AppError::Unauthorized(format!(
+            "Request timestamp is more than {} seconds from the server clock",
+            MAX_CLOCK_SKEW_SECONDS
+        )));
+    }
+
+    let key = decode_stellar_public(account)
+        .and_then(|key| VerifyingKey::from_bytes(&key).ok())
+        .ok_or_else(|| AppError::Unauthorized(format!("Invalid Stellar account: {}", account)))?;
+    let signature = hex::decode(header(SIGNATURE_HEADER)?)
+        .ok()
+        .and_then(|bytes| Signature::try_from(bytes.as_slice()).ok())
+        .ok_or_else(|| AppError::Unauthorized(format!("{} must be a hex ed25519 signature", SIGNATURE_HEADER)))?;
+
+    // Nested routers see the path without its prefix; clients sign the full one.
+    let uri = parts
+        .extensions
+        .get::<OriginalUri>()
+        .map(|OriginalUri(uri)| uri)
+        .unwrap_or(&parts.uri);
+    let path = uri.path_and_query().map(|pq| pq.as_str()).unwrap_or("/");
+
+    let payload = signing_payload(timestamp, parts.method.as_str(), path, body);
+    key.verify(payload.as_bytes(), &signature)
+        .map_err(|_| AppError::Unauthorized(format!("Signature does not match account {}", account)))?;
+
+    Ok(account.to_string())
+}
+
+/// `{timestamp}\n{METHOD}\n{path?query}\n{hex sha256(body)}`
+pub fn signing_payload(timestamp: i64, method: &str, path: &str, body: &[u8]) -> String {
+    format!(
+        "{}\n{}\n{}\n{}",
+        timestamp,
+        method.to_uppercase(),
+        path,
+        hex::encode(Sha256::digest(body))
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::Request as HttpRequest;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    // Address of SigningKey::from_bytes(&[7; 32]).
+    const ACCOUNT: &str = "GDVEU3DD4KOFECV66VIHWEZOYX4ZKR3WV27L464SIIPOU2IUI3JCZA57";
+
+    fn signed(key: &SigningKey, timestamp: i64, path: &str, body: &'static str) -> (Parts, &'static str) {
+        let payload = signing_payload(timestamp, "POST", path, body.as_bytes());
+        let signature = hex::encode(key.sign(payload.as_bytes()).to_bytes());
+        let (parts, _) = HttpRequest::post(path)
+            .header(ACCOUNT_HEADER, ACCOUNT)
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(SIGNATURE_HEADER, signature)
+            .body(())
+            .unwrap()
+            .into_parts();
+        (parts, body)
+    }
+
+    #[test]
+    fn test_verify_signed_request() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        assert_eq!(
+            decode_stellar_public(ACCOUNT),
+            Some(key.verifying_key().to_bytes())
+        );
+
+        let (parts, body) = signed(&key, 1_700_000_000, "/api/quotes", r#"{"a":1}"#);
+        assert_eq!(verify(&parts, body.as_bytes(), 1_700_000_010).unwrap(), ACCOUNT);
+
+        // Another body, a stale timestamp or another key are refused.
+        assert!(verify(&parts, br#"{"a":2}"#, 1_700_000_010).is_err());
+        assert!(verify(&parts, body.as_bytes(), 1_700_000_000 + MAX_CLOCK_SKEW_SECONDS + 1).is_err());
+
+        let other = SigningKey::from_bytes(&[8; 32]);
+        let (parts, body) = signed(&other, 1_700_000_000, "/api/quotes", r#"{"a":1}"#);
+        assert!(verify(&parts, body.as_bytes(), 1_700_000_000).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_optional_wallet_auth() {
+        use axum::{middleware::from_fn, routing::post, Router};
+        use tower::ServiceExt;
+
+        async fn whoami(signer: Option<WalletAuth>) -> String {
+            signer.map(|WalletAuth(account)| account).unwrap_or_default()
+        }
+        let app = Router::new().route("/whoami", post(whoami)).layer(from_fn(optional_wallet_auth));
+
+        // Anonymous callers go through unsigned.
+        let response = app.clone().oneshot(HttpRequest::post("/whoami").body(Body::empty()).unwrap()).await.unwrap();
+        assert_eq!(response.status(), 200);
+        let body = to_bytes(response.into_body(), MAX_BODY_BYTES).await.unwrap();
+        assert!(body.is_empty());
+
+        // Naming an account without signing for it is refused.
+        let request = HttpRequest::post("/whoami")
+            .header(ACCOUNT_HEADER, ACCOUNT)
+            .header(TIMESTAMP_HEADER, Utc::now().timestamp().to_string())
+            .header(SIGNATURE_HEADER, "00")
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(app.oneshot(request).await.unwrap().status(), 401);
+    }
+}
\ No newline at end of file
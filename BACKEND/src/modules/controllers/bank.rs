@@ -1,25 +1,38 @@
-use axum::{extract::State, Json};
-use crate::error::AppError;
+use axum::{extract::{Path, Query, State}, Json};
+use crate::error::{AppError, FieldError};
+use crate::middleware::admin_auth::AdminPrincipal;
+use crate::middleware::client_ip::ClientIp;
+use crate::middleware::wallet_auth::WalletAuth;
 use crate::modules::models::bank::*;
 use crate::state::AppState;
 
 pub async fn create_transfer(
     State(state): State<AppState>,
+    WalletAuth(account): WalletAuth,
+    ClientIp(client_ip): ClientIp,
     Json(payload): Json<BankTransferRequest>,
 ) -> Result<Json<BankTransferResponse>, AppError> {
+    if payload.public_key.trim() != account {
+        return Err(AppError::Validation(vec![FieldError::new(
+            "public_key",
+            "forbidden",
+            "Only the signing wallet's funds can be transferred",
+        )]));
+    }
+
     let (transfer_id, status, details) = state
         .bank_service
-        .create_transfer(
-            &payload.public_key,
-            payload.amount_fiat,
-            &payload.currency,
-            &payload.bank_account,
-        )
+        .create_transfer(&payload, client_ip.as_deref())
         .await?;
 
-    let message = if status == "completed" {
+    let message = if status == TransferStatus::Pending.as_str() {
+        format!(
+            "Bank transfer of {} {} queued for payout",
+            payload.amount_fiat, payload.currency
+        )
+    } else if status == TransferStatus::Review.as_str() {
         format!(
-            "Bank transfer of {} {} successfully processed",
+            "Bank transfer of {} {} held for manual review",
             payload.amount_fiat, payload.currency
         )
     } else {
@@ -34,16 +47,72 @@ pub async fn create_transfer(
     }))
 }
 
-pub async fn list_transfers(
+pub async fn get_transfer(
     State(state): State<AppState>,
-) -> Result<Json<TransferListResponse>, AppError> {
-    let transfers = state
+    WalletAuth(account): WalletAuth,
+    Path(id): Path<String>,
+) -> Result<Json<BankTransferStatusResponse>, AppError> {
+    let (transfer, transitions, transactions) = state.bank_service.get_own_transfer(&id, &account).await?;
+
+    Ok(Json(BankTransferStatusResponse { transfer, transitions, transactions }))
+}
+
+pub async fn cancel_transfer(
+    State(state): State<AppState>,
+    WalletAuth(account): WalletAuth,
+    Path(id): Path<String>,
+    Json(payload): Json<CancelTransferRequest>,
+) -> Result<Json<BankTransferStatusResponse>, AppError> {
+    state
         .bank_service
-        .list_all_transfers()
-        .await
-        .map_err(|e| AppError::InternalError(e.to_string()))?;
+        .cancel_transfer(&id, &account, payload.reason.as_deref())
+        .await?;
 
-    let total = transfers.len();
+    let (transfer, transitions, transactions) = state.bank_service.get_transfer(&id).await?;
 
-    Ok(Json(TransferListResponse { transfers, total }))
+    Ok(Json(BankTransferStatusResponse { transfer, transitions, transactions }))
+}
+
+pub async fn reverse_transfer(
+    State(state): State<AppState>,
+    AdminPrincipal(operator): AdminPrincipal,
+    Path(id): Path<String>,
+    Json(payload): Json<ReverseTransferRequest>,
+) -> Result<Json<BankTransferStatusResponse>, AppError> {
+    state
+        .bank_service
+        .reverse_transfer(&id, &operator, &payload.reason, payload.refund_escrow)
+        .await?;
+
+    let (transfer, transitions, transactions) = state.bank_service.get_transfer(&id).await?;
+
+    Ok(Json(BankTransferStatusResponse { transfer, transitions, transactions }))
+}
+
+/// The signing wallet's own transfers; a `public_key` in the query must be
+/// that wallet's.
+pub async fn list_wallet_transfers(
+    State(state): State<AppState>,
+    WalletAuth(account): WalletAuth,
+    Query(mut query): Query<TransferListQuery>,
+) -> Result<Json<TransferListResponse>, AppError> {
+    if let Some(public_key) = query.public_key.as_deref().map(str::trim).filter(|key| !key.is_empty()) {
+        if public_key != account {
+            return Err(AppError::Validation(vec![FieldError::new(
+                "public_key",
+                "forbidden",
+                "Only the signing wallet's transfers can be listed",
+            )]));
+        }
+    }
+    query.public_key = Some(account);
+
+    Ok(Json(state.bank_service.list_transfers(&query).await?))
+}
+
+pub async fn list_transfers(
+    State(state): State<AppState>,
+    Query(query): Query<TransferListQuery>,
+) -> Result<Json<TransferListResponse>, AppError> {
+    Ok(Json(state.bank_service.list_transfers(&query).await?))
 }
\ No newline at end of file
@@ -0,0 +1,74 @@
+use axum::{extract::{Path, State}, Json};
+use crate::error::{AppError, FieldError};
+use crate::middleware::{admin_auth::AdminPrincipal, wallet_auth::WalletAuth};
+use crate::modules::models::beneficiary::*;
+use crate::state::AppState;
+
+pub async fn create_beneficiary(
+    State(state): State<AppState>,
+    WalletAuth(signer): WalletAuth,
+    Path(pubkey): Path<String>,
+    Json(payload): Json<CreateBeneficiaryRequest>,
+) -> Result<Json<BeneficiaryResponse>, AppError> {
+    require_signer(&pubkey, &signer)?;
+    let beneficiary = state.beneficiary_service.create(&pubkey, &payload).await?;
+
+    Ok(Json(beneficiary))
+}
+
+pub async fn list_beneficiaries(
+    State(state): State<AppState>,
+    WalletAuth(signer): WalletAuth,
+    Path(pubkey): Path<String>,
+) -> Result<Json<BeneficiaryListResponse>, AppError> {
+    require_signer(&pubkey, &signer)?;
+    let beneficiaries = state.beneficiary_service.list(&pubkey).await?;
+    let total = beneficiaries.len();
+
+    Ok(Json(BeneficiaryListResponse { beneficiaries, total }))
+}
+
+pub async fn get_beneficiary(
+    State(state): State<AppState>,
+    WalletAuth(signer): WalletAuth,
+    Path((pubkey, id)): Path<(String, String)>,
+) -> Result<Json<BeneficiaryResponse>, AppError> {
+    require_signer(&pubkey, &signer)?;
+    let beneficiary = state.beneficiary_service.get(&pubkey, &id).await?;
+
+    Ok(Json(beneficiary))
+}
+
+pub async fn delete_beneficiary(
+    State(state): State<AppState>,
+    WalletAuth(signer): WalletAuth,
+    Path((pubkey, id)): Path<(String, String)>,
+) -> Result<(), AppError> {
+    require_signer(&pubkey, &signer)?;
+    state.beneficiary_service.delete(&pubkey, &id).await
+}
+
+pub async fn verify_beneficiary(
+    State(state): State<AppState>,
+    AdminPrincipal(operator): AdminPrincipal,
+    Path(id): Path<String>,
+    Json(payload): Json<BeneficiaryVerificationRequest>,
+) -> Result<Json<BeneficiaryResponse>, AppError> {
+    let beneficiary = state
+        .beneficiary_service
+        .set_verification(&id, &payload.status, payload.note.as_deref(), &operator)
+        .await?;
+
+    Ok(Json(beneficiary))
+}
+
+fn require_signer(pubkey: &str, signer: &str) -> Result<(), AppError> {
+    if pubkey != signer {
+        return Err(AppError::Validation(vec![FieldError::new(
+            "pubkey",
+            "forbidden",
+            "Only the signing wallet's beneficiaries can be read or changed",
+        )]));
+    }
+    Ok(())
+}
\ No newline at end of file
@@ -1,25 +1,131 @@
-use axum::{extract::{Query, State}, Json};
+use axum::{
+    extract::{ws::{Message, WebSocket, WebSocketUpgrade}, Query, State},
+    response::Response,
+    Json,
+};
 use crate::error::AppError;
+use crate::middleware::wallet_auth::WalletAuth;
+use crate::modules::models::amount::{FixedPoint, Rounding};
 use crate::modules::models::convert::*;
+use crate::modules::models::quote::QuoteAsset;
+use crate::modules::models::rate_history::{RateHistoryQuery, RateHistoryResponse};
+use crate::modules::models::rate_stream::{RateStreamMessage, RateStreamQuery, RateStreamRequest};
+use crate::modules::services::rate_stream_service::RateStreamClient;
 use crate::state::AppState;
 
+/// Market conversion for anyone; with a `quote_id`, the quote of the
+/// signing wallet that requested it.
 pub async fn convert_to_usdc(
     State(state): State<AppState>,
+    signer: Option<WalletAuth>,
     Json(payload): Json<ConvertRequest>,
 ) -> Result<Json<ConvertResponse>, AppError> {
-    let (usdc_amount, fiat_amount, rate_source) = state
+    if let Some(quote_id) = &payload.quote_id {
+        let Some(WalletAuth(signer)) = signer else {
+            return Err(AppError::Unauthorized(
+                "Converting with a quote_id must be signed by the wallet that requested it".to_string(),
+            ));
+        };
+        return convert_with_quote(&state, &payload, quote_id, &signer).await;
+    }
+
+    let token = state.convert_service.resolve_token(&payload.from_token)?;
+    let fiat_currency = state.convert_service.resolve_fiat(payload.to_fiat.as_deref())?;
+
+    let (usdc_amount, fiat_amount, to_usd, usd_to_fiat) = state
         .convert_service
-        .convert_to_usdc(&payload.from_token, &payload.amount)
+        .convert_to_fiat(&token.symbol, payload.amount, &fiat_currency)
         .await
         .map_err(|e| AppError::ExternalApiError(e.to_string()))?;
 
     Ok(Json(ConvertResponse {
         from_token: payload.from_token.clone(),
-        from_amount: payload.amount.clone(),
+        from_amount: payload.amount,
         usdc_amount,
         fiat_amount,
-        fiat_currency: "MXN".to_string(),
-        rate_source,
+        fiat_currency,
+        rate_source: format!("{}; {}", to_usd.describe(), usd_to_fiat.describe()),
+        to_usd,
+        usd_to_fiat,
+        quote_id: None,
+    }))
+}
+
+async fn convert_with_quote(
+    state: &AppState,
+    payload: &ConvertRequest,
+    quote_id: &str,
+    signer: &str,
+) -> Result<Json<ConvertResponse>, AppError> {
+    let requested_fiat = payload
+        .to_fiat
+        .as_deref()
+        .map(|code| state.convert_service.resolve_fiat(Some(code)))
+        .transpose()?;
+    // Showing what a quote converts to does not spend it.
+    let quote = state
+        .quote_service
+        .usable(quote_id, signer, |quote| {
+            let sell = QuoteAsset::parse(&quote.sell_asset);
+            if sell.map(|a| a.symbol()) != Some(payload.from_token.to_lowercase()) {
+                return Err(AppError::BadRequest(format!(
+                    "Quote sells {}, not {}",
+                    quote.sell_asset, payload.from_token
+                )));
+            }
+            if quote.sell_amount != payload.amount {
+                return Err(AppError::BadRequest(format!(
+                    "Quote is for {} {}, not {}",
+                    quote.sell_amount, payload.from_token, payload.amount
+                )));
+            }
+            match QuoteAsset::parse(&quote.buy_asset) {
+                Some(QuoteAsset::Fiat(code)) => {
+                    if let Some(requested) = &requested_fiat {
+                        if requested != &code {
+                            return Err(AppError::BadRequest(format!(
+                                "Quote buys {}, not {}",
+                                code, requested
+                            )));
+                        }
+                    }
+                    Ok(())
+                }
+                _ => Err(AppError::BadRequest(
+                    "Conversion quotes must buy a fiat currency".to_string(),
+                )),
+            }
+        })
+        .await?;
+
+    let fiat_currency = QuoteAsset::parse(&quote.buy_asset)
+        .ok_or_else(|| AppError::InternalError(format!("Stored quote has invalid buy_asset {}", quote.buy_asset)))?
+        .symbol()
+        .to_uppercase();
+
+    // The legs are the market rates the quote was priced from, as stored with
+    // it; the locked amounts below already include the fee.
+    let (to_usd, usd_to_fiat) = match <[ConversionLeg; 2]>::try_from(state.quote_service.legs(&quote.id).await?) {
+        Ok([to_usd, usd_to_fiat]) => (to_usd, usd_to_fiat),
+        Err(legs) => {
+            return Err(AppError::InternalError(format!(
+                "Quote {} has {} stored legs, expected 2",
+                quote.id,
+                legs.len()
+            )))
+        }
+    };
+
+    Ok(Json(ConvertResponse {
+        from_token: payload.from_token.clone(),
+        from_amount: payload.amount,
+        usdc_amount: quote.usd_amount,
+        fiat_amount: quote.buy_amount.rescale(Rounding::Down)?,
+        fiat_currency,
+        rate_source: quote.rate_source.clone(),
+        to_usd,
+        usd_to_fiat,
+        quote_id: Some(quote.id),
     }))
 }
 
@@ -36,8 +142,107 @@ pub async fn get_rates(
     Ok(Json(RatesResponse {
         from: params.from,
         to: params.to,
-        rate,
-        source: "CoinGecko".to_string(),
+        rate: rate.rate,
+        source: rate.describe(),
+        fetched_at: rate.fetched_at,
+        fallback: rate.fallback,
+        stale: rate.stale,
         timestamp: chrono::Utc::now().to_rfc3339(),
     }))
+}
+
+pub async fn get_rate_history(
+    State(state): State<AppState>,
+    Query(params): Query<RateHistoryQuery>,
+) -> Result<Json<RateHistoryResponse>, AppError> {
+    let history = state.rate_history_service.history(&params).await?;
+
+    Ok(Json(history))
+}
+
+pub async fn stream_rates(
+    ws: WebSocketUpgrade,
+    State(state): State<AppState>,
+    Query(params): Query<RateStreamQuery>,
+) -> Result<Response, AppError> {
+    let initial: Vec<String> = params
+        .pairs
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .filter(|p| !p.trim().is_empty())
+        .map(String::from)
+        .collect();
+    // Unknown pairs are refused before the upgrade, with a plain 400.
+    let initial = state.rate_stream_service.normalize_pairs(&initial)?;
+
+    Ok(ws.on_upgrade(move |socket| async move {
+        let client = state.rate_stream_service.connect();
+        handle_rate_stream(socket, client, initial).await;
+    }))
+}
+
+async fn handle_rate_stream(mut socket: WebSocket, mut client: RateStreamClient, initial: Vec<String>) {
+    if !initial.is_empty() {
+        for message in subscribe(&mut client, &initial) {
+            if send_message(&mut socket, &message).await.is_err() {
+                return;
+            }
+        }
+    }
+
+    loop {
+        tokio::select! {
+            incoming = socket.recv() => {
+                let text = match incoming {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Close(_))) | None | Some(Err(_)) => break,
+                    Some(Ok(_)) => continue,
+                };
+
+                let replies = match serde_json::from_str::<RateStreamRequest>(&text) {
+                    Ok(RateStreamRequest::Subscribe { pairs }) => subscribe(&mut client, &pairs),
+                    Ok(RateStreamRequest::Unsubscribe { pairs }) => {
+                        client.unsubscribe(&pairs);
+                        Vec::new()
+                    }
+                    Err(e) => vec![RateStreamMessage::Error {
+                        pair: None,
+                        message: format!("Invalid message: {}", e),
+                    }],
+                };
+
+                for message in replies {
+                    if send_message(&mut socket, &message).await.is_err() {
+                        return;
+                    }
+                }
+            }
+            update = client.next() => {
+                let Some(message) = update else { break };
+                if send_message(&mut socket, &message).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+fn subscribe(client: &mut RateStreamClient, pairs: &[String]) -> Vec<RateStreamMessage> {
+    match client.subscribe(pairs) {
+        Ok((pairs, latest)) => {
+            let mut messages = vec![RateStreamMessage::Subscribed { pairs }];
+            messages.extend(latest);
+            messages
+        }
+        Err(e) => vec![RateStreamMessage::Error {
+            pair: None,
+            message: e.to_string(),
+        }],
+    }
+}
+
+async fn send_message(socket: &mut WebSocket, message: &RateStreamMessage) -> Result<(), axum::Error> {
+    let text = serde_json::to_string(message).unwrap_or_default();
+    socket.send(Message::Text(text)).await
 }
\ No newline at end of file
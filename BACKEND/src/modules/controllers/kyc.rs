@@ -0,0 +1,78 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use crate::error::{AppError, FieldError};
+use crate::middleware::{admin_auth::AdminPrincipal, wallet_auth::WalletAuth};
+use crate::modules::models::kyc::*;
+use crate::state::AppState;
+
+pub async fn put_customer(
+    State(state): State<AppState>,
+    WalletAuth(signer): WalletAuth,
+    Json(payload): Json<PutCustomerRequest>,
+) -> Result<Json<PutCustomerResponse>, AppError> {
+    require_signer("account", &payload.account, &signer)?;
+    let id = state.kyc_service.put_customer(&payload).await?;
+
+    Ok(Json(PutCustomerResponse { id }))
+}
+
+/// Without `account` or `id`, the signing wallet's customer.
+pub async fn get_customer(
+    State(state): State<AppState>,
+    WalletAuth(signer): WalletAuth,
+    Query(mut query): Query<GetCustomerQuery>,
+) -> Result<Json<GetCustomerResponse>, AppError> {
+    match &query.account {
+        Some(account) => require_signer("account", account, &signer)?,
+        None => query.account = Some(signer),
+    }
+    let customer = state.kyc_service.get_customer(&query).await?;
+
+    Ok(Json(customer))
+}
+
+pub async fn delete_customer(
+    State(state): State<AppState>,
+    WalletAuth(signer): WalletAuth,
+    Path(account): Path<String>,
+) -> Result<(), AppError> {
+    require_signer("account", &account, &signer)?;
+    state.kyc_service.delete_customer(&account).await
+}
+
+pub async fn list_customers(
+    State(state): State<AppState>,
+    Query(query): Query<KycListQuery>,
+) -> Result<Json<KycListResponse>, AppError> {
+    let customers = state.kyc_service.list(query.status.as_deref()).await?;
+    let total = customers.len();
+
+    Ok(Json(KycListResponse { customers, total }))
+}
+
+pub async fn decide_customer(
+    State(state): State<AppState>,
+    AdminPrincipal(operator): AdminPrincipal,
+    Path(id): Path<String>,
+    Json(payload): Json<KycDecisionRequest>,
+) -> Result<Json<KycCustomer>, AppError> {
+    let customer = state
+        .kyc_service
+        .decide(&id, &payload.status, payload.message.as_deref(), &operator)
+        .await?;
+
+    Ok(Json(customer))
+}
+
+fn require_signer(field: &str, account: &str, signer: &str) -> Result<(), AppError> {
+    if account.trim() != signer {
+        return Err(AppError::Validation(vec![FieldError::new(
+            field,
+            "forbidden",
+            "Only the signing wallet's customer can be read or changed",
+        )]));
+    }
+    Ok(())
+}
\ No newline at end of file
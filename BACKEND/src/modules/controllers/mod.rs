@@ -1,6 +1,13 @@
 pub mod admin;
 pub mod bank;
+pub mod beneficiary;
 pub mod convert;
 pub mod health;
+pub mod kyc;
+pub mod quotes;
+pub mod reconciliation;
+pub mod remittance;
 pub mod reputation;
-pub mod wallet;
\ No newline at end of file
+pub mod review;
+pub mod wallet;
+pub mod webhook;
\ No newline at end of file
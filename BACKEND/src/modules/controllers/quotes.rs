@@ -0,0 +1,28 @@
+use axum::{extract::{Path, State}, Json};
+use crate::error::AppError;
+use crate::middleware::wallet_auth::WalletAuth;
+use crate::modules::models::quote::*;
+use crate::state::AppState;
+
+pub async fn create_quote(
+    State(state): State<AppState>,
+    WalletAuth(account): WalletAuth,
+    Json(payload): Json<QuoteRequest>,
+) -> Result<Json<QuoteResponse>, AppError> {
+    let quote = state.quote_service.create_quote(&payload, &account).await?;
+
+    Ok(Json(QuoteResponse::from(&quote)))
+}
+
+pub async fn get_quote(
+    State(state): State<AppState>,
+    WalletAuth(account): WalletAuth,
+    Path(id): Path<String>,
+) -> Result<Json<QuoteResponse>, AppError> {
+    let quote = state.quote_service.get_quote(&id).await?;
+    if quote.requested_by.as_deref() != Some(account.as_str()) {
+        return Err(AppError::QuoteNotFound(id));
+    }
+
+    Ok(Json(QuoteResponse::from(&quote)))
+}
\ No newline at end of file
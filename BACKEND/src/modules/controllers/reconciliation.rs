@@ -0,0 +1,64 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::header,
+    response::IntoResponse,
+    Json,
+};
+use crate::error::AppError;
+use crate::modules::models::reconciliation::*;
+use crate::state::AppState;
+
+/// The body is the provider's settlement CSV as sent.
+pub async fn import_statement(
+    State(state): State<AppState>,
+    Query(query): Query<ImportStatementQuery>,
+    body: String,
+) -> Result<Json<ReconciliationReport>, AppError> {
+    let report = state
+        .reconciliation_service
+        .import_statement(&query.provider, query.filename.as_deref(), &body)
+        .await?;
+
+    Ok(Json(report))
+}
+
+pub async fn run_reconciliation(
+    State(state): State<AppState>,
+    Json(payload): Json<ReconciliationRunRequest>,
+) -> Result<Json<ReconciliationReport>, AppError> {
+    let report = state.reconciliation_service.run(&payload.provider).await?;
+
+    Ok(Json(report))
+}
+
+pub async fn list_runs(
+    State(state): State<AppState>,
+    Query(query): Query<ReconciliationRunsQuery>,
+) -> Result<Json<ReconciliationRunListResponse>, AppError> {
+    let runs = state.reconciliation_service.list_runs(query.provider.as_deref()).await?;
+    let total = runs.len();
+
+    Ok(Json(ReconciliationRunListResponse { runs, total }))
+}
+
+pub async fn get_run(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<ReconciliationReport>, AppError> {
+    let report = state.reconciliation_service.get_report(&id).await?;
+
+    Ok(Json(report))
+}
+
+pub async fn download_report(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let csv = state.reconciliation_service.report_csv(&id).await?;
+    let disposition = format!("attachment; filename=\"reconciliation-{}.csv\"", id);
+
+    Ok((
+        [(header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()), (header::CONTENT_DISPOSITION, disposition)],
+        csv,
+    ))
+}
\ No newline at end of file
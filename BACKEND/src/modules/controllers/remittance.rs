@@ -0,0 +1,54 @@
+use axum::{extract::{Path, Query, State}, Json};
+use crate::error::{AppError, FieldError};
+use crate::middleware::client_ip::ClientIp;
+use crate::middleware::wallet_auth::WalletAuth;
+use crate::modules::models::remittance::*;
+use crate::state::AppState;
+
+pub async fn create_remittance(
+    State(state): State<AppState>,
+    WalletAuth(account): WalletAuth,
+    ClientIp(client_ip): ClientIp,
+    Json(payload): Json<CreateRemittanceRequest>,
+) -> Result<Json<RemittanceResponse>, AppError> {
+    require_signer(&payload.public_key, &account, "Only the signing wallet's funds can be remitted")?;
+
+    let remittance = state
+        .remittance_service
+        .create(&payload, client_ip.as_deref())
+        .await?;
+
+    Ok(Json(remittance))
+}
+
+pub async fn get_remittance(
+    State(state): State<AppState>,
+    WalletAuth(account): WalletAuth,
+    Path(id): Path<String>,
+) -> Result<Json<RemittanceResponse>, AppError> {
+    let remittance = state.remittance_service.get_own(&id, &account).await?;
+
+    Ok(Json(remittance))
+}
+
+pub async fn list_remittances(
+    State(state): State<AppState>,
+    WalletAuth(account): WalletAuth,
+    Query(query): Query<RemittanceListQuery>,
+) -> Result<Json<RemittanceListResponse>, AppError> {
+    if let Some(public_key) = query.public_key.as_deref().filter(|key| !key.trim().is_empty()) {
+        require_signer(public_key, &account, "Only the signing wallet's remittances can be listed")?;
+    }
+
+    let remittances = state.remittance_service.list(&account).await?;
+    let total = remittances.len();
+
+    Ok(Json(RemittanceListResponse { remittances, total }))
+}
+
+fn require_signer(public_key: &str, signer: &str, message: &str) -> Result<(), AppError> {
+    if public_key.trim() != signer {
+        return Err(AppError::Validation(vec![FieldError::new("public_key", "forbidden", message)]));
+    }
+    Ok(())
+}
\ No newline at end of file
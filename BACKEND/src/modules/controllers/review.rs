@@ -0,0 +1,71 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use crate::error::AppError;
+use crate::middleware::admin_auth::AdminPrincipal;
+use crate::modules::models::review::*;
+use crate::modules::services::review_service::ReviewService;
+use crate::state::AppState;
+
+pub async fn list_reviews(
+    State(state): State<AppState>,
+    Query(query): Query<ReviewListQuery>,
+) -> Result<Json<ReviewListResponse>, AppError> {
+    let reviews = state.review_service.list(query.status.as_deref()).await?;
+    let total = reviews.len();
+
+    Ok(Json(ReviewListResponse { reviews, total }))
+}
+
+pub async fn get_review(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<ReviewDetailResponse>, AppError> {
+    let (review, notes) = state.review_service.get(&id).await?;
+    let (transfer, _, _) = state.bank_service.get_transfer(&review.transfer_id).await?;
+
+    Ok(Json(ReviewDetailResponse {
+        review: ReviewService::summarize(review),
+        transfer,
+        notes,
+    }))
+}
+
+pub async fn claim_review(
+    State(state): State<AppState>,
+    AdminPrincipal(reviewer): AdminPrincipal,
+    Path(id): Path<String>,
+) -> Result<Json<ReviewSummary>, AppError> {
+    let review = state.review_service.claim(&id, &reviewer).await?;
+
+    Ok(Json(ReviewService::summarize(review)))
+}
+
+pub async fn approve_review(
+    State(state): State<AppState>,
+    AdminPrincipal(reviewer): AdminPrincipal,
+    Path(id): Path<String>,
+    Json(payload): Json<ReviewDecisionRequest>,
+) -> Result<Json<ReviewSummary>, AppError> {
+    let review = state
+        .bank_service
+        .approve_review(&id, &reviewer, payload.note.as_deref())
+        .await?;
+
+    Ok(Json(ReviewService::summarize(review)))
+}
+
+pub async fn reject_review(
+    State(state): State<AppState>,
+    AdminPrincipal(reviewer): AdminPrincipal,
+    Path(id): Path<String>,
+    Json(payload): Json<ReviewDecisionRequest>,
+) -> Result<Json<ReviewSummary>, AppError> {
+    let review = state
+        .bank_service
+        .reject_review(&id, &reviewer, payload.note.as_deref())
+        .await?;
+
+    Ok(Json(ReviewService::summarize(review)))
+}
\ No newline at end of file
@@ -2,7 +2,8 @@ use axum::{
     extract::{Path, State},
     Json,
 };
-use crate::error::AppError;
+use crate::error::{AppError, FieldError};
+use crate::middleware::{client_ip::ClientIp, wallet_auth::WalletAuth};
 use crate::modules::models::wallet::*;
 use crate::state::AppState;
 
@@ -58,18 +59,9 @@ pub async fn get_balance(
             }
         })?;
 
-    let balance_list: Vec<Balance> = balances
-        .into_iter()
-        .map(|(asset_code, balance)| Balance {
-            asset_code,
-            balance,
-            asset_issuer: None,
-        })
-        .collect();
-
     Ok(Json(BalanceResponse {
         public_key: pubkey,
-        balances: balance_list,
+        balances,
         recent_transactions: recent_txs,
     }))
 }
@@ -77,18 +69,17 @@ pub async fn get_balance(
 pub async fn send_transaction(
     State(state): State<AppState>,
     Path(pubkey): Path<String>,
+    ClientIp(client_ip): ClientIp,
     Json(payload): Json<SendTransactionRequest>,
 ) -> Result<Json<SendTransactionResponse>, AppError> {
+    let token = state
+        .token_registry
+        .resolve_stellar(payload.asset_code.as_deref().unwrap_or("xlm"))?;
+
     let tx_hash = state
         .wallet_service
-        .send_transaction(
-            &pubkey,
-            &payload.destination,
-            &payload.amount,
-            payload.asset_code.as_deref(),
-        )
-        .await
-        .map_err(|e| AppError::InternalError(e.to_string()))?;
+        .send_transaction(&pubkey, &payload.destination, payload.amount, token, client_ip.as_deref())
+        .await?;
 
     Ok(Json(SendTransactionResponse {
         tx_hash,
@@ -96,6 +87,28 @@ pub async fn send_transaction(
     }))
 }
 
+pub async fn convert_assets(
+    State(state): State<AppState>,
+    Path(pubkey): Path<String>,
+    WalletAuth(signer): WalletAuth,
+    Json(payload): Json<WalletConvertRequest>,
+) -> Result<Json<WalletConvertResponse>, AppError> {
+    if pubkey != signer {
+        return Err(AppError::Validation(vec![FieldError::new(
+            "pubkey",
+            "forbidden",
+            "Only the signing wallet's assets can be converted",
+        )]));
+    }
+
+    let response = state
+        .wallet_service
+        .convert_assets(&pubkey, &payload.quote_id, payload.slippage_bps)
+        .await?;
+
+    Ok(Json(response))
+}
+
 pub async fn aa_relay_transaction(
     State(state): State<AppState>,
     Json(payload): Json<RelayTransactionRequest>,
@@ -0,0 +1,51 @@
+use axum::{extract::{Path, State}, Json};
+use crate::error::AppError;
+use crate::modules::models::webhook::*;
+use crate::state::AppState;
+
+pub async fn create_webhook(
+    State(state): State<AppState>,
+    Json(payload): Json<CreateWebhookRequest>,
+) -> Result<Json<WebhookSubscriptionResponse>, AppError> {
+    let subscription = state.webhook_service.create_subscription(&payload).await?;
+
+    Ok(Json(subscription))
+}
+
+pub async fn list_webhooks(
+    State(state): State<AppState>,
+) -> Result<Json<WebhookListResponse>, AppError> {
+    let subscriptions = state.webhook_service.list_subscriptions().await?;
+    let total = subscriptions.len();
+
+    Ok(Json(WebhookListResponse { subscriptions, total }))
+}
+
+pub async fn delete_webhook(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<(), AppError> {
+    state.webhook_service.delete_subscription(&id).await
+}
+
+pub async fn list_dead_letters(
+    State(state): State<AppState>,
+) -> Result<Json<DeadLetterListResponse>, AppError> {
+    let deliveries = state.webhook_service.dead_letters().await?;
+    let total = deliveries.len();
+
+    Ok(Json(DeadLetterListResponse { deliveries, total }))
+}
+
+pub async fn replay_event(
+    State(state): State<AppState>,
+    Path(event_id): Path<String>,
+    Json(payload): Json<ReplayWebhookRequest>,
+) -> Result<Json<ReplayWebhookResponse>, AppError> {
+    let deliveries = state
+        .webhook_service
+        .replay(&event_id, payload.subscription_id.as_deref())
+        .await?;
+
+    Ok(Json(ReplayWebhookResponse { event_id, deliveries }))
+}
\ No newline at end of file
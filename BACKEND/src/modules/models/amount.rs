@@ -0,0 +1,446 @@
+//! Exact fixed-point money types.
+//!
+//! * [`Amount`] - Stellar asset amounts as i64 stroops (7 decimals).
+//! * [`FiatAmount`] - fiat amounts as i64 minor units (2 decimals).
+//! * [`Rate`] - exchange rates with 12 decimals.
+//!
+//! All of them serialize as decimal strings and are stored as TEXT so nothing
+//! is lost going through JSON or SQLite. Parsing is strict: a value with more
+//! decimals than the type holds is rejected instead of being rounded. Any
+//! arithmetic that can lose precision takes an explicit [`Rounding`]; the
+//! convention is `Down` for amounts paid out to the user, `Up` for fees and
+//! amounts the user has to pay, and `HalfEven` for rates.
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use sqlx::encode::IsNull;
+use sqlx::error::BoxDynError;
+use sqlx::sqlite::{Sqlite, SqliteArgumentValue, SqliteTypeInfo, SqliteValueRef};
+use sqlx::{Decode, Encode, Type};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    /// Towards zero.
+    Down,
+    /// Away from zero.
+    Up,
+    /// To the nearest value, ties to even.
+    HalfEven,
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum AmountError {
+    #[error("Invalid decimal value: {0}")]
+    Invalid(String),
+
+    #[error("{value} has more than {scale} decimal places")]
+    TooPrecise { value: String, scale: u32 },
+
+    #[error("Amount out of range")]
+    Overflow,
+
+    #[error("Division by zero")]
+    DivisionByZero,
+}
+
+/// Shared behaviour of the fixed-point types. Values are never negative.
+pub trait FixedPoint: Copy + Sized {
+    const SCALE: u32;
+
+    const ZERO: Self;
+
+    fn units(&self) -> i128;
+
+    fn from_units(units: i128) -> Result<Self, AmountError>;
+
+    /// Parses a decimal string, rounding extra decimals instead of rejecting them.
+    fn parse_rounded(value: &str, rounding: Rounding) -> Result<Self, AmountError> {
+        Self::from_units(parse_units(value, Self::SCALE, Some(rounding))?)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.units() == 0
+    }
+
+    fn to_f64(&self) -> f64 {
+        self.units() as f64 / 10f64.powi(Self::SCALE as i32)
+    }
+
+    /// Converts to another fixed-point type, rounding if it has fewer decimals.
+    fn rescale<T: FixedPoint>(&self, rounding: Rounding) -> Result<T, AmountError> {
+        T::from_units(rescale_units(self.units(), Self::SCALE, T::SCALE, rounding)?)
+    }
+
+    /// Rounds to `decimals` places while keeping the type.
+    fn round_to(&self, decimals: u32, rounding: Rounding) -> Result<Self, AmountError> {
+        if decimals >= Self::SCALE {
+            return Ok(*self);
+        }
+        let step = pow10(Self::SCALE - decimals)?;
+        Self::from_units(div_round(self.units(), step, rounding)? * step)
+    }
+
+    /// Multiplies by `numerator / denominator`, e.g. a fee in basis points.
+    fn scale_by(&self, numerator: i128, denominator: i128, rounding: Rounding) -> Result<Self, AmountError> {
+        let product = self.units().checked_mul(numerator).ok_or(AmountError::Overflow)?;
+        Self::from_units(div_round(product, denominator, rounding)?)
+    }
+
+    fn checked_add(&self, other: Self) -> Result<Self, AmountError> {
+        Self::from_units(self.units().checked_add(other.units()).ok_or(AmountError::Overflow)?)
+    }
+
+    fn checked_sub(&self, other: Self) -> Result<Self, AmountError> {
+        Self::from_units(self.units().checked_sub(other.units()).ok_or(AmountError::Overflow)?)
+    }
+
+    /// Displays with at most `decimals` places (rounding half-even).
+    fn format_with(&self, decimals: u32) -> String {
+        let decimals = decimals.min(Self::SCALE);
+        match rescale_units(self.units(), Self::SCALE, decimals, Rounding::HalfEven) {
+            Ok(units) => format_units(units, decimals),
+            Err(_) => format_units(self.units(), Self::SCALE),
+        }
+    }
+}
+
+macro_rules! fixed_point_type {
+    ($name:ident, $repr:ty, $scale:expr) => {
+        #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct $name($repr);
+
+        impl FixedPoint for $name {
+            const SCALE: u32 = $scale;
+
+            const ZERO: Self = Self(0);
+
+            fn units(&self) -> i128 {
+                self.0 as i128
+            }
+
+            fn from_units(units: i128) -> Result<Self, AmountError> {
+                if units < 0 {
+                    return Err(AmountError::Invalid("negative value".to_string()));
+                }
+                <$repr>::try_from(units)
+                    .map(Self)
+                    .map_err(|_| AmountError::Overflow)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = AmountError;
+
+            fn from_str(value: &str) -> Result<Self, Self::Err> {
+                <Self as FixedPoint>::from_units(parse_units(value, $scale, None)?)
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&format_units(self.0 as i128, $scale))
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(&self.to_string())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                deserializer.deserialize_str(DecimalVisitor::<$name>(std::marker::PhantomData))
+            }
+        }
+
+        impl Type<Sqlite> for $name {
+            fn type_info() -> SqliteTypeInfo {
+                <String as Type<Sqlite>>::type_info()
+            }
+
+            fn compatible(ty: &SqliteTypeInfo) -> bool {
+                <String as Type<Sqlite>>::compatible(ty)
+                    || <f64 as Type<Sqlite>>::compatible(ty)
+                    || <i64 as Type<Sqlite>>::compatible(ty)
+            }
+        }
+
+        impl<'q> Encode<'q, Sqlite> for $name {
+            fn encode_by_ref(&self, buf: &mut Vec<SqliteArgumentValue<'q>>) -> IsNull {
+                <String as Encode<'q, Sqlite>>::encode(self.to_string(), buf)
+            }
+        }
+
+        impl<'r> Decode<'r, Sqlite> for $name {
+            fn decode(value: SqliteValueRef<'r>) -> Result<Self, BoxDynError> {
+                let text = <String as Decode<'r, Sqlite>>::decode(value)?;
+                Ok(text.parse()?)
+            }
+        }
+    };
+}
+
+fixed_point_type!(Amount, i64, 7);
+fixed_point_type!(FiatAmount, i64, 2);
+fixed_point_type!(Rate, i128, 12);
+
+impl Amount {
+    pub const fn from_stroops(stroops: i64) -> Self {
+        Self(stroops)
+    }
+
+    pub fn stroops(&self) -> i64 {
+        self.0
+    }
+}
+
+impl Rate {
+    pub const ONE: Self = Self(1_000_000_000_000);
+
+    /// For prices coming from external APIs as JSON numbers.
+    pub fn from_f64(value: f64) -> Result<Self, AmountError> {
+        if !value.is_finite() {
+            return Err(AmountError::Invalid(value.to_string()));
+        }
+        Self::parse_rounded(&value.to_string(), Rounding::HalfEven)
+    }
+
+    /// `numerator / denominator` as a rate.
+    pub fn from_ratio<A: FixedPoint, B: FixedPoint>(
+        numerator: A,
+        denominator: B,
+        rounding: Rounding,
+    ) -> Result<Self, AmountError> {
+        // units(rate) = n / 10^a / (d / 10^b) * 10^r
+        let (n, d) = (numerator.units(), denominator.units());
+        let up = pow10(Self::SCALE + B::SCALE)?;
+        let down = pow10(A::SCALE)?;
+        let scaled = n.checked_mul(up).ok_or(AmountError::Overflow)?;
+        let denominator = d.checked_mul(down).ok_or(AmountError::Overflow)?;
+        Self::from_units(div_round(scaled, denominator, rounding)?)
+    }
+
+    /// Converts `amount` at this rate into another fixed-point type.
+    pub fn apply<A: FixedPoint, B: FixedPoint>(&self, amount: A, rounding: Rounding) -> Result<B, AmountError> {
+        let product = amount.units().checked_mul(self.units()).ok_or(AmountError::Overflow)?;
+        let shift = (A::SCALE + Self::SCALE) as i64 - B::SCALE as i64;
+        let units = if shift >= 0 {
+            div_round(product, pow10(shift as u32)?, rounding)?
+        } else {
+            product.checked_mul(pow10((-shift) as u32)?).ok_or(AmountError::Overflow)?
+        };
+        B::from_units(units)
+    }
+
+    /// Converts `amount` by dividing by this rate (the inverse conversion).
+    pub fn apply_inverse<A: FixedPoint, B: FixedPoint>(&self, amount: A, rounding: Rounding) -> Result<B, AmountError> {
+        let shift = (B::SCALE + Self::SCALE) as i64 - A::SCALE as i64;
+        let numerator = if shift >= 0 {
+            amount.units().checked_mul(pow10(shift as u32)?).ok_or(AmountError::Overflow)?
+        } else {
+            div_round(amount.units(), pow10((-shift) as u32)?, rounding)?
+        };
+        B::from_units(div_round(numerator, self.units(), rounding)?)
+    }
+
+    pub fn mul(&self, other: Rate, rounding: Rounding) -> Result<Rate, AmountError> {
+        self.apply(other, rounding)
+    }
+
+    pub fn div(&self, other: Rate, rounding: Rounding) -> Result<Rate, AmountError> {
+        Self::from_ratio(*self, other, rounding)
+    }
+
+    pub fn inverse(&self, rounding: Rounding) -> Result<Rate, AmountError> {
+        Self::ONE.div(*self, rounding)
+    }
+
+    pub fn midpoint(&self, other: Rate) -> Result<Rate, AmountError> {
+        let sum = self.checked_add(other)?;
+        Self::from_units(div_round(sum.units(), 2, Rounding::HalfEven)?)
+    }
+}
+
+struct DecimalVisitor<T>(std::marker::PhantomData<T>);
+
+impl<'de, T> de::Visitor<'de> for DecimalVisitor<T>
+where
+    T: FromStr<Err = AmountError>,
+{
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // JSON numbers are refused: serde reads them as f64 before we see them.
+        f.write_str("a non-negative decimal string")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<T, E> {
+        value.parse().map_err(E::custom)
+    }
+}
+
+fn pow10(exp: u32) -> Result<i128, AmountError> {
+    10i128.checked_pow(exp).ok_or(AmountError::Overflow)
+}
+
+fn div_round(numerator: i128, denominator: i128, rounding: Rounding) -> Result<i128, AmountError> {
+    if denominator == 0 {
+        return Err(AmountError::DivisionByZero);
+    }
+
+    let quotient = numerator / denominator;
+    let remainder = numerator % denominator;
+    if remainder == 0 {
+        return Ok(quotient);
+    }
+
+    let away = if (numerator < 0) != (denominator < 0) { -1 } else { 1 };
+    let rounded = match rounding {
+        Rounding::Down => quotient,
+        Rounding::Up => quotient + away,
+        Rounding::HalfEven => {
+            let twice = remainder.abs() * 2;
+            let denominator = denominator.abs();
+            if twice > denominator || (twice == denominator && quotient % 2 != 0) {
+                quotient + away
+            } else {
+                quotient
+            }
+        }
+    };
+    Ok(rounded)
+}
+
+fn rescale_units(units: i128, from: u32, to: u32, rounding: Rounding) -> Result<i128, AmountError> {
+    if to >= from {
+        units.checked_mul(pow10(to - from)?).ok_or(AmountError::Overflow)
+    } else {
+        div_round(units, pow10(from - to)?, rounding)
+    }
+}
+
+fn parse_units(value: &str, scale: u32, rounding: Option<Rounding>) -> Result<i128, AmountError> {
+    let invalid = || AmountError::Invalid(value.to_string());
+    let trimmed = value.trim();
+
+    let (integer, fraction) = match trimmed.split_once('.') {
+        Some((integer, fraction)) => (integer, fraction),
+        None => (trimmed, ""),
+    };
+
+    let all_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    if integer.is_empty() || !all_digits(integer) || !all_digits(fraction) {
+        return Err(invalid());
+    }
+    if trimmed.ends_with('.') {
+        return Err(invalid());
+    }
+
+    let scale_len = scale as usize;
+    let (kept, extra) = fraction.split_at(fraction.len().min(scale_len));
+
+    let mut units: i128 = 0;
+    for digit in integer.bytes().chain(kept.bytes()) {
+        units = units
+            .checked_mul(10)
+            .and_then(|u| u.checked_add((digit - b'0') as i128))
+            .ok_or(AmountError::Overflow)?;
+    }
+    units = units
+        .checked_mul(pow10((scale_len - kept.len()) as u32)?)
+        .ok_or(AmountError::Overflow)?;
+
+    if extra.bytes().any(|b| b != b'0') {
+        let rounding = rounding.ok_or_else(|| AmountError::TooPrecise {
+            value: value.to_string(),
+            scale,
+        })?;
+        let first = extra.as_bytes()[0] - b'0';
+        let rest_nonzero = extra.bytes().skip(1).any(|b| b != b'0');
+        let round_up = match rounding {
+            Rounding::Down => false,
+            Rounding::Up => true,
+            Rounding::HalfEven => first > 5 || (first == 5 && (rest_nonzero || units % 2 != 0)),
+        };
+        if round_up {
+            units = units.checked_add(1).ok_or(AmountError::Overflow)?;
+        }
+    }
+
+    Ok(units)
+}
+
+fn format_units(units: i128, scale: u32) -> String {
+    let divisor = 10i128.pow(scale);
+    let integer = units / divisor;
+    let fraction = (units % divisor).abs();
+    if scale == 0 {
+        integer.to_string()
+    } else {
+        format!("{}.{:0width$}", integer, fraction, width = scale as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_format() {
+        let amount: Amount = "10000".parse().unwrap();
+        assert_eq!(amount.stroops(), 100_000_000_000);
+        assert_eq!(amount.to_string(), "10000.0000000");
+
+        let fiat: FiatAmount = "1000.50".parse().unwrap();
+        assert_eq!(fiat.to_string(), "1000.50");
+        assert_eq!("1000.500".parse::<FiatAmount>().unwrap(), fiat);
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid() {
+        assert!("1000.505".parse::<FiatAmount>().is_err());
+        assert!("-1".parse::<Amount>().is_err());
+        assert!("1e5".parse::<Amount>().is_err());
+        assert!(".5".parse::<Amount>().is_err());
+        assert!("5.".parse::<Amount>().is_err());
+        assert!("".parse::<Amount>().is_err());
+    }
+
+    #[test]
+    fn test_rounding() {
+        assert_eq!(FiatAmount::parse_rounded("1.005", Rounding::HalfEven).unwrap().to_string(), "1.00");
+        assert_eq!(FiatAmount::parse_rounded("1.015", Rounding::HalfEven).unwrap().to_string(), "1.02");
+        assert_eq!(FiatAmount::parse_rounded("1.001", Rounding::Up).unwrap().to_string(), "1.01");
+        assert_eq!(FiatAmount::parse_rounded("1.009", Rounding::Down).unwrap().to_string(), "1.00");
+    }
+
+    #[test]
+    fn test_apply_rate() {
+        let xlm: Amount = "100".parse().unwrap();
+        let xlm_usd: Rate = "0.12".parse().unwrap();
+        let usd: Amount = xlm_usd.apply(xlm, Rounding::Down).unwrap();
+        assert_eq!(usd.to_string(), "12.0000000");
+
+        let usd_mxn: Rate = "17.123456".parse().unwrap();
+        let mxn: FiatAmount = usd_mxn.apply(usd, Rounding::Down).unwrap();
+        assert_eq!(mxn.to_string(), "205.48");
+
+        let back: Amount = xlm_usd.apply_inverse(usd, Rounding::Up).unwrap();
+        assert_eq!(back, xlm);
+    }
+
+    #[test]
+    fn test_serde_roundtrip() {
+        let amount: Amount = serde_json::from_str("\"1.5\"").unwrap();
+        assert_eq!(serde_json::to_string(&amount).unwrap(), "\"1.5000000\"");
+
+        let fiat: FiatAmount = serde_json::from_str("\"1000.0\"").unwrap();
+        assert_eq!(fiat.to_string(), "1000.00");
+
+        assert!(serde_json::from_str::<FiatAmount>("1000.0").is_err());
+        assert!(serde_json::from_str::<Amount>("1").is_err());
+    }
+}
\ No newline at end of file
@@ -1,27 +1,158 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::error::FieldError;
+use crate::modules::models::amount::{Amount, FiatAmount};
+use crate::modules::models::transaction::Transaction;
+
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct BankTransfer {
     pub id: String,
     pub wallet_id: String,
     pub public_key: String,
-    pub amount_fiat: f64,
+    pub amount_fiat: FiatAmount,
     pub currency: String,
     pub bank_account_masked: String,
     pub status: String,
     pub rejection_reason: Option<String>,
     pub reputation_score: Option<i64>,
+    pub quote_id: Option<String>,
+    /// Payout provider's id for the transfer, set once it has been submitted.
+    pub provider_reference: Option<String>,
+    /// Provider the transfer was routed to when it was created.
+    pub payout_provider: Option<String>,
+    /// Encrypted beneficiary details for the payout worker.
+    #[serde(skip_serializing, default)]
+    pub payout_destination: Option<String>,
+    /// Saved beneficiary the transfer was sent to, if any.
+    pub beneficiary_id: Option<String>,
     pub created_at: DateTime<Utc>,
     pub completed_at: Option<DateTime<Utc>>,
 }
 
+impl BankTransfer {
+    pub fn transfer_status(&self) -> Option<TransferStatus> {
+        TransferStatus::parse(&self.status)
+    }
+}
+
+/// Lifecycle of a bank transfer. Money only moves once a payout provider
+/// reports it, so a new transfer starts as `Pending`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferStatus {
+    /// Held for manual review before it can enter the payout pipeline.
+    Review,
+    /// Escrow held until its remittance converts it on the DEX.
+    Converting,
+    Pending,
+    Processing,
+    Sent,
+    Completed,
+    Failed,
+    Reversed,
+    Cancelled,
+    /// Refused at creation (e.g. low reputation); never entered the pipeline.
+    Rejected,
+}
+
+impl TransferStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransferStatus::Review => "review",
+            TransferStatus::Converting => "converting",
+            TransferStatus::Pending => "pending",
+            TransferStatus::Processing => "processing",
+            TransferStatus::Sent => "sent",
+            TransferStatus::Completed => "completed",
+            TransferStatus::Failed => "failed",
+            TransferStatus::Reversed => "reversed",
+            TransferStatus::Cancelled => "cancelled",
+            TransferStatus::Rejected => "rejected",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "review" => Some(TransferStatus::Review),
+            "converting" => Some(TransferStatus::Converting),
+            "pending" => Some(TransferStatus::Pending),
+            "processing" => Some(TransferStatus::Processing),
+            "sent" => Some(TransferStatus::Sent),
+            "completed" => Some(TransferStatus::Completed),
+            "failed" => Some(TransferStatus::Failed),
+            "reversed" => Some(TransferStatus::Reversed),
+            "cancelled" => Some(TransferStatus::Cancelled),
+            "rejected" => Some(TransferStatus::Rejected),
+            _ => None,
+        }
+    }
+
+    pub fn can_transition_to(&self, next: TransferStatus) -> bool {
+        use TransferStatus::*;
+
+        matches!(
+            (self, next),
+            (Review, Pending | Converting | Rejected | Cancelled)
+                | (Converting, Pending | Cancelled | Failed)
+                | (Pending, Processing | Cancelled | Failed)
+                | (Processing, Sent | Failed)
+                | (Sent, Completed | Failed | Reversed)
+                | (Completed, Reversed)
+        )
+    }
+}
+
+impl std::fmt::Display for TransferStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// One status change of a transfer. The first entry has no `from_status`.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct BankTransferTransition {
+    pub id: String,
+    pub transfer_id: String,
+    pub from_status: Option<String>,
+    pub to_status: String,
+    pub reason: String,
+    pub created_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BankTransferRequest {
     pub public_key: String,
-    pub amount_fiat: f64,
+    pub amount_fiat: FiatAmount,
     pub currency: String,
+    /// Required unless `beneficiary_id` is given.
+    #[serde(default)]
     pub bank_account: String,
+    /// Saved beneficiary to pay instead of `bank_account`.
+    pub beneficiary_id: Option<String>,
+    /// Account holder, passed to providers that require it.
+    pub beneficiary_name: Option<String>,
+    /// Required for US (ABA) accounts.
+    pub routing_number: Option<String>,
+    /// ISO 3166 country of the receiving bank; inferred from an IBAN or the currency.
+    pub bank_country: Option<String>,
+    pub quote_id: Option<String>,
+    /// Asset the transfer is funded from, as a registry symbol (`usdc`) or
+    /// SEP-38 asset. Defaults to USDC, or the quote's sell asset.
+    pub source_asset: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CancelTransferRequest {
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReverseTransferRequest {
+    pub reason: String,
+    /// The fiat came back, so the escrow is refunded to the wallet.
+    #[serde(default)]
+    pub refund_escrow: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,15 +165,169 @@ pub struct BankTransferResponse {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BankTransferDetails {
-    pub amount: f64,
+    pub amount: FiatAmount,
     pub currency: String,
     pub bank_account_masked: String,
+    /// Detected from the CLABE bank code, IBAN or routing number, when known.
+    pub bank_name: Option<String>,
     pub reputation_score: u8,
+    pub quote_id: Option<String>,
+    pub source_asset: String,
+    /// Escrowed from the wallet, fee included.
+    pub source_amount: Amount,
+    pub escrow_tx_hash: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BankTransferStatusResponse {
+    pub transfer: BankTransfer,
+    pub transitions: Vec<BankTransferTransition>,
+    /// Escrow and release (refund) transactions linked to the transfer.
+    pub transactions: Vec<Transaction>,
+}
+
+/// Filters for a transfer listing. `from` and `to` bound `created_at`;
+/// `from` is inclusive and `to` exclusive.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TransferListQuery {
+    /// Required on `/bank/transfers`; optional for admins.
+    pub public_key: Option<String>,
+    pub status: Option<String>,
+    pub currency: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    /// `-created_at` (newest first, the default) or `created_at`.
+    pub sort: Option<String>,
+    /// `next_cursor` of the previous page.
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// Validated listing filters, as the repository applies them.
+#[derive(Debug, Clone, Default)]
+pub struct TransferFilter {
+    pub wallet_id: Option<String>,
+    pub status: Option<String>,
+    pub currency: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// Position after the last transfer of a page. Transfers are ordered by
+/// `created_at` with the id breaking ties, so pages stay stable while new
+/// transfers arrive.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransferCursor {
+    pub ascending: bool,
     pub created_at: DateTime<Utc>,
+    pub id: String,
+}
+
+impl TransferCursor {
+    pub fn after(transfer: &BankTransfer, ascending: bool) -> Self {
+        Self {
+            ascending,
+            created_at: transfer.created_at,
+            id: transfer.id.clone(),
+        }
+    }
+
+    /// Opaque to clients; only meant to be sent back as `cursor`.
+    pub fn encode(&self) -> String {
+        let direction = if self.ascending { "a" } else { "d" };
+        hex::encode(format!(
+            "{}|{}|{}",
+            direction,
+            self.created_at.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true),
+            self.id
+        ))
+    }
+
+    pub fn decode(value: &str) -> Result<Self, FieldError> {
+        let invalid = || FieldError::new("cursor", "invalid", "Cursor is not one returned by this listing");
+
+        let bytes = hex::decode(value.trim()).map_err(|_| invalid())?;
+        let text = String::from_utf8(bytes).map_err(|_| invalid())?;
+        let mut parts = text.splitn(3, '|');
+
+        let ascending = match parts.next() {
+            Some("a") => true,
+            Some("d") => false,
+            _ => return Err(invalid()),
+        };
+        let created_at = parts
+            .next()
+            .and_then(|value| DateTime::parse_from_rfc3339(value).ok())
+            .ok_or_else(invalid)?
+            .with_timezone(&Utc);
+        let id = parts.next().filter(|id| !id.is_empty()).ok_or_else(invalid)?;
+
+        Ok(Self {
+            ascending,
+            created_at,
+            id: id.to_string(),
+        })
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransferListResponse {
     pub transfers: Vec<BankTransfer>,
-    pub total: usize,
+    /// Transfers matching the filters, across all pages.
+    pub total: i64,
+    /// Pass as `cursor` for the next page; absent on the last one.
+    pub next_cursor: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transfer_transitions() {
+        use TransferStatus::*;
+
+        assert!(Review.can_transition_to(Pending));
+        assert!(Review.can_transition_to(Rejected));
+        assert!(Pending.can_transition_to(Processing));
+        assert!(Processing.can_transition_to(Sent));
+        assert!(Sent.can_transition_to(Completed));
+        assert!(Completed.can_transition_to(Reversed));
+        assert!(Converting.can_transition_to(Pending));
+        assert!(Review.can_transition_to(Converting));
+
+        assert!(!Pending.can_transition_to(Completed));
+        assert!(!Processing.can_transition_to(Cancelled));
+        assert!(!Completed.can_transition_to(Failed));
+        assert!(!Failed.can_transition_to(Processing));
+        assert!(!Rejected.can_transition_to(Pending));
+        assert!(!Review.can_transition_to(Processing));
+        assert!(!Pending.can_transition_to(Review));
+        assert!(!Sent.can_transition_to(Sent));
+        assert!(!Converting.can_transition_to(Processing));
+    }
+
+    #[test]
+    fn test_transfer_status_roundtrip() {
+        use TransferStatus::*;
+
+        for status in [Review, Converting, Pending, Processing, Sent, Completed, Failed, Reversed, Cancelled, Rejected] {
+            assert_eq!(TransferStatus::parse(status.as_str()), Some(status));
+        }
+        assert_eq!(TransferStatus::parse("settled"), None);
+    }
+
+    #[test]
+    fn test_transfer_cursor_roundtrip() {
+        let cursor = TransferCursor {
+            ascending: false,
+            created_at: DateTime::parse_from_rfc3339("2024-05-01T12:30:00.123456Z").unwrap().with_timezone(&Utc),
+            id: "3f1c|9".to_string(),
+        };
+
+        assert_eq!(TransferCursor::decode(&cursor.encode()), Ok(cursor));
+        assert!(TransferCursor::decode("zz").is_err());
+        assert!(TransferCursor::decode(&hex::encode("x|2024-05-01T12:30:00Z|id")).is_err());
+    }
 }
\ No newline at end of file
@@ -0,0 +1,156 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Whether a saved beneficiary's account was checked to belong to its holder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationStatus {
+    Unverified,
+    Verified,
+    /// Transfers to it are refused.
+    Rejected,
+}
+
+impl VerificationStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VerificationStatus::Unverified => "unverified",
+            VerificationStatus::Verified => "verified",
+            VerificationStatus::Rejected => "rejected",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "unverified" => Some(VerificationStatus::Unverified),
+            "verified" => Some(VerificationStatus::Verified),
+            "rejected" => Some(VerificationStatus::Rejected),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for VerificationStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A bank account saved to a wallet's account book.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Beneficiary {
+    pub id: String,
+    pub wallet_id: String,
+    pub nickname: String,
+    pub currency: String,
+    pub country: String,
+    pub scheme: String,
+    pub bank_name: Option<String>,
+    pub account_masked: String,
+    /// Same key the risk rules count bank accounts by; one entry per account and wallet.
+    #[serde(skip_serializing)]
+    pub account_hash: String,
+    /// Encrypted `PayoutDestination` with the full account and holder name.
+    #[serde(skip_serializing)]
+    pub details: String,
+    pub verification_status: String,
+    pub verification_note: Option<String>,
+    /// End of the cooling-off period after the beneficiary was added.
+    pub available_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Set when the wallet removed it; the row is kept for its verification state.
+    #[serde(skip_serializing)]
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+impl Beneficiary {
+    pub fn status(&self) -> Option<VerificationStatus> {
+        VerificationStatus::parse(&self.verification_status)
+    }
+
+    /// Verified beneficiaries skip the cooling-off period; rejected ones are
+    /// never usable. Deleting one does not change this.
+    pub fn is_usable(&self, now: DateTime<Utc>) -> bool {
+        match self.status() {
+            Some(VerificationStatus::Verified) => true,
+            Some(VerificationStatus::Unverified) => now >= self.available_at,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateBeneficiaryRequest {
+    pub nickname: String,
+    pub holder_name: String,
+    pub currency: String,
+    pub bank_account: String,
+    /// Required for US (ABA) accounts.
+    pub routing_number: Option<String>,
+    /// ISO 3166 country of the receiving bank; inferred from an IBAN or the currency.
+    pub bank_country: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BeneficiaryResponse {
+    #[serde(flatten)]
+    pub beneficiary: Beneficiary,
+    /// Whether transfers can use it now.
+    pub usable: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BeneficiaryListResponse {
+    pub beneficiaries: Vec<BeneficiaryResponse>,
+    pub total: usize,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BeneficiaryVerificationRequest {
+    /// `verified` or `rejected`.
+    pub status: String,
+    pub note: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn beneficiary(status: VerificationStatus, available_at: DateTime<Utc>) -> Beneficiary {
+        Beneficiary {
+            id: "b1".to_string(),
+            wallet_id: "w1".to_string(),
+            nickname: "Mamá".to_string(),
+            currency: "MXN".to_string(),
+            country: "MX".to_string(),
+            scheme: "clabe".to_string(),
+            bank_name: None,
+            account_masked: "****7897".to_string(),
+            account_hash: "hash".to_string(),
+            details: String::new(),
+            verification_status: status.to_string(),
+            verification_note: None,
+            available_at,
+            created_at: available_at,
+            updated_at: available_at,
+            deleted_at: None,
+        }
+    }
+
+    #[test]
+    fn test_is_usable() {
+        let now = Utc::now();
+        let later = now + Duration::hours(1);
+
+        assert!(!beneficiary(VerificationStatus::Unverified, later).is_usable(now));
+        assert!(beneficiary(VerificationStatus::Unverified, later).is_usable(later));
+        assert!(beneficiary(VerificationStatus::Verified, later).is_usable(now));
+        assert!(!beneficiary(VerificationStatus::Rejected, now).is_usable(later));
+
+        let mut deleted = beneficiary(VerificationStatus::Rejected, now);
+        deleted.deleted_at = Some(now);
+        assert!(!deleted.is_usable(later));
+    }
+}
\ No newline at end of file
@@ -1,19 +1,60 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::modules::models::amount::{Amount, FiatAmount, Rate};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConvertRequest {
     pub from_token: String,
-    pub amount: String,
+    pub amount: Amount,
+    pub quote_id: Option<String>,
+    /// ISO 4217 code of the target fiat; defaults to `CONVERT_DEFAULT_FIAT`.
+    pub to_fiat: Option<String>,
+}
+
+/// One hop of a conversion, e.g. XLM -> USD or USD -> MXN.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversionLeg {
+    pub from: String,
+    pub to: String,
+    pub rate: Rate,
+    pub source: String,
+    pub fetched_at: DateTime<Utc>,
+    /// No live source answered; see `PriceOracle` for what is served instead.
+    pub fallback: bool,
+    pub stale: bool,
+}
+
+impl ConversionLeg {
+    /// A leg between an asset and itself (or a 1:1 peg), which needs no lookup.
+    pub fn par(from: &str, to: &str) -> Self {
+        ConversionLeg {
+            from: from.to_uppercase(),
+            to: to.to_uppercase(),
+            rate: Rate::ONE,
+            source: "par".to_string(),
+            fetched_at: Utc::now(),
+            fallback: false,
+            stale: false,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        format!("{}/{}: {}", self.from, self.to, self.source)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConvertResponse {
     pub from_token: String,
-    pub from_amount: String,
-    pub usdc_amount: String,
-    pub fiat_amount: String,
+    pub from_amount: Amount,
+    pub usdc_amount: Amount,
+    pub fiat_amount: FiatAmount,
     pub fiat_currency: String,
     pub rate_source: String,
+    pub to_usd: ConversionLeg,
+    pub usd_to_fiat: ConversionLeg,
+    pub quote_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,7 +67,10 @@ pub struct RatesQuery {
 pub struct RatesResponse {
     pub from: String,
     pub to: String,
-    pub rate: f64,
+    pub rate: Rate,
     pub source: String,
+    pub fetched_at: DateTime<Utc>,
+    pub fallback: bool,
+    pub stale: bool,
     pub timestamp: String,
 }
\ No newline at end of file
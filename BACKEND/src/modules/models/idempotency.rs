@@ -0,0 +1,16 @@
+/// A request seen under an `Idempotency-Key`, with the response it produced
+/// once the handler has finished.
+#[derive(Debug, Clone)]
+pub struct IdempotencyRecord {
+    /// SHA-256 of the method, path and body of the original request.
+    pub fingerprint: String,
+    pub response_status: Option<i64>,
+    pub response_content_type: Option<String>,
+    pub response_body: Option<Vec<u8>>,
+}
+
+impl IdempotencyRecord {
+    pub fn is_in_flight(&self) -> bool {
+        self.response_status.is_none()
+    }
+}
\ No newline at end of file
@@ -0,0 +1,251 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::error::FieldError;
+
+/// SEP-12 customer status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum KycStatus {
+    /// Required fields are missing.
+    NeedsInfo,
+    /// Every required field was provided and awaits a decision.
+    Processing,
+    Accepted,
+    Rejected,
+}
+
+impl KycStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KycStatus::NeedsInfo => "NEEDS_INFO",
+            KycStatus::Processing => "PROCESSING",
+            KycStatus::Accepted => "ACCEPTED",
+            KycStatus::Rejected => "REJECTED",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_uppercase().as_str() {
+            "NEEDS_INFO" => Some(KycStatus::NeedsInfo),
+            "PROCESSING" => Some(KycStatus::Processing),
+            "ACCEPTED" => Some(KycStatus::Accepted),
+            "REJECTED" => Some(KycStatus::Rejected),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for KycStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A SEP-9 field we collect.
+#[derive(Debug, Clone, Copy)]
+pub struct KycField {
+    pub name: &'static str,
+    pub field_type: &'static str,
+    pub description: &'static str,
+    pub optional: bool,
+}
+
+const fn field(name: &'static str, field_type: &'static str, description: &'static str, optional: bool) -> KycField {
+    KycField { name, field_type, description, optional }
+}
+
+pub const KYC_FIELDS: &[KycField] = &[
+    field("first_name", "string", "Given or first name", false),
+    field("last_name", "string", "Family or last name", false),
+    field("email_address", "string", "Email address", false),
+    field("mobile_number", "string", "Mobile phone number in E.164 format", true),
+    field("birth_date", "date", "Date of birth, YYYY-MM-DD", false),
+    field("address", "string", "Street address", true),
+    field("city", "string", "City of residence", true),
+    field("state_or_province", "string", "State or province", true),
+    field("postal_code", "string", "Postal code", true),
+    field("address_country_code", "string", "Country of residence, ISO 3166-1 alpha-3", false),
+    field("id_type", "string", "passport, drivers_license or id_card", false),
+    field("id_number", "string", "Passport or ID number", false),
+    field("id_country_code", "string", "Country that issued the ID, ISO 3166-1 alpha-3", true),
+    field("id_expiration_date", "date", "ID expiration date, YYYY-MM-DD", true),
+];
+
+const ID_TYPES: &[&str] = &["passport", "drivers_license", "id_card"];
+
+pub fn kyc_field(name: &str) -> Option<&'static KycField> {
+    KYC_FIELDS.iter().find(|field| field.name == name)
+}
+
+/// Checks submitted SEP-9 values; unknown fields are rejected so PII we do
+/// not expect is never stored.
+pub fn validate_fields(fields: &BTreeMap<String, String>) -> Result<(), Vec<FieldError>> {
+    let mut errors = Vec::new();
+
+    for (name, value) in fields {
+        let Some(field) = kyc_field(name) else {
+            errors.push(FieldError::new(name, "unknown_field", "Not a supported SEP-9 field"));
+            continue;
+        };
+        let value = value.trim();
+        if value.is_empty() {
+            errors.push(FieldError::new(name, "required", "Must not be empty"));
+            continue;
+        }
+
+        let valid = match field.name {
+            "email_address" => value.contains('@') && !value.starts_with('@') && !value.ends_with('@'),
+            "mobile_number" => {
+                value.starts_with('+') && (8..=16).contains(&value.len()) && value[1..].chars().all(|c| c.is_ascii_digit())
+            }
+            "address_country_code" | "id_country_code" => {
+                value.len() == 3 && value.chars().all(|c| c.is_ascii_alphabetic())
+            }
+            "id_type" => ID_TYPES.contains(&value),
+            _ if field.field_type == "date" => NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok(),
+            _ => value.len() <= 255,
+        };
+        if !valid {
+            errors.push(FieldError::new(name, "invalid", format!("Invalid {}", field.description.to_lowercase())));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Required fields not yet provided.
+pub fn missing_fields(fields: &BTreeMap<String, String>) -> Vec<&'static KycField> {
+    KYC_FIELDS
+        .iter()
+        .filter(|field| !field.optional && !fields.contains_key(field.name))
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct KycCustomer {
+    pub id: String,
+    pub wallet_id: String,
+    /// Stellar account of the wallet.
+    pub account: String,
+    pub status: String,
+    /// Why the customer was rejected or needs attention.
+    pub message: Option<String>,
+    /// Encrypted JSON map of the SEP-9 fields.
+    #[serde(skip_serializing)]
+    pub fields: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl KycCustomer {
+    pub fn kyc_status(&self) -> Option<KycStatus> {
+        KycStatus::parse(&self.status)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PutCustomerRequest {
+    pub id: Option<String>,
+    pub account: String,
+    /// SEP-9 fields, e.g. `first_name`, `email_address`.
+    #[serde(flatten)]
+    pub fields: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PutCustomerResponse {
+    pub id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GetCustomerQuery {
+    pub id: Option<String>,
+    pub account: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CustomerFieldInfo {
+    #[serde(rename = "type")]
+    pub field_type: &'static str,
+    pub description: &'static str,
+    pub optional: bool,
+    /// For provided fields: `ACCEPTED`, `PROCESSING` or `REJECTED` with the customer.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<KycStatus>,
+}
+
+impl CustomerFieldInfo {
+    pub fn new(field: &KycField, status: Option<KycStatus>) -> Self {
+        Self {
+            field_type: field.field_type,
+            description: field.description,
+            optional: field.optional,
+            status,
+        }
+    }
+}
+
+/// SEP-12 `GET /customer` body: the fields still needed and the ones provided.
+#[derive(Debug, Clone, Serialize)]
+pub struct GetCustomerResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub status: KycStatus,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields: BTreeMap<&'static str, CustomerFieldInfo>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub provided_fields: BTreeMap<&'static str, CustomerFieldInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct KycListQuery {
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct KycListResponse {
+    pub customers: Vec<KycCustomer>,
+    pub total: usize,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct KycDecisionRequest {
+    /// `ACCEPTED` or `REJECTED`.
+    pub status: String,
+    pub message: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_and_missing_fields() {
+        let mut fields = BTreeMap::from([
+            ("first_name".to_string(), "Ana".to_string()),
+            ("email_address".to_string(), "ana@example.com".to_string()),
+            ("birth_date".to_string(), "1990-02-30".to_string()),
+            ("address_country_code".to_string(), "MEX".to_string()),
+            ("favorite_color".to_string(), "blue".to_string()),
+        ]);
+
+        let errors = validate_fields(&fields).unwrap_err();
+        let failed = errors.iter().map(|e| e.field.as_str()).collect::<Vec<_>>();
+        assert_eq!(failed, vec!["birth_date", "favorite_color"]);
+
+        fields.remove("favorite_color");
+        fields.insert("birth_date".to_string(), "1990-02-28".to_string());
+        assert!(validate_fields(&fields).is_ok());
+
+        let missing = missing_fields(&fields).iter().map(|f| f.name).collect::<Vec<_>>();
+        assert_eq!(missing, vec!["last_name", "id_type", "id_number"]);
+    }
+}
\ No newline at end of file
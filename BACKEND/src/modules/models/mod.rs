@@ -1,5 +1,19 @@
+pub mod amount;
 pub mod bank;
+pub mod beneficiary;
 pub mod convert;
+pub mod idempotency;
+pub mod kyc;
+pub mod quote;
+pub mod rate_history;
+pub mod rate_stream;
+pub mod reconciliation;
+pub mod remittance;
 pub mod reputation;
+pub mod review;
+pub mod risk;
+pub mod screening;
+pub mod token;
 pub mod transaction;
-pub mod wallet;
\ No newline at end of file
+pub mod wallet;
+pub mod webhook;
\ No newline at end of file
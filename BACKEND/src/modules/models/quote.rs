@@ -0,0 +1,166 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::modules::models::amount::{Amount, FixedPoint, Rate};
+use crate::modules::models::token::Token;
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Quote {
+    pub id: String,
+    pub context: String,
+    pub sell_asset: String,
+    pub sell_amount: Amount,
+    pub buy_asset: String,
+    pub buy_amount: Amount,
+    pub price: Rate,
+    pub total_price: Rate,
+    pub fee_total: Amount,
+    pub fee_asset: String,
+    pub usd_amount: Amount,
+    pub rate_source: String,
+    pub rate_fallback: bool,
+    pub rate_stale: bool,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub used_at: Option<DateTime<Utc>>,
+    pub used_by: Option<String>,
+    /// Public key of the wallet the quote was issued to.
+    pub requested_by: Option<String>,
+}
+
+/// SEP-38 `POST /quote` request. Exactly one of `sell_amount` and
+/// `buy_amount` must be set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuoteRequest {
+    pub sell_asset: String,
+    pub buy_asset: String,
+    pub sell_amount: Option<Amount>,
+    pub buy_amount: Option<Amount>,
+    pub context: String,
+    pub expire_after: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuoteFee {
+    pub total: String,
+    pub asset: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuoteResponse {
+    pub id: String,
+    pub expires_at: DateTime<Utc>,
+    pub total_price: Rate,
+    pub price: Rate,
+    pub sell_asset: String,
+    pub sell_amount: String,
+    pub buy_asset: String,
+    pub buy_amount: String,
+    pub fee: QuoteFee,
+}
+
+/// Asset identifier in SEP-38 format: `stellar:native`,
+/// `stellar:CODE:ISSUER` or `iso4217:CCY`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum QuoteAsset {
+    Stellar { code: String, issuer: Option<String> },
+    Fiat(String),
+}
+
+impl QuoteAsset {
+    pub fn parse(asset: &str) -> Option<Self> {
+        let parts: Vec<&str> = asset.split(':').collect();
+        match parts.as_slice() {
+            ["stellar", "native"] => Some(QuoteAsset::Stellar {
+                code: "XLM".to_string(),
+                issuer: None,
+            }),
+            ["stellar", code, issuer] if !code.is_empty() && !issuer.is_empty() => {
+                Some(QuoteAsset::Stellar {
+                    code: code.to_uppercase(),
+                    issuer: Some(issuer.to_string()),
+                })
+            }
+            ["iso4217", code] if code.len() == 3 => Some(QuoteAsset::Fiat(code.to_uppercase())),
+            _ => None,
+        }
+    }
+
+    pub fn from_token(token: &Token) -> Option<Self> {
+        token.stellar_code.as_ref().map(|code| QuoteAsset::Stellar {
+            code: code.to_uppercase(),
+            issuer: token.stellar_issuer.clone(),
+        })
+    }
+
+    /// Issuer of a credit Stellar asset; `None` for XLM and fiat.
+    pub fn issuer(&self) -> Option<&str> {
+        match self {
+            QuoteAsset::Stellar { issuer, .. } => issuer.as_deref(),
+            QuoteAsset::Fiat(_) => None,
+        }
+    }
+
+    /// Lowercase symbol as understood by the price oracle.
+    pub fn symbol(&self) -> String {
+        match self {
+            QuoteAsset::Stellar { code, .. } => code.to_lowercase(),
+            QuoteAsset::Fiat(code) => code.to_lowercase(),
+        }
+    }
+
+    pub fn decimals(&self) -> u32 {
+        match self {
+            QuoteAsset::Stellar { .. } => 7,
+            QuoteAsset::Fiat(_) => 2,
+        }
+    }
+
+    pub fn is_usd(&self) -> bool {
+        matches!(self.symbol().as_str(), "usd" | "usdc")
+    }
+
+    pub fn format_amount(&self, amount: Amount) -> String {
+        amount.format_with(self.decimals())
+    }
+}
+
+impl std::fmt::Display for QuoteAsset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QuoteAsset::Stellar { issuer: None, .. } => write!(f, "stellar:native"),
+            QuoteAsset::Stellar { code, issuer: Some(issuer) } => write!(f, "stellar:{}:{}", code, issuer),
+            QuoteAsset::Fiat(code) => write!(f, "iso4217:{}", code),
+        }
+    }
+}
+
+impl Quote {
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at <= now
+    }
+}
+
+impl From<&Quote> for QuoteResponse {
+    fn from(quote: &Quote) -> Self {
+        let format = |asset: &str, amount: Amount| match QuoteAsset::parse(asset) {
+            Some(parsed) => parsed.format_amount(amount),
+            None => amount.to_string(),
+        };
+
+        QuoteResponse {
+            id: quote.id.clone(),
+            expires_at: quote.expires_at,
+            total_price: quote.total_price,
+            price: quote.price,
+            sell_asset: quote.sell_asset.clone(),
+            sell_amount: format(&quote.sell_asset, quote.sell_amount),
+            buy_asset: quote.buy_asset.clone(),
+            buy_amount: format(&quote.buy_asset, quote.buy_amount),
+            fee: QuoteFee {
+                total: format(&quote.fee_asset, quote.fee_total),
+                asset: quote.fee_asset.clone(),
+            },
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,91 @@
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::modules::models::amount::Rate;
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct RateSnapshot {
+    pub id: String,
+    pub base: String,
+    pub quote: String,
+    pub rate: Rate,
+    pub source: String,
+    pub observed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct RateCandle {
+    pub base: String,
+    pub quote: String,
+    pub interval: String,
+    pub bucket_start: DateTime<Utc>,
+    pub open: Rate,
+    pub high: Rate,
+    pub low: Rate,
+    pub close: Rate,
+    pub samples: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CandleInterval {
+    Minute,
+    Hour,
+    Day,
+}
+
+impl CandleInterval {
+    pub const ALL: [CandleInterval; 3] = [CandleInterval::Minute, CandleInterval::Hour, CandleInterval::Day];
+
+    pub fn parse(interval: &str) -> Option<Self> {
+        match interval {
+            "1m" => Some(CandleInterval::Minute),
+            "1h" => Some(CandleInterval::Hour),
+            "1d" => Some(CandleInterval::Day),
+            _ => None,
+        }
+    }
+
+    pub fn duration(&self) -> Duration {
+        match self {
+            CandleInterval::Minute => Duration::minutes(1),
+            CandleInterval::Hour => Duration::hours(1),
+            CandleInterval::Day => Duration::days(1),
+        }
+    }
+
+    pub fn bucket_start(&self, at: DateTime<Utc>) -> DateTime<Utc> {
+        at.duration_trunc(self.duration()).unwrap_or(at)
+    }
+}
+
+impl std::fmt::Display for CandleInterval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CandleInterval::Minute => write!(f, "1m"),
+            CandleInterval::Hour => write!(f, "1h"),
+            CandleInterval::Day => write!(f, "1d"),
+        }
+    }
+}
+
+/// `interval` is `1m`, `1h`, `1d` or `raw` for the individual snapshots.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateHistoryQuery {
+    pub from: String,
+    pub to: String,
+    pub interval: Option<String>,
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateHistoryResponse {
+    pub from: String,
+    pub to: String,
+    pub interval: String,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub candles: Vec<RateCandle>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub snapshots: Vec<RateSnapshot>,
+}
\ No newline at end of file
@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+
+use crate::modules::models::convert::ConversionLeg;
+
+/// Pairs to subscribe to on connect, comma separated (`xlm/usd,usd/mxn`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateStreamQuery {
+    pub pairs: Option<String>,
+}
+
+/// Messages clients send over `/api/rates/stream`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum RateStreamRequest {
+    Subscribe { pairs: Vec<String> },
+    Unsubscribe { pairs: Vec<String> },
+}
+
+/// Messages pushed to clients.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RateStreamMessage {
+    Rate(ConversionLeg),
+    Subscribed { pairs: Vec<String> },
+    Error { pair: Option<String>, message: String },
+}
+
+impl RateStreamMessage {
+    /// Pair the message is about, as `from/to` in lowercase.
+    pub fn pair(&self) -> Option<String> {
+        match self {
+            RateStreamMessage::Rate(leg) => Some(format!("{}/{}", leg.from, leg.to).to_lowercase()),
+            RateStreamMessage::Error { pair, .. } => pair.clone(),
+            RateStreamMessage::Subscribed { .. } => None,
+        }
+    }
+}
\ No newline at end of file
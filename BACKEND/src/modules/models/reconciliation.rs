@@ -0,0 +1,451 @@
+use std::collections::{BTreeMap, HashMap};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::FieldError;
+use crate::modules::models::amount::FiatAmount;
+use crate::modules::models::bank::{BankTransfer, TransferStatus};
+
+/// How a completed transfer, or a settlement row, compares with the other side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReconciliationStatus {
+    /// One settlement row with the transfer's reference, amount and currency.
+    Matched,
+    /// Completed transfer no settlement file mentions.
+    Missing,
+    /// Completed transfer whose reference appears in more than one row.
+    Duplicate,
+    /// Settled under the transfer's reference for a different amount or currency.
+    AmountMismatch,
+    /// Settlement row without a completed transfer behind it.
+    Unmatched,
+    /// Reversed transfer a settlement file still reports as paid out.
+    ReversedSettled,
+}
+
+impl ReconciliationStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReconciliationStatus::Matched => "matched",
+            ReconciliationStatus::Missing => "missing",
+            ReconciliationStatus::Duplicate => "duplicate",
+            ReconciliationStatus::AmountMismatch => "amount_mismatch",
+            ReconciliationStatus::Unmatched => "unmatched",
+            ReconciliationStatus::ReversedSettled => "reversed_settled",
+        }
+    }
+}
+
+impl std::fmt::Display for ReconciliationStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct SettlementStatement {
+    pub id: String,
+    pub provider: String,
+    pub filename: Option<String>,
+    pub checksum: String,
+    pub row_count: i64,
+    pub imported_at: DateTime<Utc>,
+}
+
+/// One payout a provider reports as settled.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct SettlementRow {
+    pub id: String,
+    pub statement_id: String,
+    pub provider: String,
+    /// Line in the file, header included.
+    pub line: i64,
+    /// Provider's payout reference, as stored in `bank_transfers.provider_reference`.
+    pub reference: String,
+    pub amount: FiatAmount,
+    pub currency: String,
+    /// As written in the file.
+    pub settled_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ReconciliationRun {
+    pub id: String,
+    pub provider: String,
+    pub statement_id: Option<String>,
+    pub matched: i64,
+    pub missing: i64,
+    pub duplicate: i64,
+    pub amount_mismatch: i64,
+    pub unmatched: i64,
+    pub reversed_settled: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ReconciliationItem {
+    pub id: String,
+    pub run_id: String,
+    pub status: String,
+    pub transfer_id: Option<String>,
+    pub reference: Option<String>,
+    pub expected_amount: Option<FiatAmount>,
+    pub settled_amount: Option<FiatAmount>,
+    pub currency: Option<String>,
+    pub detail: String,
+}
+
+/// A statement line before it is stored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatementLine {
+    pub line: i64,
+    pub reference: String,
+    pub amount: FiatAmount,
+    pub currency: String,
+    pub settled_at: Option<String>,
+}
+
+/// Outcome for one transfer or settlement reference, before it is stored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReconciliationFinding {
+    pub status: ReconciliationStatus,
+    pub transfer_id: Option<String>,
+    pub reference: Option<String>,
+    pub expected_amount: Option<FiatAmount>,
+    pub settled_amount: Option<FiatAmount>,
+    pub currency: Option<String>,
+    pub detail: String,
+}
+
+/// Settlement CSV with a header row naming at least `reference`, `amount` and
+/// `currency`; `settled_at` is optional and other columns are ignored.
+pub fn parse_statement(contents: &str) -> Result<Vec<StatementLine>, Vec<FieldError>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(contents.as_bytes());
+
+    let headers = reader
+        .headers()
+        .map_err(|e| vec![FieldError::new("file", "invalid", format!("Unreadable CSV header: {}", e))])?
+        .clone();
+    let column = |name: &str| headers.iter().position(|header| header.eq_ignore_ascii_case(name));
+
+    let (Some(reference), Some(amount), Some(currency)) = (column("reference"), column("amount"), column("currency")) else {
+        return Err(vec![FieldError::new(
+            "file",
+            "missing_columns",
+            "The header must name the reference, amount and currency columns",
+        )]);
+    };
+    let settled_at = column("settled_at");
+
+    let mut lines = Vec::new();
+    let mut errors = Vec::new();
+    for (index, record) in reader.records().enumerate() {
+        // Line 1 is the header.
+        let line = index as i64 + 2;
+        let field = format!("line {}", line);
+
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                errors.push(FieldError::new(&field, "invalid", e.to_string()));
+                continue;
+            }
+        };
+        let value = |index: usize| record.get(index).map(str::trim).filter(|value| !value.is_empty());
+
+        let Some(reference) = value(reference) else {
+            errors.push(FieldError::new(&field, "missing_reference", "Reference is empty"));
+            continue;
+        };
+        let amount = match value(amount).map(str::parse::<FiatAmount>) {
+            Some(Ok(amount)) => amount,
+            _ => {
+                errors.push(FieldError::new(&field, "invalid_amount", "Amount must be a positive decimal"));
+                continue;
+            }
+        };
+        let Some(currency) = value(currency).filter(|c| c.len() == 3) else {
+            errors.push(FieldError::new(&field, "invalid_currency", "Currency must be an ISO 4217 code"));
+            continue;
+        };
+
+        lines.push(StatementLine {
+            line,
+            reference: reference.to_string(),
+            amount,
+            currency: currency.to_uppercase(),
+            settled_at: settled_at.and_then(value).map(str::to_string),
+        });
+    }
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    if lines.is_empty() {
+        return Err(vec![FieldError::new("file", "empty", "The statement has no rows")]);
+    }
+    Ok(lines)
+}
+
+/// Compares the transfers routed to a provider with every row its settlement
+/// files contain, matching by provider reference. A payout repeated in a later,
+/// overlapping file (same reference, amount and `settled_at`) counts once.
+/// Completed transfers give one finding each, reversed ones only when still
+/// settled; rows left over are `Unmatched`.
+pub fn reconcile(transfers: &[BankTransfer], rows: &[SettlementRow]) -> Vec<ReconciliationFinding> {
+    let mut by_reference: BTreeMap<&str, Vec<&SettlementRow>> = BTreeMap::new();
+    for row in dedupe_across_statements(rows) {
+        by_reference.entry(row.reference.as_str()).or_default().push(row);
+    }
+    let transfers_by_reference: HashMap<&str, &BankTransfer> = transfers
+        .iter()
+        .filter_map(|transfer| transfer.provider_reference.as_deref().map(|reference| (reference, transfer)))
+        .collect();
+
+    let mut findings = Vec::new();
+    for transfer in transfers.iter().filter(|t| t.transfer_status() == Some(TransferStatus::Completed)) {
+        let reference = transfer.provider_reference.as_deref();
+        let matching = reference.and_then(|reference| by_reference.remove(reference)).unwrap_or_default();
+
+        let finding = |status, settled_amount, detail: String| ReconciliationFinding {
+            status,
+            transfer_id: Some(transfer.id.clone()),
+            reference: reference.map(str::to_string),
+            expected_amount: Some(transfer.amount_fiat),
+            settled_amount,
+            currency: Some(transfer.currency.to_uppercase()),
+            detail,
+        };
+
+        findings.push(match matching.as_slice() {
+            [] if reference.is_none() => {
+                finding(ReconciliationStatus::Missing, None, "Completed without a provider reference".to_string())
+            }
+            [] => finding(ReconciliationStatus::Missing, None, "Not in any settlement file".to_string()),
+            [row] if row.amount == transfer.amount_fiat && row.currency.eq_ignore_ascii_case(&transfer.currency) => {
+                finding(ReconciliationStatus::Matched, Some(row.amount), format!("Settlement line {}", row.line))
+            }
+            [row] => finding(
+                ReconciliationStatus::AmountMismatch,
+                Some(row.amount),
+                format!(
+                    "Expected {} {}, settled {} {} (line {})",
+                    transfer.amount_fiat,
+                    transfer.currency.to_uppercase(),
+                    row.amount,
+                    row.currency,
+                    row.line
+                ),
+            ),
+            rows => finding(
+                ReconciliationStatus::Duplicate,
+                Some(rows[0].amount),
+                format!("Settled {} times (lines {})", rows.len(), lines(rows)),
+            ),
+        });
+    }
+
+    for transfer in transfers.iter().filter(|t| t.transfer_status() == Some(TransferStatus::Reversed)) {
+        let Some(reference) = transfer.provider_reference.as_deref() else { continue };
+        let Some(rows) = by_reference.remove(reference) else { continue };
+
+        findings.push(ReconciliationFinding {
+            status: ReconciliationStatus::ReversedSettled,
+            transfer_id: Some(transfer.id.clone()),
+            reference: Some(reference.to_string()),
+            expected_amount: Some(transfer.amount_fiat),
+            settled_amount: Some(rows[0].amount),
+            currency: Some(rows[0].currency.clone()),
+            detail: format!("Reversed, but settled (lines {})", lines(&rows)),
+        });
+    }
+
+    for (reference, rows) in by_reference {
+        let detail = match transfers_by_reference.get(reference) {
+            Some(transfer) => format!("Transfer {} is {}", transfer.id, transfer.status),
+            None => "No transfer with this reference".to_string(),
+        };
+        findings.push(ReconciliationFinding {
+            status: ReconciliationStatus::Unmatched,
+            transfer_id: transfers_by_reference.get(reference).map(|transfer| transfer.id.clone()),
+            reference: Some(reference.to_string()),
+            expected_amount: None,
+            settled_amount: Some(rows[0].amount),
+            currency: Some(rows[0].currency.clone()),
+            detail: format!("{} (lines {})", detail, lines(&rows)),
+        });
+    }
+
+    findings
+}
+
+/// Drops rows another statement already reported with the same reference,
+/// amount, currency and `settled_at`. Repeats within one statement are kept,
+/// so they still show as duplicates.
+fn dedupe_across_statements(rows: &[SettlementRow]) -> Vec<&SettlementRow> {
+    let mut first_statement: HashMap<(&str, FiatAmount, &str, Option<&str>), &str> = HashMap::new();
+    rows.iter()
+        .filter(|row| {
+            let key = (row.reference.as_str(), row.amount, row.currency.as_str(), row.settled_at.as_deref());
+            *first_statement.entry(key).or_insert(row.statement_id.as_str()) == row.statement_id
+        })
+        .collect()
+}
+
+fn lines(rows: &[&SettlementRow]) -> String {
+    rows.iter().map(|row| row.line.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ImportStatementQuery {
+    pub provider: String,
+    pub filename: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReconciliationRunRequest {
+    pub provider: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReconciliationRunsQuery {
+    pub provider: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReconciliationReport {
+    #[serde(flatten)]
+    pub run: ReconciliationRun,
+    pub items: Vec<ReconciliationItem>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReconciliationRunListResponse {
+    pub runs: Vec<ReconciliationRun>,
+    pub total: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transfer(id: &str, status: TransferStatus, reference: Option<&str>, amount: &str) -> BankTransfer {
+        BankTransfer {
+            id: id.to_string(),
+            wallet_id: "w".to_string(),
+            public_key: "G".to_string(),
+            amount_fiat: amount.parse().unwrap(),
+            currency: "mxn".to_string(),
+            bank_account_masked: "****1234".to_string(),
+            status: status.to_string(),
+            rejection_reason: None,
+            reputation_score: None,
+            quote_id: None,
+            provider_reference: reference.map(str::to_string),
+            payout_provider: Some("spei".to_string()),
+            payout_destination: None,
+            beneficiary_id: None,
+            created_at: Utc::now(),
+            completed_at: None,
+        }
+    }
+
+    fn rows(csv: &str) -> Vec<SettlementRow> {
+        parse_statement(csv)
+            .unwrap()
+            .into_iter()
+            .map(|line| SettlementRow {
+                id: line.line.to_string(),
+                statement_id: "s".to_string(),
+                provider: "spei".to_string(),
+                line: line.line,
+                reference: line.reference,
+                amount: line.amount,
+                currency: line.currency,
+                settled_at: line.settled_at,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_statement() {
+        let lines = parse_statement("Reference,Amount,Currency,Settled_At\nref-1, 100.50 ,mxn,2024-05-01\n").unwrap();
+        assert_eq!(lines[0].line, 2);
+        assert_eq!(lines[0].amount.to_string(), "100.50");
+        assert_eq!(lines[0].currency, "MXN");
+        assert_eq!(lines[0].settled_at.as_deref(), Some("2024-05-01"));
+
+        let errors = parse_statement("reference,amount,currency\nref-1,abc,MXN\n,5,MXN\n").unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].field, "line 2");
+        assert!(parse_statement("id,amount\n1,2\n").is_err());
+    }
+
+    #[test]
+    fn test_reconcile() {
+        let transfers = vec![
+            transfer("matched", TransferStatus::Completed, Some("r1"), "100.00"),
+            transfer("mismatch", TransferStatus::Completed, Some("r2"), "200.00"),
+            transfer("duplicate", TransferStatus::Completed, Some("r3"), "300.00"),
+            transfer("missing", TransferStatus::Completed, Some("r4"), "400.00"),
+            transfer("failed", TransferStatus::Failed, Some("r5"), "500.00"),
+        ];
+        let rows = rows("reference,amount,currency\nr1,100,MXN\nr2,199.99,MXN\nr3,300,MXN\nr3,300,MXN\nr5,500,MXN\nr9,1,MXN\n");
+
+        let findings = reconcile(&transfers, &rows);
+        let status_of = |reference: &str| {
+            findings.iter().find(|f| f.reference.as_deref() == Some(reference)).map(|f| f.status)
+        };
+
+        assert_eq!(status_of("r1"), Some(ReconciliationStatus::Matched));
+        assert_eq!(status_of("r2"), Some(ReconciliationStatus::AmountMismatch));
+        assert_eq!(status_of("r3"), Some(ReconciliationStatus::Duplicate));
+        assert_eq!(status_of("r4"), Some(ReconciliationStatus::Missing));
+        assert_eq!(status_of("r5"), Some(ReconciliationStatus::Unmatched));
+        assert_eq!(status_of("r9"), Some(ReconciliationStatus::Unmatched));
+        assert_eq!(findings.len(), 6);
+    }
+
+    #[test]
+    fn test_reconcile_flags_reversed_transfers_still_settled() {
+        let transfers = vec![
+            transfer("reversed", TransferStatus::Reversed, Some("r1"), "100.00"),
+            transfer("returned", TransferStatus::Reversed, Some("r2"), "200.00"),
+        ];
+        let rows = rows("reference,amount,currency\nr1,100,MXN\n");
+
+        let findings = reconcile(&transfers, &rows);
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].status, ReconciliationStatus::ReversedSettled);
+        assert_eq!(findings[0].transfer_id.as_deref(), Some("reversed"));
+    }
+
+    #[test]
+    fn test_reconcile_counts_overlapping_statements_once() {
+        let transfers = vec![
+            transfer("overlap", TransferStatus::Completed, Some("r1"), "100.00"),
+            transfer("repeated", TransferStatus::Completed, Some("r2"), "200.00"),
+        ];
+        let mut rows = rows(
+            "reference,amount,currency,settled_at\nr1,100,MXN,2024-05-01\nr2,200,MXN,2024-05-01\nr2,200,MXN,2024-05-01\n",
+        );
+        let mut later = rows[0].clone();
+        later.statement_id = "s2".to_string();
+        later.line = 9;
+        rows.push(later);
+
+        let findings = reconcile(&transfers, &rows);
+        let status_of = |reference: &str| {
+            findings.iter().find(|f| f.reference.as_deref() == Some(reference)).map(|f| f.status)
+        };
+
+        assert_eq!(status_of("r1"), Some(ReconciliationStatus::Matched));
+        assert_eq!(status_of("r2"), Some(ReconciliationStatus::Duplicate));
+        assert_eq!(findings.len(), 2);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,248 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::modules::models::amount::{Amount, FiatAmount};
+use crate::modules::models::bank::TransferStatus;
+
+/// Where a remittance is in its saga. A failure after funds were escrowed
+/// goes through `Compensating` before ending as `Failed`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RemittanceStatus {
+    Quoted,
+    /// Source funds escrowed in a bank transfer held for the conversion, or
+    /// for its review.
+    Escrowed,
+    /// Claimed for the DEX conversion, which is under way.
+    Converting,
+    /// Escrow converted on the DEX; the transfer is in the payout pipeline.
+    Converted,
+    PayingOut,
+    Completed,
+    /// Unwinding the conversion and checking the escrow was refunded.
+    Compensating,
+    Failed,
+}
+
+impl RemittanceStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RemittanceStatus::Quoted => "quoted",
+            RemittanceStatus::Escrowed => "escrowed",
+            RemittanceStatus::Converting => "converting",
+            RemittanceStatus::Converted => "converted",
+            RemittanceStatus::PayingOut => "paying_out",
+            RemittanceStatus::Completed => "completed",
+            RemittanceStatus::Compensating => "compensating",
+            RemittanceStatus::Failed => "failed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "quoted" => Some(RemittanceStatus::Quoted),
+            "escrowed" => Some(RemittanceStatus::Escrowed),
+            "converting" => Some(RemittanceStatus::Converting),
+            "converted" => Some(RemittanceStatus::Converted),
+            "paying_out" => Some(RemittanceStatus::PayingOut),
+            "completed" => Some(RemittanceStatus::Completed),
+            "compensating" => Some(RemittanceStatus::Compensating),
+            "failed" => Some(RemittanceStatus::Failed),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for RemittanceStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SagaStep {
+    Quote,
+    /// Bank transfer created with the source funds in escrow.
+    Escrow,
+    Convert,
+    Payout,
+    /// Provider confirmed the payout and the escrow was settled.
+    Settle,
+    /// Compensation: the DEX conversion swapped back.
+    UnwindConvert,
+    /// Compensation: the escrow returned to the wallet.
+    Refund,
+}
+
+impl SagaStep {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SagaStep::Quote => "quote",
+            SagaStep::Escrow => "escrow",
+            SagaStep::Convert => "convert",
+            SagaStep::Payout => "payout",
+            SagaStep::Settle => "settle",
+            SagaStep::UnwindConvert => "unwind_convert",
+            SagaStep::Refund => "refund",
+        }
+    }
+}
+
+impl std::fmt::Display for SagaStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// How the saga reads its bank transfer's status once the escrow is converted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferOutcome {
+    Waiting,
+    PayingOut,
+    Settled,
+    Failed,
+}
+
+impl TransferOutcome {
+    pub fn of(status: TransferStatus) -> Self {
+        use TransferStatus::*;
+
+        match status {
+            Review | Converting | Pending => TransferOutcome::Waiting,
+            Processing | Sent => TransferOutcome::PayingOut,
+            Completed => TransferOutcome::Settled,
+            Failed | Reversed | Cancelled | Rejected => TransferOutcome::Failed,
+        }
+    }
+}
+
+/// What an escrowed remittance does, from its bank transfer's status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConversionAction {
+    /// Held in review; converted once approved.
+    Wait,
+    Convert,
+    /// Failed, or released to the payout queue without the conversion.
+    Abort,
+}
+
+impl ConversionAction {
+    pub fn of(status: TransferStatus) -> Self {
+        match status {
+            TransferStatus::Review => ConversionAction::Wait,
+            TransferStatus::Converting => ConversionAction::Convert,
+            _ => ConversionAction::Abort,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Remittance {
+    pub id: String,
+    pub wallet_id: String,
+    pub public_key: String,
+    pub amount_fiat: FiatAmount,
+    pub currency: String,
+    pub source_asset: String,
+    /// Escrowed from the wallet, fee included.
+    pub source_amount: Option<Amount>,
+    pub dex_asset: String,
+    /// Received for the escrow on the DEX.
+    pub dex_amount: Option<Amount>,
+    pub quote_id: Option<String>,
+    pub transfer_id: Option<String>,
+    pub status: String,
+    pub failure_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl Remittance {
+    pub fn remittance_status(&self) -> Option<RemittanceStatus> {
+        RemittanceStatus::parse(&self.status)
+    }
+}
+
+/// One entry of a remittance's timeline.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct RemittanceStep {
+    pub id: String,
+    pub remittance_id: String,
+    pub step: String,
+    /// `completed` or `failed`.
+    pub status: String,
+    pub detail: String,
+    pub tx_hash: Option<String>,
+    /// Not submitted to the network; `tx_hash` does not exist on it.
+    pub simulated: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateRemittanceRequest {
+    pub public_key: String,
+    /// Delivered to the beneficiary, in the remittance currency.
+    pub amount_fiat: FiatAmount,
+    /// Required unless `beneficiary_id` is given.
+    #[serde(default)]
+    pub bank_account: String,
+    pub beneficiary_id: Option<String>,
+    pub beneficiary_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RemittanceListQuery {
+    /// Defaults to the signing wallet, the only one that can be listed.
+    pub public_key: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RemittanceResponse {
+    #[serde(flatten)]
+    pub remittance: Remittance,
+    /// Status of the bank transfer paying it out, if one was created.
+    pub transfer_status: Option<String>,
+    pub timeline: Vec<RemittanceStep>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RemittanceListResponse {
+    pub remittances: Vec<Remittance>,
+    pub total: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remittance_status_roundtrip() {
+        use RemittanceStatus::*;
+
+        for status in [Quoted, Escrowed, Converting, Converted, PayingOut, Completed, Compensating, Failed] {
+            assert_eq!(RemittanceStatus::parse(status.as_str()), Some(status));
+        }
+        assert_eq!(RemittanceStatus::parse("settled"), None);
+    }
+
+    #[test]
+    fn test_transfer_outcome() {
+        assert_eq!(TransferOutcome::of(TransferStatus::Converting), TransferOutcome::Waiting);
+        assert_eq!(TransferOutcome::of(TransferStatus::Review), TransferOutcome::Waiting);
+        assert_eq!(TransferOutcome::of(TransferStatus::Sent), TransferOutcome::PayingOut);
+        assert_eq!(TransferOutcome::of(TransferStatus::Completed), TransferOutcome::Settled);
+        assert_eq!(TransferOutcome::of(TransferStatus::Reversed), TransferOutcome::Failed);
+        assert_eq!(TransferOutcome::of(TransferStatus::Cancelled), TransferOutcome::Failed);
+    }
+
+    #[test]
+    fn test_conversion_waits_for_review() {
+        assert_eq!(ConversionAction::of(TransferStatus::Review), ConversionAction::Wait);
+        assert_eq!(ConversionAction::of(TransferStatus::Converting), ConversionAction::Convert);
+        assert_eq!(ConversionAction::of(TransferStatus::Pending), ConversionAction::Abort);
+        assert_eq!(ConversionAction::of(TransferStatus::Rejected), ConversionAction::Abort);
+        assert_eq!(ConversionAction::of(TransferStatus::Cancelled), ConversionAction::Abort);
+    }
+}
\ No newline at end of file
@@ -1,13 +1,17 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::modules::models::amount::Amount;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Reputation {
     pub public_key: String,
     pub trust_score: u8,
     pub level: String,
     pub tx_count: u32,
-    pub total_volume: f64,
+    pub total_volume: Amount,
+    /// Bank transfers reversed after payout; each one lowers the score.
+    pub reversed_transfers: u32,
     pub last_calculated: DateTime<Utc>,
 }
 
@@ -22,7 +26,8 @@ pub struct ReputationResponse {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReputationDetails {
     pub tx_count: u32,
-    pub total_volume: f64,
+    pub total_volume: Amount,
+    pub reversed_transfers: u32,
     pub account_age_days: i64,
     pub last_activity: Option<DateTime<Utc>>,
 }
\ No newline at end of file
@@ -0,0 +1,128 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::modules::models::amount::Amount;
+use crate::modules::models::bank::BankTransfer;
+
+/// Where a held transfer is in the manual review queue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewStatus {
+    Open,
+    /// Approved once; the amount needs a second, different reviewer.
+    AwaitingSecondApproval,
+    Approved,
+    Rejected,
+    /// The transfer was cancelled before anyone decided.
+    Cancelled,
+}
+
+impl ReviewStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReviewStatus::Open => "open",
+            ReviewStatus::AwaitingSecondApproval => "awaiting_second_approval",
+            ReviewStatus::Approved => "approved",
+            ReviewStatus::Rejected => "rejected",
+            ReviewStatus::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "open" => Some(ReviewStatus::Open),
+            "awaiting_second_approval" => Some(ReviewStatus::AwaitingSecondApproval),
+            "approved" => Some(ReviewStatus::Approved),
+            "rejected" => Some(ReviewStatus::Rejected),
+            "cancelled" => Some(ReviewStatus::Cancelled),
+            _ => None,
+        }
+    }
+
+    pub fn is_decided(&self) -> bool {
+        matches!(self, ReviewStatus::Approved | ReviewStatus::Rejected | ReviewStatus::Cancelled)
+    }
+}
+
+impl std::fmt::Display for ReviewStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct TransferReview {
+    pub id: String,
+    pub transfer_id: String,
+    pub status: String,
+    /// Why the transfer was held.
+    pub reason: String,
+    pub amount_usd: Option<Amount>,
+    pub requires_second_approval: bool,
+    /// Reviewer currently holding the claim.
+    pub assigned_to: Option<String>,
+    /// First time anyone claimed the review.
+    pub claimed_at: Option<DateTime<Utc>>,
+    pub first_approved_by: Option<String>,
+    pub first_approved_at: Option<DateTime<Utc>>,
+    pub decided_by: Option<String>,
+    pub decided_at: Option<DateTime<Utc>>,
+    pub sla_due_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TransferReview {
+    pub fn review_status(&self) -> Option<ReviewStatus> {
+        ReviewStatus::parse(&self.status)
+    }
+
+    /// Still undecided past its SLA.
+    pub fn is_overdue(&self, now: DateTime<Utc>) -> bool {
+        !self.review_status().is_some_and(|status| status.is_decided()) && now > self.sla_due_at
+    }
+}
+
+/// One reviewer action on a review, with its optional note.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ReviewNote {
+    pub id: String,
+    pub review_id: String,
+    pub reviewer: String,
+    pub action: String,
+    pub note: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReviewListQuery {
+    /// One review status; open and awaiting second approval when omitted.
+    pub status: Option<String>,
+}
+
+/// The reviewer is the authenticated operator.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReviewDecisionRequest {
+    /// Required when rejecting.
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReviewSummary {
+    #[serde(flatten)]
+    pub review: TransferReview,
+    pub overdue: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReviewListResponse {
+    pub reviews: Vec<ReviewSummary>,
+    pub total: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReviewDetailResponse {
+    #[serde(flatten)]
+    pub review: ReviewSummary,
+    pub transfer: BankTransfer,
+    pub notes: Vec<ReviewNote>,
+}
\ No newline at end of file
@@ -0,0 +1,221 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::modules::models::amount::{Amount, FixedPoint, Rate, Rounding};
+
+/// What a rule, or a whole assessment, decides. Ordered from least to most
+/// severe so the strictest fired rule wins.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskAction {
+    Allow,
+    Review,
+    Block,
+}
+
+impl RiskAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RiskAction::Allow => "allow",
+            RiskAction::Review => "review",
+            RiskAction::Block => "block",
+        }
+    }
+}
+
+impl std::fmt::Display for RiskAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskOperation {
+    BankTransfer,
+    Send,
+}
+
+impl RiskOperation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RiskOperation::BankTransfer => "bank_transfer",
+            RiskOperation::Send => "send",
+        }
+    }
+}
+
+/// What a velocity rule counts operations by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VelocityScope {
+    Wallet,
+    BankAccount,
+    Ip,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RiskRuleKind {
+    /// More than `max_count` operations, or more than `max_amount_usd` in
+    /// total, within the window, counting the one being assessed.
+    Velocity {
+        scope: VelocityScope,
+        window_seconds: i64,
+        #[serde(default)]
+        max_count: Option<usize>,
+        #[serde(default)]
+        max_amount_usd: Option<Amount>,
+    },
+    /// An amount above `multiplier` times the wallet's average over the
+    /// lookback, once it has at least `min_history` operations.
+    AmountSpike {
+        multiplier: Rate,
+        min_history: usize,
+        lookback_days: i64,
+    },
+    /// The first outgoing operation within `cooldown_seconds` of funding.
+    FundingCooldown { cooldown_seconds: i64 },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskRule {
+    pub id: String,
+    /// Operations the rule applies to; all of them when empty.
+    #[serde(default)]
+    pub operations: Vec<RiskOperation>,
+    pub action: RiskAction,
+    #[serde(flatten)]
+    pub kind: RiskRuleKind,
+}
+
+impl RiskRule {
+    pub fn applies_to(&self, operation: RiskOperation) -> bool {
+        self.operations.is_empty() || self.operations.contains(&operation)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RiskRuleSet {
+    pub rules: Vec<RiskRule>,
+}
+
+/// The operation being assessed.
+#[derive(Debug, Clone)]
+pub struct RiskSubject {
+    pub operation: RiskOperation,
+    pub wallet_id: String,
+    pub public_key: String,
+    /// Bank transfer id, or the transaction hash once a send is recorded.
+    pub reference: Option<String>,
+    /// `None` when the asset could not be priced; amount rules are skipped.
+    pub amount_usd: Option<Amount>,
+    pub bank_account_hash: Option<String>,
+    pub ip_address: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FiredRule {
+    pub rule_id: String,
+    pub action: RiskAction,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct RiskAssessment {
+    pub decision_id: String,
+    pub action: RiskAction,
+    pub fired: Vec<FiredRule>,
+}
+
+impl RiskAssessment {
+    pub fn rule_ids(&self) -> String {
+        self.fired.iter().map(|rule| rule.rule_id.as_str()).collect::<Vec<_>>().join(", ")
+    }
+
+    /// Fired rules with their reasons, for people reviewing the operation.
+    pub fn describe(&self) -> String {
+        self.fired
+            .iter()
+            .map(|rule| format!("{} ({})", rule.rule_id, rule.reason))
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+}
+
+/// A stored assessment; `rules` is the JSON list of fired rules.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct RiskDecision {
+    pub id: String,
+    pub operation: String,
+    pub wallet_id: String,
+    pub public_key: String,
+    pub reference: Option<String>,
+    pub amount_usd: Option<Amount>,
+    pub bank_account_hash: Option<String>,
+    pub ip_address: Option<String>,
+    pub action: String,
+    pub rules: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A past, non-blocked operation counted by velocity and spike rules.
+#[derive(Debug, Clone)]
+pub struct RiskHistoryEntry {
+    pub operation: String,
+    pub amount_usd: Option<Amount>,
+}
+
+/// The strictest action among the fired rules.
+pub fn combine(fired: &[FiredRule]) -> RiskAction {
+    fired.iter().map(|rule| rule.action).max().unwrap_or(RiskAction::Allow)
+}
+
+/// The average of `history` when `amount` exceeds `multiplier` times it.
+pub fn spike_average(amount: Amount, history: &[Amount], multiplier: Rate) -> Option<Amount> {
+    if history.is_empty() {
+        return None;
+    }
+    let total = history.iter().try_fold(Amount::ZERO, |total, value| total.checked_add(*value)).ok()?;
+    let average = total.scale_by(1, history.len() as i128, Rounding::Down).ok()?;
+    let limit: Amount = multiplier.apply(average, Rounding::Down).ok()?;
+    (amount > limit).then_some(average)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rules() {
+        let json = r#"{"rules": [
+            {"id": "burst", "type": "velocity", "scope": "wallet", "window_seconds": 60, "max_count": 3, "action": "block"},
+            {"id": "spike", "type": "amount_spike", "operations": ["send"], "multiplier": "5",
+             "min_history": 3, "lookback_days": 30, "action": "review"}
+        ]}"#;
+        let set: RiskRuleSet = serde_json::from_str(json).unwrap();
+
+        assert_eq!(set.rules.len(), 2);
+        assert!(matches!(
+            set.rules[0].kind,
+            RiskRuleKind::Velocity { scope: VelocityScope::Wallet, max_count: Some(3), max_amount_usd: None, .. }
+        ));
+        assert!(set.rules[0].applies_to(RiskOperation::BankTransfer));
+        assert!(!set.rules[1].applies_to(RiskOperation::BankTransfer));
+        assert_eq!(set.rules[1].action, RiskAction::Review);
+    }
+
+    #[test]
+    fn test_combine_and_spike() {
+        let fired = |action| FiredRule { rule_id: "r".to_string(), action, reason: String::new() };
+        assert_eq!(combine(&[]), RiskAction::Allow);
+        assert_eq!(combine(&[fired(RiskAction::Review), fired(RiskAction::Block)]), RiskAction::Block);
+
+        let usd = |value: &str| value.parse::<Amount>().unwrap();
+        let five = "5".parse::<Rate>().unwrap();
+        let history = [usd("10"), usd("20"), usd("30")];
+        assert_eq!(spike_average(usd("100"), &history, five), None);
+        assert_eq!(spike_average(usd("100.01"), &history, five), Some(usd("20")));
+        assert_eq!(spike_average(usd("1000"), &[], five), None);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,85 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::modules::models::risk::{RiskAction, RiskOperation};
+use crate::utils::watchlist::MatchType;
+
+/// One loaded version of a sanctions or internal list. The version is the
+/// SHA-256 of the file, so a reload of an unchanged file reuses the row.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ScreeningList {
+    pub id: String,
+    /// `sdn` or `internal`.
+    pub source: String,
+    pub path: String,
+    pub version: String,
+    pub entry_count: i64,
+    pub loaded_at: DateTime<Utc>,
+}
+
+/// The parties of an operation checked against the lists.
+#[derive(Debug, Clone, Serialize)]
+pub struct ScreeningSubject {
+    pub operation: RiskOperation,
+    pub wallet_id: String,
+    pub reference: Option<String>,
+    pub names: Vec<String>,
+    pub stellar_addresses: Vec<String>,
+    pub bank_accounts: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScreeningMatch {
+    pub list_id: String,
+    pub source: String,
+    pub entry_id: String,
+    pub entry_name: Option<String>,
+    pub match_type: MatchType,
+    pub score: f64,
+    pub action: RiskAction,
+}
+
+/// Stored outcome of one screening, with the list versions it ran against.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ScreeningResult {
+    pub id: String,
+    pub operation: String,
+    pub wallet_id: String,
+    pub reference: Option<String>,
+    pub action: String,
+    /// Encrypted JSON of the screened names, addresses and accounts.
+    pub subject: String,
+    /// JSON list of `ScreeningMatch`.
+    pub matches: String,
+    /// JSON list of the `screening_lists` ids used.
+    pub list_ids: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ScreeningOutcome {
+    /// `None` when no lists are configured and nothing was stored.
+    pub result_id: Option<String>,
+    pub action: RiskAction,
+    pub matches: Vec<ScreeningMatch>,
+}
+
+impl ScreeningOutcome {
+    /// e.g. `sdn 306 "DOE, John" (fuzzy_name 0.93)`, for review reasons.
+    pub fn describe(&self) -> String {
+        self.matches
+            .iter()
+            .map(|hit| {
+                format!(
+                    "{} {} \"{}\" ({} {:.2})",
+                    hit.source,
+                    hit.entry_id,
+                    hit.entry_name.as_deref().unwrap_or("-"),
+                    hit.match_type.as_str(),
+                    hit.score
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
\ No newline at end of file
@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+/// Registry entry tying a symbol to its price feed and Stellar asset.
+/// A Stellar code without issuer is the native asset (XLM).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Token {
+    pub symbol: String,
+    pub coingecko_id: Option<String>,
+    pub stellar_code: Option<String>,
+    pub stellar_issuer: Option<String>,
+    /// Decimals clients should display; amounts themselves keep full precision.
+    pub decimals: u32,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl Token {
+    pub fn matches_stellar(&self, code: &str, issuer: Option<&str>) -> bool {
+        self.stellar_code.as_deref().is_some_and(|c| c.eq_ignore_ascii_case(code))
+            && self.stellar_issuer.as_deref() == issuer
+    }
+}
\ No newline at end of file
@@ -1,6 +1,8 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::modules::models::amount::Amount;
+
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Transaction {
     pub id: String,
@@ -9,8 +11,21 @@ pub struct Transaction {
     pub tx_type: String,
     pub from_address: Option<String>,
     pub to_address: Option<String>,
-    pub amount: String,
+    pub amount: Amount,
     pub asset: String,
+    /// `None` for XLM.
+    pub asset_issuer: Option<String>,
+    /// Received leg of a `Convert` transaction.
+    pub dest_amount: Option<Amount>,
+    pub dest_asset: Option<String>,
+    pub dest_asset_issuer: Option<String>,
+    /// Platform fee taken out of `amount`, in `fee_asset`.
+    pub fee_amount: Option<Amount>,
+    pub fee_asset: Option<String>,
+    /// The transaction was never submitted to the network; `tx_hash` is made up.
+    pub simulated: bool,
+    /// Bank transfer an `Escrow` or `Release` transaction belongs to.
+    pub bank_transfer_id: Option<String>,
     pub status: String,
     pub created_at: DateTime<Utc>,
 }
@@ -20,6 +35,10 @@ pub enum TransactionType {
     Send,
     Receive,
     Convert,
+    /// Funds held for a bank transfer until its payout settles.
+    Escrow,
+    /// Escrowed funds returned after a bank transfer failed, was cancelled or reversed.
+    Release,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,6 +54,8 @@ impl std::fmt::Display for TransactionType {
             TransactionType::Send => write!(f, "send"),
             TransactionType::Receive => write!(f, "receive"),
             TransactionType::Convert => write!(f, "convert"),
+            TransactionType::Escrow => write!(f, "escrow"),
+            TransactionType::Release => write!(f, "release"),
         }
     }
 }
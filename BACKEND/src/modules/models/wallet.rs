@@ -1,6 +1,8 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::modules::models::amount::{Amount, Rate};
+
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Wallet {
     pub id: String,
@@ -40,8 +42,10 @@ pub struct FundWalletResponse {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Balance {
     pub asset_code: String,
-    pub balance: String,
+    pub balance: Amount,
     pub asset_issuer: Option<String>,
+    /// Display decimals from the token registry; `None` for unregistered assets.
+    pub decimals: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -54,7 +58,7 @@ pub struct BalanceResponse {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SendTransactionRequest {
     pub destination: String,
-    pub amount: String,
+    pub amount: Amount,
     pub asset_code: Option<String>,
     pub memo: Option<String>,
 }
@@ -65,6 +69,34 @@ pub struct SendTransactionResponse {
     pub status: String,
 }
 
+/// Swaps the assets of a firm quote on-chain. `slippage_bps` defaults to,
+/// and cannot exceed, `CONVERT_MAX_SLIPPAGE_BPS`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalletConvertRequest {
+    pub quote_id: String,
+    pub slippage_bps: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalletConvertResponse {
+    pub tx_hash: String,
+    pub status: String,
+    pub quote_id: String,
+    pub send_asset: String,
+    pub send_amount: Amount,
+    pub dest_asset: String,
+    pub dest_amount: Amount,
+    pub dest_min: Amount,
+    /// Part of `send_amount` kept as the platform fee.
+    pub fee_amount: Amount,
+    pub fee_asset: String,
+    pub path: Vec<String>,
+    pub quoted_rate: Rate,
+    pub realized_rate: Rate,
+    /// Nothing was submitted to the Stellar network; `tx_hash` is made up.
+    pub simulated: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RelayTransactionRequest {
     pub public_key: String,
@@ -0,0 +1,189 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::modules::models::bank::TransferStatus;
+use crate::modules::models::remittance::RemittanceStatus;
+
+/// A wallet received funds from Friendbot.
+pub const EVENT_WALLET_FUNDED: &str = "wallet.funded";
+/// A send or conversion was recorded as completed.
+pub const EVENT_TRANSACTION_CONFIRMED: &str = "transaction.confirmed";
+
+const BANK_TRANSFER_PREFIX: &str = "bank_transfer.";
+const REMITTANCE_PREFIX: &str = "remittance.";
+
+/// `bank_transfer.<status>`, published when a transfer is created or changes status.
+pub fn bank_transfer_event(status: TransferStatus) -> String {
+    format!("{}{}", BANK_TRANSFER_PREFIX, status)
+}
+
+/// `remittance.<status>`, published when a remittance is created or changes status.
+pub fn remittance_event(status: RemittanceStatus) -> String {
+    format!("{}{}", REMITTANCE_PREFIX, status)
+}
+
+pub fn is_known_event_type(event_type: &str) -> bool {
+    if let Some(status) = event_type.strip_prefix(BANK_TRANSFER_PREFIX) {
+        return TransferStatus::parse(status).is_some();
+    }
+    if let Some(status) = event_type.strip_prefix(REMITTANCE_PREFIX) {
+        return RemittanceStatus::parse(status).is_some();
+    }
+    matches!(event_type, EVENT_WALLET_FUNDED | EVENT_TRANSACTION_CONFIRMED)
+}
+
+/// Whether a subscription pattern covers `event_type`: an exact type, a
+/// whole group such as `bank_transfer.*`, or `*` for everything.
+pub fn event_matches(pattern: &str, event_type: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some("") => true,
+        Some(prefix) if prefix.ends_with('.') => event_type.starts_with(prefix),
+        _ => pattern == event_type,
+    }
+}
+
+/// Whether a pattern can ever match a known event type.
+pub fn is_valid_pattern(pattern: &str) -> bool {
+    match pattern.strip_suffix(".*") {
+        Some(group) => matches!(group, "bank_transfer" | "remittance" | "wallet" | "transaction"),
+        None => pattern == "*" || is_known_event_type(pattern),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    /// Waiting for its first or next attempt.
+    Pending,
+    Delivered,
+    /// Out of attempts; stays in the dead-letter queue until replayed.
+    Dead,
+}
+
+impl DeliveryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeliveryStatus::Pending => "pending",
+            DeliveryStatus::Delivered => "delivered",
+            DeliveryStatus::Dead => "dead",
+        }
+    }
+}
+
+impl std::fmt::Display for DeliveryStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct WebhookSubscription {
+    pub id: String,
+    pub url: String,
+    /// Comma-separated event types or patterns.
+    pub event_types: String,
+    /// Encrypted signing secret; only returned when the subscription is created.
+    #[serde(skip_serializing)]
+    pub secret: String,
+    pub description: Option<String>,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl WebhookSubscription {
+    pub fn wants(&self, event_type: &str) -> bool {
+        self.event_types.split(',').any(|pattern| event_matches(pattern.trim(), event_type))
+    }
+}
+
+/// Something that happened, stored once and delivered to every subscription wanting it.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct WebhookEvent {
+    pub id: String,
+    pub event_type: String,
+    /// Body sent to subscribers: `{"id", "type", "created_at", "data"}`.
+    pub payload: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct WebhookDelivery {
+    pub id: String,
+    pub event_id: String,
+    pub subscription_id: String,
+    pub status: String,
+    pub attempts: i64,
+    pub next_attempt_at: DateTime<Utc>,
+    /// HTTP status of the last attempt, when the endpoint answered.
+    pub last_status_code: Option<i64>,
+    pub last_error: Option<String>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateWebhookRequest {
+    pub url: String,
+    /// Event types, `bank_transfer.*`-style groups or `*`.
+    pub event_types: Vec<String>,
+    /// Generated when omitted.
+    pub secret: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookSubscriptionResponse {
+    #[serde(flatten)]
+    pub subscription: WebhookSubscription,
+    /// Plain signing secret, only present in the response that created the subscription.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookListResponse {
+    pub subscriptions: Vec<WebhookSubscriptionResponse>,
+    pub total: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeadLetterListResponse {
+    pub deliveries: Vec<WebhookDelivery>,
+    pub total: usize,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReplayWebhookRequest {
+    /// Every active subscription wanting the event when omitted.
+    pub subscription_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayWebhookResponse {
+    pub event_id: String,
+    pub deliveries: Vec<WebhookDelivery>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_patterns() {
+        assert!(event_matches("*", "wallet.funded"));
+        assert!(event_matches("bank_transfer.*", "bank_transfer.completed"));
+        assert!(event_matches("transaction.confirmed", "transaction.confirmed"));
+        assert!(!event_matches("bank_transfer.*", "transaction.confirmed"));
+        assert!(!event_matches("bank_transfer.completed", "bank_transfer.failed"));
+
+        assert!(is_known_event_type(&bank_transfer_event(TransferStatus::Reversed)));
+        assert!(!is_known_event_type("bank_transfer.settled"));
+        assert!(is_known_event_type(&remittance_event(RemittanceStatus::PayingOut)));
+        assert!(is_valid_pattern("remittance.*"));
+        assert!(is_valid_pattern("bank_transfer.*"));
+        assert!(!is_valid_pattern("quote.*"));
+        assert!(!is_valid_pattern("bank_transfer.unknown"));
+    }
+}
\ No newline at end of file
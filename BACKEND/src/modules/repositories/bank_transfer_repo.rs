@@ -1,6 +1,26 @@
 use anyhow::Result;
-use sqlx::SqlitePool;
-use crate::modules::models::bank::BankTransfer;
+use chrono::{DateTime, Utc};
+use sqlx::{SqliteConnection, SqlitePool};
+use crate::modules::models::amount::{Amount, FiatAmount, FixedPoint};
+use crate::modules::models::bank::{
+    BankTransfer, BankTransferTransition, TransferCursor, TransferFilter, TransferStatus,
+};
+use crate::modules::models::transaction::Transaction;
+use crate::modules::models::review::TransferReview;
+use crate::modules::repositories::quote_repo::QuoteRepository;
+use crate::modules::repositories::review_repo::ReviewRepository;
+use crate::modules::repositories::transaction_repo::TransactionRepository;
+
+/// Result of [`BankTransferRepository::create_with_escrow`]. Unless it is
+/// `Created`, nothing was stored.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CreateOutcome {
+    Created,
+    QuoteUnavailable,
+    /// The escrow would commit more than `balance`; `available` is what was
+    /// left of it.
+    InsufficientFunds { available: Amount },
+}
 
 #[derive(Clone)]
 pub struct BankTransferRepository {
@@ -12,12 +32,67 @@ impl BankTransferRepository {
         Self { pool }
     }
 
-    pub async fn create(&self, transfer: &BankTransfer) -> Result<()> {
+    /// Inserts the transfer together with its first transition.
+    pub async fn create(&self, transfer: &BankTransfer, reason: &str) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        Self::insert(&mut tx, transfer, reason).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Redeems the quote, inserts the transfer, its escrow and the review it
+    /// is held for, if any, then checks that everything the wallet has
+    /// committed in the escrowed asset still fits in `balance`, all in one
+    /// transaction. The first write takes
+    /// SQLite's write lock, so concurrent transfers of the same wallet are
+    /// checked one after the other and cannot both spend the same funds.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_with_escrow(
+        &self,
+        transfer: &BankTransfer,
+        reason: &str,
+        quote_id: &str,
+        escrow: &Transaction,
+        review: Option<&TransferReview>,
+        balance: Amount,
+        now: DateTime<Utc>,
+    ) -> Result<CreateOutcome> {
+        let mut tx = self.pool.begin().await?;
+
+        let used_by = format!("bank_transfer:{}", transfer.id);
+        if !QuoteRepository::mark_used_with(&mut tx, quote_id, &transfer.public_key, &used_by, now).await? {
+            return Ok(CreateOutcome::QuoteUnavailable);
+        }
+
+        Self::insert(&mut tx, transfer, reason).await?;
+        TransactionRepository::create_with(&mut tx, escrow).await?;
+        if let Some(review) = review {
+            ReviewRepository::create_with(&mut tx, review).await?;
+        }
+
+        let committed = TransactionRepository::sum_committed_with(
+            &mut tx,
+            &escrow.wallet_id,
+            &escrow.asset,
+            escrow.asset_issuer.as_deref(),
+        )
+        .await?;
+        if committed > balance {
+            let held = committed.checked_sub(escrow.amount)?;
+            let available = if balance > held { balance.checked_sub(held)? } else { Amount::ZERO };
+            return Ok(CreateOutcome::InsufficientFunds { available });
+        }
+
+        tx.commit().await?;
+        Ok(CreateOutcome::Created)
+    }
+
+    async fn insert(tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>, transfer: &BankTransfer, reason: &str) -> Result<()> {
         sqlx::query!(
             r#"
             INSERT INTO bank_transfers 
-            (id, wallet_id, public_key, amount_fiat, currency, bank_account_masked, status, rejection_reason, reputation_score, created_at, completed_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            (id, wallet_id, public_key, amount_fiat, currency, bank_account_masked, status, rejection_reason, reputation_score, quote_id, provider_reference, payout_provider, payout_destination, beneficiary_id, created_at, completed_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
             transfer.id,
             transfer.wallet_id,
@@ -28,60 +103,314 @@ impl BankTransferRepository {
             transfer.status,
             transfer.rejection_reason,
             transfer.reputation_score,
+            transfer.quote_id,
+            transfer.provider_reference,
+            transfer.payout_provider,
+            transfer.payout_destination,
+            transfer.beneficiary_id,
             transfer.created_at,
             transfer.completed_at
         )
-        .execute(&self.pool)
+        .execute(&mut **tx)
         .await?;
+
+        let transition_id = uuid::Uuid::new_v4().to_string();
+        sqlx::query!(
+            r#"
+            INSERT INTO bank_transfer_transitions (id, transfer_id, from_status, to_status, reason, created_at)
+            VALUES (?, ?, NULL, ?, ?, ?)
+            "#,
+            transition_id,
+            transfer.id,
+            transfer.status,
+            reason,
+            transfer.created_at
+        )
+        .execute(&mut **tx)
+        .await?;
+
         Ok(())
     }
 
-    pub async fn find_all(&self) -> Result<Vec<BankTransfer>> {
-        let transfers = sqlx::query_as!(
+    pub async fn find_by_id(&self, id: &str) -> Result<Option<BankTransfer>> {
+        let transfer = sqlx::query_as!(
             BankTransfer,
             r#"
-            SELECT id, wallet_id, public_key, amount_fiat, currency, bank_account_masked, 
-                   status, rejection_reason, reputation_score, created_at, completed_at
+            SELECT id, wallet_id, public_key, amount_fiat as "amount_fiat: FiatAmount", currency, bank_account_masked,
+                   status, rejection_reason, reputation_score, quote_id, provider_reference, payout_provider, payout_destination, beneficiary_id, created_at, completed_at
             FROM bank_transfers 
-            ORDER BY created_at DESC
-            "#
+            WHERE id = ?
+            "#,
+            id
         )
-        .fetch_all(&self.pool)
+        .fetch_optional(&self.pool)
         .await?;
+        Ok(transfer)
+    }
+
+    /// One page of transfers matching the filter, ordered by `created_at`
+    /// then id and starting after `cursor`.
+    pub async fn find_page(
+        &self,
+        filter: &TransferFilter,
+        cursor: Option<&TransferCursor>,
+        ascending: bool,
+        limit: i64,
+    ) -> Result<Vec<BankTransfer>> {
+        let after_created_at = cursor.map(|c| c.created_at);
+        let after_id = cursor.map(|c| c.id.as_str());
+
+        let transfers = if ascending {
+            sqlx::query_as!(
+                BankTransfer,
+                r#"
+                SELECT id, wallet_id, public_key, amount_fiat as "amount_fiat: FiatAmount", currency, bank_account_masked,
+                       status, rejection_reason, reputation_score, quote_id, provider_reference, payout_provider, payout_destination, beneficiary_id, created_at, completed_at
+                FROM bank_transfers
+                WHERE (?1 IS NULL OR wallet_id = ?1)
+                  AND (?2 IS NULL OR status = ?2)
+                  AND (?3 IS NULL OR UPPER(currency) = ?3)
+                  AND (?4 IS NULL OR created_at >= ?4)
+                  AND (?5 IS NULL OR created_at < ?5)
+                  AND (?6 IS NULL OR created_at > ?6 OR (created_at = ?6 AND id > ?7))
+                ORDER BY created_at ASC, id ASC
+                LIMIT ?8
+                "#,
+                filter.wallet_id,
+                filter.status,
+                filter.currency,
+                filter.from,
+                filter.to,
+                after_created_at,
+                after_id,
+                limit
+            )
+            .fetch_all(&self.pool)
+            .await?
+        } else {
+            sqlx::query_as!(
+                BankTransfer,
+                r#"
+                SELECT id, wallet_id, public_key, amount_fiat as "amount_fiat: FiatAmount", currency, bank_account_masked,
+                       status, rejection_reason, reputation_score, quote_id, provider_reference, payout_provider, payout_destination, beneficiary_id, created_at, completed_at
+                FROM bank_transfers
+                WHERE (?1 IS NULL OR wallet_id = ?1)
+                  AND (?2 IS NULL OR status = ?2)
+                  AND (?3 IS NULL OR UPPER(currency) = ?3)
+                  AND (?4 IS NULL OR created_at >= ?4)
+                  AND (?5 IS NULL OR created_at < ?5)
+                  AND (?6 IS NULL OR created_at < ?6 OR (created_at = ?6 AND id < ?7))
+                ORDER BY created_at DESC, id DESC
+                LIMIT ?8
+                "#,
+                filter.wallet_id,
+                filter.status,
+                filter.currency,
+                filter.from,
+                filter.to,
+                after_created_at,
+                after_id,
+                limit
+            )
+            .fetch_all(&self.pool)
+            .await?
+        };
         Ok(transfers)
     }
 
-    pub async fn find_by_wallet(&self, wallet_id: &str) -> Result<Vec<BankTransfer>> {
+    pub async fn count(&self, filter: &TransferFilter) -> Result<i64> {
+        let count = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) as "count: i64"
+            FROM bank_transfers
+            WHERE (?1 IS NULL OR wallet_id = ?1)
+              AND (?2 IS NULL OR status = ?2)
+              AND (?3 IS NULL OR UPPER(currency) = ?3)
+              AND (?4 IS NULL OR created_at >= ?4)
+              AND (?5 IS NULL OR created_at < ?5)
+            "#,
+            filter.wallet_id,
+            filter.status,
+            filter.currency,
+            filter.from,
+            filter.to
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(count)
+    }
+
+    /// Transfers routed to a payout provider, for reconciling its settlement files.
+    pub async fn find_by_payout_provider(&self, provider: &str) -> Result<Vec<BankTransfer>> {
         let transfers = sqlx::query_as!(
             BankTransfer,
             r#"
-            SELECT id, wallet_id, public_key, amount_fiat, currency, bank_account_masked, 
-                   status, rejection_reason, reputation_score, created_at, completed_at
+            SELECT id, wallet_id, public_key, amount_fiat as "amount_fiat: FiatAmount", currency, bank_account_masked,
+                   status, rejection_reason, reputation_score, quote_id, provider_reference, payout_provider, payout_destination, beneficiary_id, created_at, completed_at
             FROM bank_transfers 
-            WHERE wallet_id = ?
-            ORDER BY created_at DESC
+            WHERE payout_provider = ?
+            ORDER BY created_at ASC
             "#,
-            wallet_id
+            provider
         )
         .fetch_all(&self.pool)
         .await?;
         Ok(transfers)
     }
 
-    pub async fn find_by_public_key(&self, public_key: &str) -> Result<Vec<BankTransfer>> {
+    /// Oldest transfers first, so the payout worker processes them in order.
+    pub async fn find_by_status(&self, status: TransferStatus, limit: i64) -> Result<Vec<BankTransfer>> {
+        let status = status.as_str();
         let transfers = sqlx::query_as!(
             BankTransfer,
             r#"
-            SELECT id, wallet_id, public_key, amount_fiat, currency, bank_account_masked, 
-                   status, rejection_reason, reputation_score, created_at, completed_at
+            SELECT id, wallet_id, public_key, amount_fiat as "amount_fiat: FiatAmount", currency, bank_account_masked,
+                   status, rejection_reason, reputation_score, quote_id, provider_reference, payout_provider, payout_destination, beneficiary_id, created_at, completed_at
             FROM bank_transfers 
-            WHERE public_key = ?
-            ORDER BY created_at DESC
+            WHERE status = ?
+            ORDER BY created_at ASC
+            LIMIT ?
             "#,
-            public_key
+            status,
+            limit
         )
         .fetch_all(&self.pool)
         .await?;
         Ok(transfers)
     }
+
+    /// USD value, as quoted, of every transfer the wallet created since
+    /// `since` that is still going or went through.
+    pub async fn usd_amounts_since(&self, wallet_id: &str, since: DateTime<Utc>) -> Result<Vec<Amount>> {
+        let amounts = sqlx::query_scalar!(
+            r#"
+            SELECT q.usd_amount as "usd_amount: Amount"
+            FROM bank_transfers t
+            JOIN quotes q ON q.id = t.quote_id
+            WHERE t.wallet_id = ? AND t.created_at >= ?
+              AND t.status NOT IN ('rejected', 'cancelled', 'failed')
+            "#,
+            wallet_id,
+            since
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(amounts)
+    }
+
+    pub async fn count_by_wallet_and_status(&self, wallet_id: &str, status: TransferStatus) -> Result<i64> {
+        let status = status.as_str();
+        let count = sqlx::query_scalar!(
+            "SELECT COUNT(*) as count FROM bank_transfers WHERE wallet_id = ? AND status = ?",
+            wallet_id,
+            status
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(count)
+    }
+
+    /// Stores why the transfer was rejected, inside the caller's transaction.
+    pub async fn set_rejection_reason_with(conn: &mut SqliteConnection, id: &str, reason: &str) -> Result<()> {
+        sqlx::query!(
+            "UPDATE bank_transfers SET rejection_reason = ? WHERE id = ?",
+            reason,
+            id
+        )
+        .execute(conn)
+        .await?;
+        Ok(())
+    }
+
+    /// Moves the transfer from `from` to `to` and records the transition.
+    /// Returns `false` when the transfer is no longer in `from`.
+    pub async fn transition(
+        &self,
+        id: &str,
+        from: TransferStatus,
+        to: TransferStatus,
+        reason: &str,
+        now: DateTime<Utc>,
+    ) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+        if !Self::transition_with(&mut tx, id, from, to, reason, now).await? {
+            return Ok(false);
+        }
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// Moves the transfer from `from` to `to` and records the transition.
+    /// Returns `false` if it was no longer in `from`.
+    pub async fn transition_with(
+        conn: &mut SqliteConnection,
+        id: &str,
+        from: TransferStatus,
+        to: TransferStatus,
+        reason: &str,
+        now: DateTime<Utc>,
+    ) -> Result<bool> {
+        let from = from.as_str();
+        let to = to.as_str();
+        let completed_at = (to == TransferStatus::Completed.as_str()).then_some(now);
+
+        let result = sqlx::query!(
+            "UPDATE bank_transfers SET status = ?, completed_at = COALESCE(?, completed_at) WHERE id = ? AND status = ?",
+            to,
+            completed_at,
+            id,
+            from
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        if result.rows_affected() != 1 {
+            return Ok(false);
+        }
+
+        let transition_id = uuid::Uuid::new_v4().to_string();
+        sqlx::query!(
+            r#"
+            INSERT INTO bank_transfer_transitions (id, transfer_id, from_status, to_status, reason, created_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+            transition_id,
+            id,
+            from,
+            to,
+            reason,
+            now
+        )
+        .execute(conn)
+        .await?;
+
+        Ok(true)
+    }
+
+    pub async fn set_provider_reference(&self, id: &str, reference: &str) -> Result<()> {
+        sqlx::query!(
+            "UPDATE bank_transfers SET provider_reference = ? WHERE id = ?",
+            reference,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find_transitions(&self, transfer_id: &str) -> Result<Vec<BankTransferTransition>> {
+        let transitions = sqlx::query_as!(
+            BankTransferTransition,
+            r#"
+            SELECT id, transfer_id, from_status, to_status, reason, created_at
+            FROM bank_transfer_transitions
+            WHERE transfer_id = ?
+            ORDER BY created_at ASC
+            "#,
+            transfer_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(transitions)
+    }
 }
\ No newline at end of file
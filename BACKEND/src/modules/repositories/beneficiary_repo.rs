@@ -0,0 +1,244 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use crate::modules::models::beneficiary::{Beneficiary, VerificationStatus};
+
+#[derive(Clone)]
+pub struct BeneficiaryRepository {
+    pool: SqlitePool,
+}
+
+impl BeneficiaryRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Saves the beneficiary, or restores the one the wallet deleted for the
+    /// same account with its verification state and cooling-off unchanged.
+    /// Returns `false` if the wallet already has the account saved.
+    pub async fn create(&self, beneficiary: &Beneficiary) -> Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO beneficiaries
+            (id, wallet_id, nickname, currency, country, scheme, bank_name, account_masked, account_hash, details,
+             verification_status, verification_note, available_at, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT (wallet_id, account_hash) DO UPDATE
+            SET nickname = excluded.nickname, details = excluded.details, updated_at = excluded.updated_at,
+                deleted_at = NULL
+            WHERE beneficiaries.deleted_at IS NOT NULL
+            "#,
+            beneficiary.id,
+            beneficiary.wallet_id,
+            beneficiary.nickname,
+            beneficiary.currency,
+            beneficiary.country,
+            beneficiary.scheme,
+            beneficiary.bank_name,
+            beneficiary.account_masked,
+            beneficiary.account_hash,
+            beneficiary.details,
+            beneficiary.verification_status,
+            beneficiary.verification_note,
+            beneficiary.available_at,
+            beneficiary.created_at,
+            beneficiary.updated_at
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Includes deleted beneficiaries.
+    pub async fn find_by_id(&self, id: &str) -> Result<Option<Beneficiary>> {
+        let beneficiary = sqlx::query_as!(
+            Beneficiary,
+            r#"
+            SELECT id, wallet_id, nickname, currency, country, scheme, bank_name, account_masked, account_hash, details,
+                   verification_status, verification_note, available_at, created_at, updated_at, deleted_at
+            FROM beneficiaries
+            WHERE id = ?
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(beneficiary)
+    }
+
+    /// The wallet's entry for an account, deleted or not.
+    pub async fn find_by_account_hash(&self, wallet_id: &str, account_hash: &str) -> Result<Option<Beneficiary>> {
+        let beneficiary = sqlx::query_as!(
+            Beneficiary,
+            r#"
+            SELECT id, wallet_id, nickname, currency, country, scheme, bank_name, account_masked, account_hash, details,
+                   verification_status, verification_note, available_at, created_at, updated_at, deleted_at
+            FROM beneficiaries
+            WHERE wallet_id = ? AND account_hash = ?
+            "#,
+            wallet_id,
+            account_hash
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(beneficiary)
+    }
+
+    pub async fn find_by_wallet(&self, wallet_id: &str) -> Result<Vec<Beneficiary>> {
+        let beneficiaries = sqlx::query_as!(
+            Beneficiary,
+            r#"
+            SELECT id, wallet_id, nickname, currency, country, scheme, bank_name, account_masked, account_hash, details,
+                   verification_status, verification_note, available_at, created_at, updated_at, deleted_at
+            FROM beneficiaries
+            WHERE wallet_id = ? AND deleted_at IS NULL
+            ORDER BY created_at DESC
+            "#,
+            wallet_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(beneficiaries)
+    }
+
+    /// Moves a beneficiary from `from` to `to`. Returns `false` if its status
+    /// changed first or it was deleted.
+    pub async fn set_verification(
+        &self,
+        id: &str,
+        from: VerificationStatus,
+        to: VerificationStatus,
+        note: Option<&str>,
+        now: DateTime<Utc>,
+    ) -> Result<bool> {
+        let from = from.as_str();
+        let to = to.as_str();
+        let result = sqlx::query!(
+            r#"
+            UPDATE beneficiaries
+            SET verification_status = ?, verification_note = ?, updated_at = ?
+            WHERE id = ? AND verification_status = ? AND deleted_at IS NULL
+            "#,
+            to,
+            note,
+            now,
+            id,
+            from
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Hides the beneficiary from the wallet; its verification state is kept.
+    pub async fn delete(&self, id: &str, wallet_id: &str, now: DateTime<Utc>) -> Result<bool> {
+        let result = sqlx::query!(
+            "UPDATE beneficiaries SET deleted_at = ? WHERE id = ? AND wallet_id = ? AND deleted_at IS NULL",
+            now,
+            id,
+            wallet_id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn repo() -> BeneficiaryRepository {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::raw_sql("CREATE TABLE wallets (id TEXT PRIMARY KEY NOT NULL); INSERT INTO wallets (id) VALUES ('w1');")
+            .execute(&pool)
+            .await
+            .unwrap();
+        // 017 also adds a column to bank_transfers, which these tests do not need.
+        let beneficiaries = include_str!("../../../migrations/017_beneficiaries.sql");
+        let beneficiaries = &beneficiaries[..beneficiaries.find("-- Beneficiary a transfer").unwrap()];
+        sqlx::raw_sql(beneficiaries).execute(&pool).await.unwrap();
+        sqlx::raw_sql(include_str!("../../../migrations/026_beneficiary_soft_delete.sql"))
+            .execute(&pool)
+            .await
+            .unwrap();
+        BeneficiaryRepository::new(pool)
+    }
+
+    fn beneficiary(id: &str, now: DateTime<Utc>) -> Beneficiary {
+        Beneficiary {
+            id: id.to_string(),
+            wallet_id: "w1".to_string(),
+            nickname: "Mamá".to_string(),
+            currency: "MXN".to_string(),
+            country: "MX".to_string(),
+            scheme: "clabe".to_string(),
+            bank_name: None,
+            account_masked: "****7897".to_string(),
+            account_hash: "hash".to_string(),
+            details: "encrypted".to_string(),
+            verification_status: VerificationStatus::Unverified.to_string(),
+            verification_note: None,
+            available_at: now + Duration::hours(24),
+            created_at: now,
+            updated_at: now,
+            deleted_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_delete_keeps_the_verification_state() {
+        let repo = repo().await;
+        let now = Utc::now();
+
+        assert!(repo.create(&beneficiary("b1", now)).await.unwrap());
+        assert!(!repo.create(&beneficiary("b2", now)).await.unwrap());
+        assert!(repo
+            .set_verification("b1", VerificationStatus::Unverified, VerificationStatus::Rejected, None, now)
+            .await
+            .unwrap());
+
+        assert!(repo.delete("b1", "w1", now).await.unwrap());
+        assert!(repo.find_by_wallet("w1").await.unwrap().is_empty());
+        let hidden = repo.find_by_account_hash("w1", "hash").await.unwrap().unwrap();
+        assert_eq!(hidden.verification_status, "rejected");
+        assert!(hidden.deleted_at.is_some());
+
+        // Saving the account again brings back the same, still rejected entry.
+        let later = now + Duration::hours(48);
+        assert!(repo.create(&beneficiary("b3", later)).await.unwrap());
+        let restored = repo.find_by_wallet("w1").await.unwrap();
+        assert_eq!(restored.len(), 1);
+        assert_eq!(restored[0].id, "b1");
+        assert_eq!(restored[0].verification_status, "rejected");
+        assert_eq!(restored[0].available_at, now + Duration::hours(24));
+    }
+
+    #[tokio::test]
+    async fn test_set_verification_requires_the_expected_status() {
+        let repo = repo().await;
+        let now = Utc::now();
+        repo.create(&beneficiary("b1", now)).await.unwrap();
+
+        assert!(!repo
+            .set_verification("b1", VerificationStatus::Verified, VerificationStatus::Rejected, None, now)
+            .await
+            .unwrap());
+        assert!(repo
+            .set_verification("b1", VerificationStatus::Unverified, VerificationStatus::Verified, None, now)
+            .await
+            .unwrap());
+
+        repo.delete("b1", "w1", now).await.unwrap();
+        assert!(!repo
+            .set_verification("b1", VerificationStatus::Verified, VerificationStatus::Rejected, None, now)
+            .await
+            .unwrap());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,171 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use crate::modules::models::idempotency::IdempotencyRecord;
+
+#[derive(Clone)]
+pub struct IdempotencyRepository {
+    pool: SqlitePool,
+}
+
+impl IdempotencyRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Records the key as in flight until `locked_until`. Returns `false` if
+    /// the key is finished or another request still holds its lease; an
+    /// in-flight key whose lease has run out is taken over.
+    pub async fn claim(
+        &self,
+        scope: &str,
+        key: &str,
+        fingerprint: &str,
+        now: DateTime<Utc>,
+        locked_until: DateTime<Utc>,
+    ) -> Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO idempotency_keys (scope, key, fingerprint, created_at, locked_until)
+            VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT (scope, key) DO UPDATE
+            SET fingerprint = excluded.fingerprint,
+                created_at = excluded.created_at,
+                locked_until = excluded.locked_until
+            WHERE idempotency_keys.response_status IS NULL
+              AND idempotency_keys.fingerprint = excluded.fingerprint
+              AND idempotency_keys.locked_until < excluded.created_at
+            "#,
+            scope,
+            key,
+            fingerprint,
+            now,
+            locked_until
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    pub async fn find(&self, scope: &str, key: &str) -> Result<Option<IdempotencyRecord>> {
+        let record = sqlx::query_as!(
+            IdempotencyRecord,
+            r#"
+            SELECT fingerprint, response_status, response_content_type, response_body
+            FROM idempotency_keys
+            WHERE scope = ? AND key = ?
+            "#,
+            scope,
+            key
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(record)
+    }
+
+    pub async fn complete(
+        &self,
+        scope: &str,
+        key: &str,
+        status: u16,
+        content_type: Option<&str>,
+        body: &[u8],
+        now: DateTime<Utc>,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE idempotency_keys
+            SET response_status = ?, response_content_type = ?, response_body = ?, completed_at = ?
+            WHERE scope = ? AND key = ? AND response_status IS NULL
+            "#,
+            status,
+            content_type,
+            body,
+            now,
+            scope,
+            key
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Extends the lease of an in-flight key whose request is still running.
+    pub async fn renew(&self, scope: &str, key: &str, locked_until: DateTime<Utc>) -> Result<()> {
+        sqlx::query!(
+            "UPDATE idempotency_keys SET locked_until = ? WHERE scope = ? AND key = ? AND response_status IS NULL",
+            locked_until,
+            scope,
+            key
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Drops an in-flight key so the request can be retried.
+    pub async fn release(&self, scope: &str, key: &str) -> Result<()> {
+        sqlx::query!(
+            "DELETE FROM idempotency_keys WHERE scope = ? AND key = ? AND response_status IS NULL",
+            scope,
+            key
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete_older_than(&self, cutoff: DateTime<Utc>) -> Result<u64> {
+        let result = sqlx::query!(
+            "DELETE FROM idempotency_keys WHERE created_at < ? AND (response_status IS NOT NULL OR locked_until < ?)",
+            cutoff,
+            cutoff
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn repo() -> IdempotencyRepository {
+        let pool = SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap();
+        sqlx::raw_sql(include_str!("../../../migrations/025_idempotency_scope_and_lease.sql"))
+            .execute(&pool)
+            .await
+            .unwrap();
+        IdempotencyRepository::new(pool)
+    }
+
+    #[tokio::test]
+    async fn test_only_an_expired_lease_is_taken_over() {
+        let repo = repo().await;
+        let start = Utc::now();
+        let lease = Duration::seconds(60);
+
+        assert!(repo.claim("/send\nGA", "k1", "f", start, start + lease).await.unwrap());
+        assert!(!repo.claim("/send\nGA", "k1", "f", start + Duration::seconds(30), start + Duration::seconds(90)).await.unwrap());
+
+        // A request still running renews its lease, so a retry past the
+        // original expiry does not run it a second time.
+        repo.renew("/send\nGA", "k1", start + Duration::seconds(110)).await.unwrap();
+        let retry = start + Duration::seconds(90);
+        assert!(!repo.claim("/send\nGA", "k1", "f", retry, retry + lease).await.unwrap());
+
+        // One whose holder stopped renewing is taken over.
+        let retry = start + Duration::seconds(120);
+        assert!(repo.claim("/send\nGA", "k1", "f", retry, retry + lease).await.unwrap());
+
+        // A finished key is replayed, never run again.
+        repo.complete("/send\nGA", "k1", 200, None, b"{}", retry).await.unwrap();
+        repo.renew("/send\nGA", "k1", retry + Duration::hours(1)).await.unwrap();
+        let later = retry + Duration::hours(2);
+        assert!(!repo.claim("/send\nGA", "k1", "f", later, later + lease).await.unwrap());
+        assert_eq!(repo.find("/send\nGA", "k1").await.unwrap().unwrap().response_status, Some(200));
+    }
+}
\ No newline at end of file
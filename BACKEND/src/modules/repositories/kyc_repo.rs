@@ -0,0 +1,140 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use crate::modules::models::kyc::{KycCustomer, KycStatus};
+
+#[derive(Clone)]
+pub struct KycRepository {
+    pool: SqlitePool,
+}
+
+impl KycRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Inserts the customer, or replaces the fields and status of the one
+    /// already stored for its wallet.
+    pub async fn upsert(&self, customer: &KycCustomer) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO kyc_customers (id, wallet_id, account, status, message, fields, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(wallet_id) DO UPDATE SET
+                status = excluded.status,
+                message = excluded.message,
+                fields = excluded.fields,
+                updated_at = excluded.updated_at
+            "#,
+            customer.id,
+            customer.wallet_id,
+            customer.account,
+            customer.status,
+            customer.message,
+            customer.fields,
+            customer.created_at,
+            customer.updated_at
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find_by_id(&self, id: &str) -> Result<Option<KycCustomer>> {
+        let customer = sqlx::query_as!(
+            KycCustomer,
+            r#"
+            SELECT id, wallet_id, account, status, message, fields, created_at, updated_at
+            FROM kyc_customers
+            WHERE id = ?
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(customer)
+    }
+
+    pub async fn find_by_account(&self, account: &str) -> Result<Option<KycCustomer>> {
+        let customer = sqlx::query_as!(
+            KycCustomer,
+            r#"
+            SELECT id, wallet_id, account, status, message, fields, created_at, updated_at
+            FROM kyc_customers
+            WHERE account = ?
+            "#,
+            account
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(customer)
+    }
+
+    pub async fn find_by_wallet(&self, wallet_id: &str) -> Result<Option<KycCustomer>> {
+        let customer = sqlx::query_as!(
+            KycCustomer,
+            r#"
+            SELECT id, wallet_id, account, status, message, fields, created_at, updated_at
+            FROM kyc_customers
+            WHERE wallet_id = ?
+            "#,
+            wallet_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(customer)
+    }
+
+    /// Customers in `status`, the longest waiting first.
+    pub async fn find_by_status(&self, status: KycStatus) -> Result<Vec<KycCustomer>> {
+        let status = status.as_str();
+        let customers = sqlx::query_as!(
+            KycCustomer,
+            r#"
+            SELECT id, wallet_id, account, status, message, fields, created_at, updated_at
+            FROM kyc_customers
+            WHERE status = ?
+            ORDER BY updated_at ASC
+            "#,
+            status
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(customers)
+    }
+
+    /// Moves a customer from `from` to `to`. Returns `false` if its status changed first.
+    pub async fn update_status(
+        &self,
+        id: &str,
+        from: KycStatus,
+        to: KycStatus,
+        message: Option<&str>,
+        now: DateTime<Utc>,
+    ) -> Result<bool> {
+        let from = from.as_str();
+        let to = to.as_str();
+        let result = sqlx::query!(
+            r#"
+            UPDATE kyc_customers
+            SET status = ?, message = ?, updated_at = ?
+            WHERE id = ? AND status = ?
+            "#,
+            to,
+            message,
+            now,
+            id,
+            from
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    pub async fn delete(&self, id: &str) -> Result<bool> {
+        let result = sqlx::query!("DELETE FROM kyc_customers WHERE id = ?", id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() == 1)
+    }
+}
\ No newline at end of file
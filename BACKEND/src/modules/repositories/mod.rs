@@ -1,3 +1,14 @@
 pub mod bank_transfer_repo;
+pub mod beneficiary_repo;
+pub mod idempotency_repo;
+pub mod kyc_repo;
+pub mod quote_repo;
+pub mod rate_history_repo;
+pub mod reconciliation_repo;
+pub mod remittance_repo;
+pub mod review_repo;
+pub mod risk_repo;
+pub mod screening_repo;
 pub mod transaction_repo;
-pub mod wallet_repo;
\ No newline at end of file
+pub mod wallet_repo;
+pub mod webhook_repo;
\ No newline at end of file
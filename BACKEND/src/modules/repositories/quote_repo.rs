@@ -0,0 +1,156 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use crate::modules::models::amount::{Amount, Rate};
+use crate::modules::models::convert::ConversionLeg;
+use crate::modules::models::quote::Quote;
+
+#[derive(Clone)]
+pub struct QuoteRepository {
+    pool: SqlitePool,
+}
+
+impl QuoteRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Inserts the quote with the legs it was priced from, in order.
+    pub async fn create(&self, quote: &Quote, legs: &[ConversionLeg]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO quotes
+            (id, context, sell_asset, sell_amount, buy_asset, buy_amount, price, total_price,
+             fee_total, fee_asset, usd_amount, rate_source, rate_fallback, rate_stale, created_at, expires_at, used_at, used_by,
+             requested_by)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+            quote.id,
+            quote.context,
+            quote.sell_asset,
+            quote.sell_amount,
+            quote.buy_asset,
+            quote.buy_amount,
+            quote.price,
+            quote.total_price,
+            quote.fee_total,
+            quote.fee_asset,
+            quote.usd_amount,
+            quote.rate_source,
+            quote.rate_fallback,
+            quote.rate_stale,
+            quote.created_at,
+            quote.expires_at,
+            quote.used_at,
+            quote.used_by,
+            quote.requested_by
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        for (position, leg) in legs.iter().enumerate() {
+            let position = position as i64;
+            sqlx::query!(
+                r#"
+                INSERT INTO quote_legs (quote_id, position, from_asset, to_asset, rate, source, fetched_at, fallback, stale)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+                quote.id,
+                position,
+                leg.from,
+                leg.to,
+                leg.rate,
+                leg.source,
+                leg.fetched_at,
+                leg.fallback,
+                leg.stale
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn find_legs(&self, quote_id: &str) -> Result<Vec<ConversionLeg>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT from_asset, to_asset, rate as "rate: Rate", source,
+                   fetched_at as "fetched_at: DateTime<Utc>", fallback, stale
+            FROM quote_legs
+            WHERE quote_id = ?
+            ORDER BY position ASC
+            "#,
+            quote_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| ConversionLeg {
+                from: row.from_asset,
+                to: row.to_asset,
+                rate: row.rate,
+                source: row.source,
+                fetched_at: row.fetched_at,
+                fallback: row.fallback,
+                stale: row.stale,
+            })
+            .collect())
+    }
+
+    pub async fn find_by_id(&self, id: &str) -> Result<Option<Quote>> {
+        let quote = sqlx::query_as!(
+            Quote,
+            r#"
+            SELECT id, context, sell_asset, sell_amount as "sell_amount: Amount", buy_asset,
+                   buy_amount as "buy_amount: Amount", price as "price: Rate", total_price as "total_price: Rate",
+                   fee_total as "fee_total: Amount", fee_asset, usd_amount as "usd_amount: Amount", rate_source,
+                   rate_fallback, rate_stale, created_at, expires_at, used_at, used_by, requested_by
+            FROM quotes
+            WHERE id = ?
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(quote)
+    }
+
+    /// Marks the quote as used if it is still unused, unexpired and issued to
+    /// `requested_by`. Returns `false` when another request got there first
+    /// or it has expired.
+    pub async fn mark_used(&self, id: &str, requested_by: &str, used_by: &str, now: DateTime<Utc>) -> Result<bool> {
+        let mut conn = self.pool.acquire().await?;
+        Self::mark_used_with(&mut conn, id, requested_by, used_by, now).await
+    }
+
+    /// [`QuoteRepository::mark_used`] on a connection, so the redemption can
+    /// commit or roll back with the operation it pays for.
+    pub async fn mark_used_with(
+        conn: &mut sqlx::SqliteConnection,
+        id: &str,
+        requested_by: &str,
+        used_by: &str,
+        now: DateTime<Utc>,
+    ) -> Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE quotes SET used_at = ?, used_by = ?
+            WHERE id = ? AND requested_by = ? AND used_at IS NULL AND expires_at > ?
+            "#,
+            now,
+            used_by,
+            id,
+            requested_by,
+            now
+        )
+        .execute(conn)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,157 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use crate::modules::models::amount::Rate;
+use crate::modules::models::rate_history::{RateCandle, RateSnapshot};
+
+#[derive(Clone)]
+pub struct RateHistoryRepository {
+    pool: SqlitePool,
+}
+
+impl RateHistoryRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create_snapshot(&self, snapshot: &RateSnapshot) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO rate_snapshots (id, base, quote, rate, source, observed_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+            snapshot.id,
+            snapshot.base,
+            snapshot.quote,
+            snapshot.rate,
+            snapshot.source,
+            snapshot.observed_at
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find_snapshots(
+        &self,
+        base: &str,
+        quote: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<RateSnapshot>> {
+        let snapshots = sqlx::query_as!(
+            RateSnapshot,
+            r#"
+            SELECT id, base, quote, rate as "rate: Rate", source, observed_at
+            FROM rate_snapshots
+            WHERE base = ? AND quote = ? AND observed_at >= ? AND observed_at < ?
+            ORDER BY observed_at ASC
+            LIMIT ?
+            "#,
+            base,
+            quote,
+            start,
+            end,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(snapshots)
+    }
+
+    pub async fn find_pairs(&self) -> Result<Vec<(String, String)>> {
+        let pairs = sqlx::query!("SELECT DISTINCT base, quote FROM rate_snapshots")
+            .fetch_all(&self.pool)
+            .await?
+            .into_iter()
+            .map(|row| (row.base, row.quote))
+            .collect();
+        Ok(pairs)
+    }
+
+    pub async fn latest_candle_start(
+        &self,
+        base: &str,
+        quote: &str,
+        interval: &str,
+    ) -> Result<Option<DateTime<Utc>>> {
+        let latest = sqlx::query_scalar!(
+            r#"
+            SELECT bucket_start as "bucket_start: DateTime<Utc>"
+            FROM rate_candles
+            WHERE base = ? AND quote = ? AND interval = ?
+            ORDER BY bucket_start DESC
+            LIMIT 1
+            "#,
+            base,
+            quote,
+            interval
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(latest)
+    }
+
+    pub async fn upsert_candle(&self, candle: &RateCandle) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO rate_candles (base, quote, interval, bucket_start, open, high, low, close, samples)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT (base, quote, interval, bucket_start)
+            DO UPDATE SET open = excluded.open, high = excluded.high, low = excluded.low,
+                          close = excluded.close, samples = excluded.samples
+            "#,
+            candle.base,
+            candle.quote,
+            candle.interval,
+            candle.bucket_start,
+            candle.open,
+            candle.high,
+            candle.low,
+            candle.close,
+            candle.samples
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find_candles(
+        &self,
+        base: &str,
+        quote: &str,
+        interval: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<RateCandle>> {
+        let candles = sqlx::query_as!(
+            RateCandle,
+            r#"
+            SELECT base, quote, interval, bucket_start, open as "open: Rate", high as "high: Rate",
+                   low as "low: Rate", close as "close: Rate", samples
+            FROM rate_candles
+            WHERE base = ? AND quote = ? AND interval = ? AND bucket_start >= ? AND bucket_start < ?
+            ORDER BY bucket_start ASC
+            LIMIT ?
+            "#,
+            base,
+            quote,
+            interval,
+            start,
+            end,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(candles)
+    }
+
+    pub async fn delete_snapshots_before(&self, cutoff: DateTime<Utc>) -> Result<u64> {
+        let result = sqlx::query!("DELETE FROM rate_snapshots WHERE observed_at < ?", cutoff)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,200 @@
+use anyhow::Result;
+use sqlx::SqlitePool;
+use crate::modules::models::amount::FiatAmount;
+use crate::modules::models::reconciliation::{
+    ReconciliationItem, ReconciliationRun, SettlementRow, SettlementStatement,
+};
+
+#[derive(Clone)]
+pub struct ReconciliationRepository {
+    pool: SqlitePool,
+}
+
+impl ReconciliationRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn find_statement_by_checksum(&self, provider: &str, checksum: &str) -> Result<Option<SettlementStatement>> {
+        let statement = sqlx::query_as!(
+            SettlementStatement,
+            r#"
+            SELECT id, provider, filename, checksum, row_count, imported_at
+            FROM settlement_statements
+            WHERE provider = ? AND checksum = ?
+            "#,
+            provider,
+            checksum
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(statement)
+    }
+
+    /// Inserts the statement together with its rows.
+    pub async fn create_statement(&self, statement: &SettlementStatement, rows: &[SettlementRow]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO settlement_statements (id, provider, filename, checksum, row_count, imported_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+            statement.id,
+            statement.provider,
+            statement.filename,
+            statement.checksum,
+            statement.row_count,
+            statement.imported_at
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        for row in rows {
+            sqlx::query!(
+                r#"
+                INSERT INTO settlement_rows (id, statement_id, provider, line, reference, amount, currency, settled_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+                row.id,
+                row.statement_id,
+                row.provider,
+                row.line,
+                row.reference,
+                row.amount,
+                row.currency,
+                row.settled_at
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Every row imported for the provider, across all of its statements.
+    pub async fn find_rows_by_provider(&self, provider: &str) -> Result<Vec<SettlementRow>> {
+        let rows = sqlx::query_as!(
+            SettlementRow,
+            r#"
+            SELECT r.id, r.statement_id, r.provider, r.line, r.reference, r.amount as "amount: FiatAmount",
+                   r.currency, r.settled_at
+            FROM settlement_rows r
+            JOIN settlement_statements s ON s.id = r.statement_id
+            WHERE r.provider = ?
+            ORDER BY s.imported_at ASC, r.line ASC
+            "#,
+            provider
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Inserts the run together with its items.
+    pub async fn create_run(&self, run: &ReconciliationRun, items: &[ReconciliationItem]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO reconciliation_runs
+            (id, provider, statement_id, matched, missing, duplicate, amount_mismatch, unmatched, reversed_settled,
+             created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+            run.id,
+            run.provider,
+            run.statement_id,
+            run.matched,
+            run.missing,
+            run.duplicate,
+            run.amount_mismatch,
+            run.unmatched,
+            run.reversed_settled,
+            run.created_at
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        for item in items {
+            sqlx::query!(
+                r#"
+                INSERT INTO reconciliation_items
+                (id, run_id, status, transfer_id, reference, expected_amount, settled_amount, currency, detail)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+                item.id,
+                item.run_id,
+                item.status,
+                item.transfer_id,
+                item.reference,
+                item.expected_amount,
+                item.settled_amount,
+                item.currency,
+                item.detail
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn find_run(&self, id: &str) -> Result<Option<ReconciliationRun>> {
+        let run = sqlx::query_as!(
+            ReconciliationRun,
+            r#"
+            SELECT id, provider, statement_id, matched, missing, duplicate, amount_mismatch, unmatched, reversed_settled,
+                   created_at
+            FROM reconciliation_runs
+            WHERE id = ?
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(run)
+    }
+
+    /// Newest runs first, optionally for one provider.
+    pub async fn find_runs(&self, provider: Option<&str>, limit: i64) -> Result<Vec<ReconciliationRun>> {
+        let runs = sqlx::query_as!(
+            ReconciliationRun,
+            r#"
+            SELECT id, provider, statement_id, matched, missing, duplicate, amount_mismatch, unmatched, reversed_settled,
+                   created_at
+            FROM reconciliation_runs
+            WHERE ? IS NULL OR provider = ?
+            ORDER BY created_at DESC
+            LIMIT ?
+            "#,
+            provider,
+            provider,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(runs)
+    }
+
+    /// Problems first, then matches, each in the order the run found them.
+    pub async fn find_items(&self, run_id: &str) -> Result<Vec<ReconciliationItem>> {
+        let items = sqlx::query_as!(
+            ReconciliationItem,
+            r#"
+            SELECT id, run_id, status, transfer_id, reference,
+                   expected_amount as "expected_amount: FiatAmount", settled_amount as "settled_amount: FiatAmount",
+                   currency, detail
+            FROM reconciliation_items
+            WHERE run_id = ?
+            ORDER BY status = 'matched', rowid
+            "#,
+            run_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(items)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,335 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use crate::modules::models::amount::{Amount, FiatAmount};
+use crate::modules::models::remittance::{Remittance, RemittanceStatus, RemittanceStep};
+
+#[derive(Clone)]
+pub struct RemittanceRepository {
+    pool: SqlitePool,
+}
+
+impl RemittanceRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Inserts the remittance together with its first steps.
+    pub async fn create(&self, remittance: &Remittance, steps: &[RemittanceStep]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO remittances
+            (id, wallet_id, public_key, amount_fiat, currency, source_asset, source_amount, dex_asset, dex_amount,
+             quote_id, transfer_id, status, failure_reason, created_at, updated_at, completed_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+            remittance.id,
+            remittance.wallet_id,
+            remittance.public_key,
+            remittance.amount_fiat,
+            remittance.currency,
+            remittance.source_asset,
+            remittance.source_amount,
+            remittance.dex_asset,
+            remittance.dex_amount,
+            remittance.quote_id,
+            remittance.transfer_id,
+            remittance.status,
+            remittance.failure_reason,
+            remittance.created_at,
+            remittance.updated_at,
+            remittance.completed_at
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        for step in steps {
+            Self::insert_step(&mut tx, step).await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Stores the remittance's new state and steps if it is still in `from`.
+    /// Returns false when another worker or request moved it first.
+    pub async fn save(&self, remittance: &Remittance, from: RemittanceStatus, steps: &[RemittanceStep]) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+        let from = from.as_str();
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE remittances
+            SET source_amount = ?, dex_amount = ?, quote_id = ?, transfer_id = ?, status = ?, failure_reason = ?,
+                updated_at = ?, completed_at = ?
+            WHERE id = ? AND status = ?
+            "#,
+            remittance.source_amount,
+            remittance.dex_amount,
+            remittance.quote_id,
+            remittance.transfer_id,
+            remittance.status,
+            remittance.failure_reason,
+            remittance.updated_at,
+            remittance.completed_at,
+            remittance.id,
+            from
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if result.rows_affected() != 1 {
+            return Ok(false);
+        }
+
+        for step in steps {
+            Self::insert_step(&mut tx, step).await?;
+        }
+
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// Claims an escrowed remittance for its DEX conversion, or takes over a
+    /// conversion untouched since `stale_before`. Returns false when another
+    /// worker or request holds it.
+    pub async fn claim_conversion(&self, id: &str, now: DateTime<Utc>, stale_before: DateTime<Utc>) -> Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE remittances
+            SET status = 'converting', updated_at = ?
+            WHERE id = ? AND (status = 'escrowed' OR (status = 'converting' AND updated_at < ?))
+            "#,
+            now,
+            id,
+            stale_before
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Whether `transfer_id` pays out a remittance.
+    pub async fn exists_for_transfer(&self, transfer_id: &str) -> Result<bool> {
+        let exists = sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM remittances WHERE transfer_id = ?) as "exists!: bool""#,
+            transfer_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(exists)
+    }
+
+    async fn insert_step(tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>, step: &RemittanceStep) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO remittance_steps (id, remittance_id, step, status, detail, tx_hash, simulated, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+            step.id,
+            step.remittance_id,
+            step.step,
+            step.status,
+            step.detail,
+            step.tx_hash,
+            step.simulated,
+            step.created_at
+        )
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find_by_id(&self, id: &str) -> Result<Option<Remittance>> {
+        let remittance = sqlx::query_as!(
+            Remittance,
+            r#"
+            SELECT id, wallet_id, public_key, amount_fiat as "amount_fiat: FiatAmount", currency, source_asset,
+                   source_amount as "source_amount: Amount", dex_asset, dex_amount as "dex_amount: Amount",
+                   quote_id, transfer_id, status, failure_reason, created_at, updated_at, completed_at
+            FROM remittances
+            WHERE id = ?
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(remittance)
+    }
+
+    pub async fn find_by_wallet(&self, wallet_id: &str) -> Result<Vec<Remittance>> {
+        let remittances = sqlx::query_as!(
+            Remittance,
+            r#"
+            SELECT id, wallet_id, public_key, amount_fiat as "amount_fiat: FiatAmount", currency, source_asset,
+                   source_amount as "source_amount: Amount", dex_asset, dex_amount as "dex_amount: Amount",
+                   quote_id, transfer_id, status, failure_reason, created_at, updated_at, completed_at
+            FROM remittances
+            WHERE wallet_id = ?
+            ORDER BY created_at DESC
+            "#,
+            wallet_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(remittances)
+    }
+
+    /// Remittances the worker has to move on: those following their transfer,
+    /// those compensating, escrowed or converting ones untouched since
+    /// `stale_before` (held for review, or interrupted by a restart) and
+    /// completed ones whose transfer was reversed afterwards.
+    pub async fn find_in_flight(&self, stale_before: DateTime<Utc>, limit: i64) -> Result<Vec<Remittance>> {
+        let remittances = sqlx::query_as!(
+            Remittance,
+            r#"
+            SELECT r.id, r.wallet_id, r.public_key, r.amount_fiat as "amount_fiat: FiatAmount", r.currency,
+                   r.source_asset, r.source_amount as "source_amount: Amount", r.dex_asset,
+                   r.dex_amount as "dex_amount: Amount", r.quote_id, r.transfer_id, r.status, r.failure_reason,
+                   r.created_at, r.updated_at, r.completed_at
+            FROM remittances r
+            LEFT JOIN bank_transfers t ON t.id = r.transfer_id
+            WHERE r.status IN ('converted', 'paying_out', 'compensating')
+               OR (r.status IN ('escrowed', 'converting') AND r.updated_at < ?)
+               OR (r.status = 'completed' AND t.status = 'reversed')
+            ORDER BY r.updated_at ASC
+            LIMIT ?
+            "#,
+            stale_before,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(remittances)
+    }
+
+    pub async fn find_steps(&self, remittance_id: &str) -> Result<Vec<RemittanceStep>> {
+        let steps = sqlx::query_as!(
+            RemittanceStep,
+            r#"
+            SELECT id, remittance_id, step, status, detail, tx_hash, simulated, created_at
+            FROM remittance_steps
+            WHERE remittance_id = ?
+            ORDER BY created_at ASC, rowid ASC
+            "#,
+            remittance_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(steps)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    /// In-memory database with the remittance tables and the parts of
+    /// `wallets` and `bank_transfers` they refer to.
+    async fn repo() -> RemittanceRepository {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::raw_sql(
+            r#"
+            CREATE TABLE wallets (id TEXT PRIMARY KEY NOT NULL);
+            CREATE TABLE bank_transfers (id TEXT PRIMARY KEY NOT NULL, status TEXT NOT NULL);
+            INSERT INTO wallets (id) VALUES ('w1');
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        for migration in [
+            include_str!("../../../migrations/020_remittances.sql"),
+            include_str!("../../../migrations/028_remittance_simulated_steps.sql"),
+        ] {
+            sqlx::raw_sql(migration).execute(&pool).await.unwrap();
+        }
+        RemittanceRepository::new(pool)
+    }
+
+    async fn remittance(
+        repo: &RemittanceRepository,
+        id: &str,
+        status: RemittanceStatus,
+        transfer_status: &str,
+        updated_at: DateTime<Utc>,
+    ) {
+        let transfer_id = format!("t-{}", id);
+        sqlx::query("INSERT INTO bank_transfers (id, status) VALUES (?, ?)")
+            .bind(&transfer_id)
+            .bind(transfer_status)
+            .execute(&repo.pool)
+            .await
+            .unwrap();
+        let remittance = Remittance {
+            id: id.to_string(),
+            wallet_id: "w1".to_string(),
+            public_key: "G".to_string(),
+            amount_fiat: "1000".parse().unwrap(),
+            currency: "MXN".to_string(),
+            source_asset: "USDC".to_string(),
+            source_amount: Some("58.5".parse().unwrap()),
+            dex_asset: "MXNT".to_string(),
+            dex_amount: None,
+            quote_id: None,
+            transfer_id: Some(transfer_id),
+            status: status.to_string(),
+            failure_reason: None,
+            created_at: updated_at,
+            updated_at,
+            completed_at: None,
+        };
+        repo.create(&remittance, &[]).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_conversion_is_claimed_once() {
+        let repo = repo().await;
+        let now = Utc::now();
+        let stale_before = now - Duration::seconds(120);
+        remittance(&repo, "r1", RemittanceStatus::Escrowed, "converting", now).await;
+
+        assert!(repo.claim_conversion("r1", now, stale_before).await.unwrap());
+        assert!(!repo.claim_conversion("r1", now, stale_before).await.unwrap());
+        assert_eq!(repo.find_by_id("r1").await.unwrap().unwrap().status, "converting");
+
+        // A conversion interrupted by a restart is taken over once stale.
+        let later = now + Duration::seconds(300);
+        assert!(repo.claim_conversion("r1", later, later - Duration::seconds(120)).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_find_in_flight() {
+        let repo = repo().await;
+        let now = Utc::now();
+        let old = now - Duration::seconds(600);
+        remittance(&repo, "held", RemittanceStatus::Escrowed, "review", old).await;
+        remittance(&repo, "fresh", RemittanceStatus::Escrowed, "converting", now).await;
+        remittance(&repo, "interrupted", RemittanceStatus::Converting, "converting", old).await;
+        remittance(&repo, "paying", RemittanceStatus::PayingOut, "sent", now).await;
+        remittance(&repo, "reversed", RemittanceStatus::Completed, "reversed", old).await;
+        remittance(&repo, "done", RemittanceStatus::Completed, "completed", old).await;
+        remittance(&repo, "failed", RemittanceStatus::Failed, "cancelled", old).await;
+
+        let mut found: Vec<String> = repo
+            .find_in_flight(now - Duration::seconds(120), 50)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|r| r.id)
+            .collect();
+        found.sort();
+        assert_eq!(found, ["held", "interrupted", "paying", "reversed"]);
+
+        assert!(repo.exists_for_transfer("t-held").await.unwrap());
+        assert!(!repo.exists_for_transfer("t-other").await.unwrap());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,499 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::{SqliteConnection, SqlitePool};
+use crate::modules::models::amount::Amount;
+use crate::modules::models::bank::TransferStatus;
+use crate::modules::models::review::{ReviewNote, ReviewStatus, TransferReview};
+use crate::modules::repositories::bank_transfer_repo::BankTransferRepository;
+
+/// Transfer status change stored in the same transaction as a review decision.
+#[derive(Debug, Clone)]
+pub struct TransferMove<'a> {
+    pub transfer_id: &'a str,
+    pub from: TransferStatus,
+    pub to: TransferStatus,
+    pub reason: &'a str,
+}
+
+/// Result of [`ReviewRepository::decide`]. Unless it is `Decided`, nothing
+/// was stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecideOutcome {
+    Decided,
+    /// The review changed or was reassigned first.
+    ReviewChanged,
+    /// The transfer was no longer in the expected status.
+    TransferChanged,
+}
+
+#[derive(Clone)]
+pub struct ReviewRepository {
+    pool: SqlitePool,
+}
+
+impl ReviewRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create_with(conn: &mut SqliteConnection, review: &TransferReview) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO transfer_reviews
+            (id, transfer_id, status, reason, amount_usd, requires_second_approval, assigned_to, claimed_at,
+             first_approved_by, first_approved_at, decided_by, decided_at, sla_due_at, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+            review.id,
+            review.transfer_id,
+            review.status,
+            review.reason,
+            review.amount_usd,
+            review.requires_second_approval,
+            review.assigned_to,
+            review.claimed_at,
+            review.first_approved_by,
+            review.first_approved_at,
+            review.decided_by,
+            review.decided_at,
+            review.sla_due_at,
+            review.created_at
+        )
+        .execute(conn)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find_by_id(&self, id: &str) -> Result<Option<TransferReview>> {
+        let review = sqlx::query_as!(
+            TransferReview,
+            r#"
+            SELECT id, transfer_id, status, reason, amount_usd as "amount_usd: Amount", requires_second_approval,
+                   assigned_to, claimed_at, first_approved_by, first_approved_at, decided_by, decided_at,
+                   sla_due_at, created_at
+            FROM transfer_reviews
+            WHERE id = ?
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(review)
+    }
+
+    /// Reviews in `status`, the ones closest to their SLA first.
+    pub async fn find_by_status(&self, status: ReviewStatus) -> Result<Vec<TransferReview>> {
+        let status = status.as_str();
+        let reviews = sqlx::query_as!(
+            TransferReview,
+            r#"
+            SELECT id, transfer_id, status, reason, amount_usd as "amount_usd: Amount", requires_second_approval,
+                   assigned_to, claimed_at, first_approved_by, first_approved_at, decided_by, decided_at,
+                   sla_due_at, created_at
+            FROM transfer_reviews
+            WHERE status = ?
+            ORDER BY sla_due_at ASC
+            "#,
+            status
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(reviews)
+    }
+
+    pub async fn find_notes(&self, review_id: &str) -> Result<Vec<ReviewNote>> {
+        let notes = sqlx::query_as!(
+            ReviewNote,
+            r#"
+            SELECT id, review_id, reviewer, action, note, created_at
+            FROM transfer_review_notes
+            WHERE review_id = ?
+            ORDER BY created_at ASC
+            "#,
+            review_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(notes)
+    }
+
+    /// Assigns an undecided review to `reviewer` unless someone else holds it
+    /// or `reviewer` gave the first approval. Returns `false` if it was not claimed.
+    pub async fn claim(&self, id: &str, reviewer: &str, now: DateTime<Utc>) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE transfer_reviews
+            SET assigned_to = ?, claimed_at = COALESCE(claimed_at, ?)
+            WHERE id = ? AND status IN ('open', 'awaiting_second_approval')
+              AND (assigned_to IS NULL OR assigned_to = ?)
+              AND (first_approved_by IS NULL OR first_approved_by != ?)
+            "#,
+            reviewer,
+            now,
+            id,
+            reviewer,
+            reviewer
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if result.rows_affected() != 1 {
+            return Ok(false);
+        }
+
+        Self::insert_note(&mut tx, id, reviewer, "claim", None, now).await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// Records the first of two approvals and hands the review back to the
+    /// queue. Returns `false` unless it was open and claimed by `reviewer`.
+    pub async fn approve_first(
+        &self,
+        id: &str,
+        reviewer: &str,
+        note: Option<&str>,
+        now: DateTime<Utc>,
+    ) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE transfer_reviews
+            SET status = 'awaiting_second_approval', first_approved_by = ?, first_approved_at = ?, assigned_to = NULL
+            WHERE id = ? AND status = 'open' AND assigned_to = ?
+            "#,
+            reviewer,
+            now,
+            id,
+            reviewer
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if result.rows_affected() != 1 {
+            return Ok(false);
+        }
+
+        Self::insert_note(&mut tx, id, reviewer, "first_approval", note, now).await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// Approves or rejects a review `reviewer` holds in `from` and moves its
+    /// transfer, both or neither. A rejection also stores the reason on the
+    /// transfer.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn decide(
+        &self,
+        id: &str,
+        from: ReviewStatus,
+        to: ReviewStatus,
+        reviewer: &str,
+        note: Option<&str>,
+        transfer: &TransferMove<'_>,
+        now: DateTime<Utc>,
+    ) -> Result<DecideOutcome> {
+        let from = from.as_str();
+        let to = to.as_str();
+        let mut tx = self.pool.begin().await?;
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE transfer_reviews
+            SET status = ?, decided_by = ?, decided_at = ?
+            WHERE id = ? AND status = ? AND assigned_to = ?
+            "#,
+            to,
+            reviewer,
+            now,
+            id,
+            from,
+            reviewer
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if result.rows_affected() != 1 {
+            return Ok(DecideOutcome::ReviewChanged);
+        }
+
+        let moved = BankTransferRepository::transition_with(
+            &mut tx,
+            transfer.transfer_id,
+            transfer.from,
+            transfer.to,
+            transfer.reason,
+            now,
+        )
+        .await?;
+        if !moved {
+            return Ok(DecideOutcome::TransferChanged);
+        }
+        if transfer.to == TransferStatus::Rejected {
+            BankTransferRepository::set_rejection_reason_with(&mut tx, transfer.transfer_id, transfer.reason).await?;
+        }
+
+        let action = if to == ReviewStatus::Approved.as_str() { "approve" } else { "reject" };
+        Self::insert_note(&mut tx, id, reviewer, action, note, now).await?;
+        tx.commit().await?;
+        Ok(DecideOutcome::Decided)
+    }
+
+    /// Closes the undecided review of a transfer that was cancelled. Returns
+    /// `false` if it has none.
+    pub async fn cancel_for_transfer(
+        &self,
+        transfer_id: &str,
+        actor: &str,
+        note: Option<&str>,
+        now: DateTime<Utc>,
+    ) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+
+        let review_id = sqlx::query_scalar!(
+            r#"
+            SELECT id FROM transfer_reviews
+            WHERE transfer_id = ? AND status IN ('open', 'awaiting_second_approval')
+            "#,
+            transfer_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(review_id) = review_id else {
+            return Ok(false);
+        };
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE transfer_reviews
+            SET status = 'cancelled', decided_by = ?, decided_at = ?, assigned_to = NULL
+            WHERE id = ? AND status IN ('open', 'awaiting_second_approval')
+            "#,
+            actor,
+            now,
+            review_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if result.rows_affected() != 1 {
+            return Ok(false);
+        }
+
+        Self::insert_note(&mut tx, &review_id, actor, "cancel", note, now).await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    async fn insert_note(
+        conn: &mut SqliteConnection,
+        review_id: &str,
+        reviewer: &str,
+        action: &str,
+        note: Option<&str>,
+        now: DateTime<Utc>,
+    ) -> Result<()> {
+        let id = uuid::Uuid::new_v4().to_string();
+        sqlx::query!(
+            r#"
+            INSERT INTO transfer_review_notes (id, review_id, reviewer, action, note, created_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+            id,
+            review_id,
+            reviewer,
+            action,
+            note,
+            now
+        )
+        .execute(conn)
+        .await?;
+        Ok(())
+    }
+}
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    /// In-memory database with the review tables and the parts of
+    /// `bank_transfers` a review decision touches, holding transfer `t1` in `status`.
+    pub(crate) async fn pool_with_transfer(status: &str) -> SqlitePool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::raw_sql(
+            r#"
+            CREATE TABLE bank_transfers (
+                id TEXT PRIMARY KEY NOT NULL,
+                status TEXT NOT NULL,
+                rejection_reason TEXT,
+                completed_at DATETIME
+            );
+            CREATE TABLE bank_transfer_transitions (
+                id TEXT PRIMARY KEY NOT NULL,
+                transfer_id TEXT NOT NULL REFERENCES bank_transfers(id),
+                from_status TEXT,
+                to_status TEXT NOT NULL,
+                reason TEXT NOT NULL,
+                created_at DATETIME NOT NULL
+            );
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::raw_sql(include_str!("../../../migrations/014_transfer_reviews.sql"))
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO bank_transfers (id, status) VALUES ('t1', ?)")
+            .bind(status)
+            .execute(&pool)
+            .await
+            .unwrap();
+        pool
+    }
+
+    async fn open_review(repo: &ReviewRepository) -> TransferReview {
+        let now = Utc::now();
+        let review = TransferReview {
+            id: "r1".to_string(),
+            transfer_id: "t1".to_string(),
+            status: ReviewStatus::Open.to_string(),
+            reason: "Flagged by risk rules".to_string(),
+            amount_usd: None,
+            requires_second_approval: false,
+            assigned_to: None,
+            claimed_at: None,
+            first_approved_by: None,
+            first_approved_at: None,
+            decided_by: None,
+            decided_at: None,
+            sla_due_at: now,
+            created_at: now,
+        };
+        ReviewRepository::create_with(&mut repo.pool.acquire().await.unwrap(), &review)
+            .await
+            .unwrap();
+        review
+    }
+
+    async fn transfer_status(pool: &SqlitePool) -> (String, Option<String>) {
+        sqlx::query_as("SELECT status, rejection_reason FROM bank_transfers WHERE id = 't1'")
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    fn approval(to: TransferStatus) -> TransferMove<'static> {
+        TransferMove { transfer_id: "t1", from: TransferStatus::Review, to, reason: "decided" }
+    }
+
+    #[tokio::test]
+    async fn test_decide_moves_the_transfer() {
+        let pool = pool_with_transfer("review").await;
+        let repo = ReviewRepository::new(pool.clone());
+        open_review(&repo).await;
+        let now = Utc::now();
+
+        // Only the reviewer holding the claim can decide.
+        let move_to_pending = approval(TransferStatus::Pending);
+        let outcome = repo
+            .decide("r1", ReviewStatus::Open, ReviewStatus::Approved, "alice", None, &move_to_pending, now)
+            .await
+            .unwrap();
+        assert_eq!(outcome, DecideOutcome::ReviewChanged);
+
+        assert!(repo.claim("r1", "alice", now).await.unwrap());
+        assert!(!repo.claim("r1", "bob", now).await.unwrap());
+        let outcome = repo
+            .decide("r1", ReviewStatus::Open, ReviewStatus::Approved, "alice", None, &move_to_pending, now)
+            .await
+            .unwrap();
+        assert_eq!(outcome, DecideOutcome::Decided);
+
+        assert_eq!(transfer_status(&pool).await, ("pending".to_string(), None));
+        let review = repo.find_by_id("r1").await.unwrap().unwrap();
+        assert_eq!(review.status, "approved");
+        assert_eq!(review.decided_by.as_deref(), Some("alice"));
+        let actions: Vec<String> = repo.find_notes("r1").await.unwrap().into_iter().map(|n| n.action).collect();
+        assert_eq!(actions, ["claim", "approve"]);
+    }
+
+    #[tokio::test]
+    async fn test_decide_leaves_the_review_open_when_the_transfer_moved() {
+        let pool = pool_with_transfer("cancelled").await;
+        let repo = ReviewRepository::new(pool.clone());
+        open_review(&repo).await;
+        let now = Utc::now();
+
+        assert!(repo.claim("r1", "alice", now).await.unwrap());
+        let outcome = repo
+            .decide("r1", ReviewStatus::Open, ReviewStatus::Approved, "alice", None, &approval(TransferStatus::Pending), now)
+            .await
+            .unwrap();
+        assert_eq!(outcome, DecideOutcome::TransferChanged);
+
+        assert_eq!(transfer_status(&pool).await, ("cancelled".to_string(), None));
+        let review = repo.find_by_id("r1").await.unwrap().unwrap();
+        assert_eq!(review.status, "open");
+        assert_eq!(review.decided_by, None);
+        assert_eq!(repo.find_notes("r1").await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_reject_stores_the_reason_on_the_transfer() {
+        let pool = pool_with_transfer("review").await;
+        let repo = ReviewRepository::new(pool.clone());
+        open_review(&repo).await;
+        let now = Utc::now();
+
+        assert!(repo.claim("r1", "alice", now).await.unwrap());
+        let outcome = repo
+            .decide(
+                "r1",
+                ReviewStatus::Open,
+                ReviewStatus::Rejected,
+                "alice",
+                Some("mule account"),
+                &approval(TransferStatus::Rejected),
+                now,
+            )
+            .await
+            .unwrap();
+        assert_eq!(outcome, DecideOutcome::Decided);
+        assert_eq!(
+            transfer_status(&pool).await,
+            ("rejected".to_string(), Some("decided".to_string()))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cancel_for_transfer_closes_the_open_review() {
+        let pool = pool_with_transfer("cancelled").await;
+        let repo = ReviewRepository::new(pool.clone());
+        open_review(&repo).await;
+        let now = Utc::now();
+
+        assert!(repo.claim("r1", "alice", now).await.unwrap());
+        assert!(repo.cancel_for_transfer("t1", "GOWNER", Some("changed my mind"), now).await.unwrap());
+
+        let review = repo.find_by_id("r1").await.unwrap().unwrap();
+        assert_eq!(review.status, "cancelled");
+        assert_eq!(review.decided_by.as_deref(), Some("GOWNER"));
+        assert_eq!(review.assigned_to, None);
+        let notes = repo.find_notes("r1").await.unwrap();
+        assert_eq!(notes.last().map(|n| n.action.as_str()), Some("cancel"));
+
+        // Nothing left open for the transfer.
+        assert!(!repo.cancel_for_transfer("t1", "GOWNER", None, now).await.unwrap());
+        assert_eq!(repo.find_notes("r1").await.unwrap().len(), 2);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,96 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use crate::modules::models::amount::Amount;
+use crate::modules::models::risk::{RiskDecision, RiskHistoryEntry, VelocityScope};
+
+#[derive(Clone)]
+pub struct RiskRepository {
+    pool: SqlitePool,
+}
+
+impl RiskRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(&self, decision: &RiskDecision) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO risk_decisions
+            (id, operation, wallet_id, public_key, reference, amount_usd, bank_account_hash, ip_address, action, rules, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+            decision.id,
+            decision.operation,
+            decision.wallet_id,
+            decision.public_key,
+            decision.reference,
+            decision.amount_usd,
+            decision.bank_account_hash,
+            decision.ip_address,
+            decision.action,
+            decision.rules,
+            decision.created_at
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn set_reference(&self, id: &str, reference: &str) -> Result<()> {
+        sqlx::query!("UPDATE risk_decisions SET reference = ? WHERE id = ?", reference, id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Operations that were not blocked for `key` in `scope` since `since`.
+    pub async fn history(&self, scope: VelocityScope, key: &str, since: DateTime<Utc>) -> Result<Vec<RiskHistoryEntry>> {
+        let entries = match scope {
+            VelocityScope::Wallet => {
+                sqlx::query_as!(
+                    RiskHistoryEntry,
+                    r#"
+                    SELECT operation, amount_usd as "amount_usd: Amount"
+                    FROM risk_decisions
+                    WHERE wallet_id = ? AND created_at >= ? AND action != 'block'
+                    "#,
+                    key,
+                    since
+                )
+                .fetch_all(&self.pool)
+                .await?
+            }
+            VelocityScope::BankAccount => {
+                sqlx::query_as!(
+                    RiskHistoryEntry,
+                    r#"
+                    SELECT operation, amount_usd as "amount_usd: Amount"
+                    FROM risk_decisions
+                    WHERE bank_account_hash = ? AND created_at >= ? AND action != 'block'
+                    "#,
+                    key,
+                    since
+                )
+                .fetch_all(&self.pool)
+                .await?
+            }
+            VelocityScope::Ip => {
+                sqlx::query_as!(
+                    RiskHistoryEntry,
+                    r#"
+                    SELECT operation, amount_usd as "amount_usd: Amount"
+                    FROM risk_decisions
+                    WHERE ip_address = ? AND created_at >= ? AND action != 'block'
+                    "#,
+                    key,
+                    since
+                )
+                .fetch_all(&self.pool)
+                .await?
+            }
+        };
+        Ok(entries)
+    }
+}
\ No newline at end of file
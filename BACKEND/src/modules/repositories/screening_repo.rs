@@ -0,0 +1,77 @@
+use anyhow::Result;
+use sqlx::SqlitePool;
+use crate::modules::models::screening::{ScreeningList, ScreeningResult};
+
+#[derive(Clone)]
+pub struct ScreeningRepository {
+    pool: SqlitePool,
+}
+
+impl ScreeningRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn find_list(&self, source: &str, version: &str) -> Result<Option<ScreeningList>> {
+        let list = sqlx::query_as!(
+            ScreeningList,
+            r#"
+            SELECT id, source, path, version, entry_count, loaded_at
+            FROM screening_lists
+            WHERE source = ? AND version = ?
+            "#,
+            source,
+            version
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(list)
+    }
+
+    pub async fn create_list(&self, list: &ScreeningList) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO screening_lists (id, source, path, version, entry_count, loaded_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+            list.id,
+            list.source,
+            list.path,
+            list.version,
+            list.entry_count,
+            list.loaded_at
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn create_result(&self, result: &ScreeningResult) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO screening_results
+            (id, operation, wallet_id, reference, action, subject, matches, list_ids, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+            result.id,
+            result.operation,
+            result.wallet_id,
+            result.reference,
+            result.action,
+            result.subject,
+            result.matches,
+            result.list_ids,
+            result.created_at
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn set_reference(&self, id: &str, reference: &str) -> Result<()> {
+        sqlx::query!("UPDATE screening_results SET reference = ? WHERE id = ?", reference, id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -1,5 +1,7 @@
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use sqlx::SqlitePool;
+use crate::modules::models::amount::{Amount, FixedPoint};
 use crate::modules::models::transaction::Transaction;
 
 #[derive(Clone)]
@@ -13,10 +15,17 @@ impl TransactionRepository {
     }
 
     pub async fn create(&self, tx: &Transaction) -> Result<()> {
+        let mut conn = self.pool.acquire().await?;
+        Self::create_with(&mut conn, tx).await
+    }
+
+    /// [`TransactionRepository::create`] on a connection, so the row can
+    /// commit or roll back with the operation it records.
+    pub async fn create_with(conn: &mut sqlx::SqliteConnection, tx: &Transaction) -> Result<()> {
         sqlx::query!(
             r#"
-            INSERT INTO transactions (id, wallet_id, tx_hash, tx_type, from_address, to_address, amount, asset, status, created_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            INSERT INTO transactions (id, wallet_id, tx_hash, tx_type, from_address, to_address, amount, asset, asset_issuer, dest_amount, dest_asset, dest_asset_issuer, fee_amount, fee_asset, simulated, bank_transfer_id, status, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
             tx.id,
             tx.wallet_id,
@@ -26,10 +35,18 @@ impl TransactionRepository {
             tx.to_address,
             tx.amount,
             tx.asset,
+            tx.asset_issuer,
+            tx.dest_amount,
+            tx.dest_asset,
+            tx.dest_asset_issuer,
+            tx.fee_amount,
+            tx.fee_asset,
+            tx.simulated,
+            tx.bank_transfer_id,
             tx.status,
             tx.created_at
         )
-        .execute(&self.pool)
+        .execute(conn)
         .await?;
         Ok(())
     }
@@ -37,7 +54,7 @@ impl TransactionRepository {
     pub async fn find_by_wallet_id(&self, wallet_id: &str, limit: i64) -> Result<Vec<Transaction>> {
         let transactions = sqlx::query_as!(
             Transaction,
-            "SELECT id, wallet_id, tx_hash, tx_type, from_address, to_address, amount, asset, status, created_at 
+            "SELECT id, wallet_id, tx_hash, tx_type, from_address, to_address, amount as \"amount: Amount\", asset, asset_issuer, dest_amount as \"dest_amount: Amount\", dest_asset, dest_asset_issuer, fee_amount as \"fee_amount: Amount\", fee_asset, simulated, bank_transfer_id, status, created_at 
              FROM transactions 
              WHERE wallet_id = ? 
              ORDER BY created_at DESC 
@@ -54,7 +71,7 @@ impl TransactionRepository {
         let transactions = sqlx::query_as!(
             Transaction,
             r#"
-            SELECT t.id, t.wallet_id, t.tx_hash, t.tx_type, t.from_address, t.to_address, t.amount, t.asset, t.status, t.created_at
+            SELECT t.id, t.wallet_id, t.tx_hash, t.tx_type, t.from_address, t.to_address, t.amount as "amount: Amount", t.asset, t.asset_issuer, t.dest_amount as "dest_amount: Amount", t.dest_asset, t.dest_asset_issuer, t.fee_amount as "fee_amount: Amount", t.fee_asset, t.simulated, t.bank_transfer_id, t.status, t.created_at
             FROM transactions t
             INNER JOIN wallets w ON t.wallet_id = w.id
             WHERE w.public_key = ?
@@ -71,7 +88,7 @@ impl TransactionRepository {
 
     pub async fn count_by_wallet_id(&self, wallet_id: &str) -> Result<i64> {
         let result = sqlx::query_scalar!(
-            "SELECT COUNT(*) as count FROM transactions WHERE wallet_id = ?",
+            "SELECT COUNT(*) as count FROM transactions WHERE wallet_id = ? AND tx_type != 'release'",
             wallet_id
         )
         .fetch_one(&self.pool)
@@ -79,13 +96,109 @@ impl TransactionRepository {
         Ok(result)
     }
 
-    pub async fn sum_volume_by_wallet_id(&self, wallet_id: &str) -> Result<f64> {
-        let result = sqlx::query_scalar!(
-            "SELECT COALESCE(SUM(CAST(amount AS REAL)), 0.0) as total FROM transactions WHERE wallet_id = ? AND status = 'completed'",
+    pub async fn sum_volume_by_wallet_id(&self, wallet_id: &str) -> Result<Amount> {
+        // Summed here rather than in SQL, where the TEXT amounts would go through REAL.
+        // Released escrow is money coming back, not activity.
+        let amounts = sqlx::query_scalar!(
+            r#"SELECT amount as "amount: Amount" FROM transactions WHERE wallet_id = ? AND status = 'completed' AND tx_type != 'release'"#,
+            wallet_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let total = amounts
+            .into_iter()
+            .try_fold(Amount::ZERO, |total, amount| total.checked_add(amount))?;
+        Ok(total)
+    }
+
+    pub async fn find_by_bank_transfer(&self, bank_transfer_id: &str) -> Result<Vec<Transaction>> {
+        let transactions = sqlx::query_as!(
+            Transaction,
+            r#"
+            SELECT id, wallet_id, tx_hash, tx_type, from_address, to_address, amount as "amount: Amount", asset, asset_issuer, dest_amount as "dest_amount: Amount", dest_asset, dest_asset_issuer, fee_amount as "fee_amount: Amount", fee_asset, simulated, bank_transfer_id, status, created_at
+            FROM transactions
+            WHERE bank_transfer_id = ?
+            ORDER BY created_at ASC
+            "#,
+            bank_transfer_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(transactions)
+    }
+
+    /// What the wallet's bank transfers have taken of `asset` (code and
+    /// issuer, `None` for XLM): escrow that is still held and escrow that was
+    /// settled, unless it was released back. Escrow never reaches the network,
+    /// so settled funds are still in the on-chain balance and must keep being
+    /// subtracted from it.
+    pub async fn sum_committed(&self, wallet_id: &str, asset: &str, issuer: Option<&str>) -> Result<Amount> {
+        let mut conn = self.pool.acquire().await?;
+        Self::sum_committed_with(&mut conn, wallet_id, asset, issuer).await
+    }
+
+    pub async fn sum_committed_with(
+        conn: &mut sqlx::SqliteConnection,
+        wallet_id: &str,
+        asset: &str,
+        issuer: Option<&str>,
+    ) -> Result<Amount> {
+        let amounts = sqlx::query_scalar!(
+            r#"
+            SELECT e.amount as "amount: Amount"
+            FROM transactions e
+            WHERE e.wallet_id = ? AND e.asset = ? AND e.asset_issuer IS ?
+              AND e.tx_type = 'escrow' AND e.status IN ('pending', 'completed')
+              AND NOT EXISTS (
+                  SELECT 1 FROM transactions r
+                  WHERE r.bank_transfer_id = e.bank_transfer_id AND r.tx_type = 'release'
+              )
+            "#,
+            wallet_id,
+            asset,
+            issuer
+        )
+        .fetch_all(conn)
+        .await?;
+
+        let total = amounts
+            .into_iter()
+            .try_fold(Amount::ZERO, |total, amount| total.checked_add(amount))?;
+        Ok(total)
+    }
+
+    /// When the wallet last received funds, e.g. from Friendbot.
+    pub async fn last_funded_at(&self, wallet_id: &str) -> Result<Option<DateTime<Utc>>> {
+        let funded_at = sqlx::query_scalar!(
+            r#"SELECT created_at as "created_at: DateTime<Utc>" FROM transactions WHERE wallet_id = ? AND tx_type = 'receive' ORDER BY created_at DESC LIMIT 1"#,
             wallet_id
         )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(funded_at)
+    }
+
+    /// Sends, conversions and escrows the wallet made after `since`.
+    pub async fn count_outgoing_since(&self, wallet_id: &str, since: DateTime<Utc>) -> Result<i64> {
+        let result = sqlx::query_scalar!(
+            "SELECT COUNT(*) as count FROM transactions WHERE wallet_id = ? AND tx_type IN ('send', 'convert', 'escrow') AND created_at > ?",
+            wallet_id,
+            since
+        )
         .fetch_one(&self.pool)
         .await?;
         Ok(result)
     }
+
+    pub async fn update_status(&self, id: &str, status: &str) -> Result<()> {
+        sqlx::query!(
+            "UPDATE transactions SET status = ? WHERE id = ?",
+            status,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
 }
\ No newline at end of file
@@ -0,0 +1,266 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use crate::modules::models::webhook::{DeliveryStatus, WebhookDelivery, WebhookEvent, WebhookSubscription};
+
+#[derive(Clone)]
+pub struct WebhookRepository {
+    pool: SqlitePool,
+}
+
+impl WebhookRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create_subscription(&self, subscription: &WebhookSubscription) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO webhook_subscriptions (id, url, event_types, secret, description, active, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+            subscription.id,
+            subscription.url,
+            subscription.event_types,
+            subscription.secret,
+            subscription.description,
+            subscription.active,
+            subscription.created_at,
+            subscription.updated_at
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find_subscription(&self, id: &str) -> Result<Option<WebhookSubscription>> {
+        let subscription = sqlx::query_as!(
+            WebhookSubscription,
+            r#"
+            SELECT id, url, event_types, secret, description, active, created_at, updated_at
+            FROM webhook_subscriptions
+            WHERE id = ?
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(subscription)
+    }
+
+    pub async fn find_active_subscriptions(&self) -> Result<Vec<WebhookSubscription>> {
+        let subscriptions = sqlx::query_as!(
+            WebhookSubscription,
+            r#"
+            SELECT id, url, event_types, secret, description, active, created_at, updated_at
+            FROM webhook_subscriptions
+            WHERE active = 1
+            ORDER BY created_at ASC
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(subscriptions)
+    }
+
+    /// Stops deliveries to a subscription; its history is kept.
+    pub async fn deactivate(&self, id: &str, now: DateTime<Utc>) -> Result<bool> {
+        let result = sqlx::query!(
+            "UPDATE webhook_subscriptions SET active = 0, updated_at = ? WHERE id = ? AND active = 1",
+            now,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Inserts the event together with its first delivery to each subscription.
+    pub async fn create_event(&self, event: &WebhookEvent, deliveries: &[WebhookDelivery]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!(
+            "INSERT INTO webhook_events (id, event_type, payload, created_at) VALUES (?, ?, ?, ?)",
+            event.id,
+            event.event_type,
+            event.payload,
+            event.created_at
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        for delivery in deliveries {
+            sqlx::query!(
+                r#"
+                INSERT INTO webhook_deliveries
+                (id, event_id, subscription_id, status, attempts, next_attempt_at, last_status_code, last_error,
+                 delivered_at, created_at, updated_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+                delivery.id,
+                delivery.event_id,
+                delivery.subscription_id,
+                delivery.status,
+                delivery.attempts,
+                delivery.next_attempt_at,
+                delivery.last_status_code,
+                delivery.last_error,
+                delivery.delivered_at,
+                delivery.created_at,
+                delivery.updated_at
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn find_event(&self, id: &str) -> Result<Option<WebhookEvent>> {
+        let event = sqlx::query_as!(
+            WebhookEvent,
+            "SELECT id, event_type, payload, created_at FROM webhook_events WHERE id = ?",
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(event)
+    }
+
+    /// Pending deliveries to active subscriptions whose next attempt is due, the oldest first.
+    pub async fn find_due_deliveries(&self, now: DateTime<Utc>, limit: i64) -> Result<Vec<WebhookDelivery>> {
+        let deliveries = sqlx::query_as!(
+            WebhookDelivery,
+            r#"
+            SELECT d.id, d.event_id, d.subscription_id, d.status, d.attempts, d.next_attempt_at,
+                   d.last_status_code, d.last_error, d.delivered_at, d.created_at, d.updated_at
+            FROM webhook_deliveries d
+            JOIN webhook_subscriptions s ON s.id = d.subscription_id
+            WHERE d.status = 'pending' AND d.next_attempt_at <= ? AND s.active = 1
+            ORDER BY d.next_attempt_at ASC
+            LIMIT ?
+            "#,
+            now,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(deliveries)
+    }
+
+    pub async fn find_deliveries_by_status(&self, status: DeliveryStatus) -> Result<Vec<WebhookDelivery>> {
+        let status = status.as_str();
+        let deliveries = sqlx::query_as!(
+            WebhookDelivery,
+            r#"
+            SELECT id, event_id, subscription_id, status, attempts, next_attempt_at,
+                   last_status_code, last_error, delivered_at, created_at, updated_at
+            FROM webhook_deliveries
+            WHERE status = ?
+            ORDER BY updated_at DESC
+            "#,
+            status
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(deliveries)
+    }
+
+    pub async fn find_delivery(&self, event_id: &str, subscription_id: &str) -> Result<Option<WebhookDelivery>> {
+        let delivery = sqlx::query_as!(
+            WebhookDelivery,
+            r#"
+            SELECT id, event_id, subscription_id, status, attempts, next_attempt_at,
+                   last_status_code, last_error, delivered_at, created_at, updated_at
+            FROM webhook_deliveries
+            WHERE event_id = ? AND subscription_id = ?
+            "#,
+            event_id,
+            subscription_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(delivery)
+    }
+
+    /// Queues the event for the subscription again with a fresh set of
+    /// attempts, whatever happened to earlier deliveries.
+    pub async fn requeue(&self, delivery: &WebhookDelivery) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO webhook_deliveries
+            (id, event_id, subscription_id, status, attempts, next_attempt_at, last_status_code, last_error,
+             delivered_at, created_at, updated_at)
+            VALUES (?, ?, ?, 'pending', 0, ?, NULL, NULL, NULL, ?, ?)
+            ON CONFLICT(event_id, subscription_id) DO UPDATE SET
+                status = 'pending',
+                attempts = 0,
+                next_attempt_at = excluded.next_attempt_at,
+                last_status_code = NULL,
+                last_error = NULL,
+                updated_at = excluded.updated_at
+            "#,
+            delivery.id,
+            delivery.event_id,
+            delivery.subscription_id,
+            delivery.next_attempt_at,
+            delivery.created_at,
+            delivery.updated_at
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn mark_delivered(&self, id: &str, attempts: i64, status_code: i64, now: DateTime<Utc>) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE webhook_deliveries
+            SET status = 'delivered', attempts = ?, last_status_code = ?, last_error = NULL, delivered_at = ?, updated_at = ?
+            WHERE id = ?
+            "#,
+            attempts,
+            status_code,
+            now,
+            now,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Records a failed attempt; `status` is `pending` with the next attempt
+    /// time, or `dead` once the attempts ran out.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn mark_failed(
+        &self,
+        id: &str,
+        status: DeliveryStatus,
+        attempts: i64,
+        next_attempt_at: DateTime<Utc>,
+        status_code: Option<i64>,
+        error: &str,
+        now: DateTime<Utc>,
+    ) -> Result<()> {
+        let status = status.as_str();
+        sqlx::query!(
+            r#"
+            UPDATE webhook_deliveries
+            SET status = ?, attempts = ?, next_attempt_at = ?, last_status_code = ?, last_error = ?, updated_at = ?
+            WHERE id = ?
+            "#,
+            status,
+            attempts,
+            next_attempt_at,
+            status_code,
+            error,
+            now,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -1,46 +1,195 @@
 use anyhow::{Context, Result};
 use std::sync::Arc;
 use chrono::Utc;
+use serde_json::json;
 
-use crate::modules::models::bank::{BankTransfer, BankTransferDetails};
+use crate::modules::models::amount::{Amount, FiatAmount, FixedPoint, Rounding};
+use crate::modules::models::bank::{
+    BankTransfer, BankTransferDetails, BankTransferRequest, BankTransferTransition, TransferCursor, TransferFilter,
+    TransferListQuery, TransferListResponse, TransferStatus,
+};
+use crate::modules::models::quote::{Quote, QuoteAsset, QuoteRequest};
+use crate::modules::models::review::{ReviewStatus, TransferReview};
+use crate::modules::models::risk::{RiskAction, RiskOperation, RiskSubject};
+use crate::modules::models::screening::ScreeningSubject;
+use crate::modules::models::transaction::{Transaction, TransactionStatus, TransactionType};
+use crate::modules::models::wallet::Wallet;
+use crate::modules::models::webhook::bank_transfer_event;
 use crate::modules::repositories::{
-    bank_transfer_repo::BankTransferRepository,
+    bank_transfer_repo::{BankTransferRepository, CreateOutcome},
+    remittance_repo::RemittanceRepository,
+    transaction_repo::TransactionRepository,
     wallet_repo::WalletRepository,
 };
-use crate::modules::services::reputation_service::ReputationService;
-use crate::error::AppError;
+use crate::modules::services::{
+    beneficiary_service::BeneficiaryService,
+    kyc_service::KycService,
+    payout_provider::{PayoutDestination, PayoutProvider, PayoutRejected, PayoutRequest, PayoutRouter, PayoutState},
+    quote_service::QuoteService,
+    reputation_service::ReputationService,
+    review_service::ReviewService,
+    risk_service::RiskService,
+    screening_service::ScreeningService,
+    stellar_service::StellarService,
+    token_registry::TokenRegistry,
+    wallet_service::check_available,
+    webhook_service::WebhookService,
+};
+use crate::error::{AppError, FieldError};
+use crate::utils::bank_account::{self, BankAccount};
+use crate::utils::encryption::FieldCipher;
+
+/// Transfers handled per status on each worker pass.
+const WORKER_BATCH_SIZE: i64 = 50;
+
+/// Funds transfers when the request names no source asset.
+const DEFAULT_SOURCE_ASSET: &str = "usdc";
+
+const DEFAULT_TRANSFER_PAGE_SIZE: i64 = 50;
+const MAX_TRANSFER_PAGE_SIZE: i64 = 200;
 
 #[derive(Clone)]
 pub struct BankService {
     bank_transfer_repo: Arc<BankTransferRepository>,
     wallet_repo: Arc<WalletRepository>,
+    transaction_repo: Arc<TransactionRepository>,
+    remittance_repo: Arc<RemittanceRepository>,
     reputation_service: Arc<ReputationService>,
+    risk_service: Arc<RiskService>,
+    review_service: Arc<ReviewService>,
+    screening_service: Arc<ScreeningService>,
+    kyc_service: Arc<KycService>,
+    beneficiary_service: Arc<BeneficiaryService>,
+    webhook_service: Arc<WebhookService>,
+    stellar_service: Arc<StellarService>,
+    quote_service: Arc<QuoteService>,
+    payouts: PayoutRouter,
+    /// Encrypts the beneficiary details stored for the payout worker.
+    cipher: FieldCipher,
+    tokens: Arc<TokenRegistry>,
+    /// Account escrowed funds are sent to; without one they are only locked.
+    settlement_account: Option<String>,
+    reject_fallback_rates: bool,
+    /// Transfers scoring less than this above the reputation threshold are held for review.
+    review_reputation_margin: u8,
 }
 
 impl BankService {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         bank_transfer_repo: Arc<BankTransferRepository>,
         wallet_repo: Arc<WalletRepository>,
+        transaction_repo: Arc<TransactionRepository>,
+        remittance_repo: Arc<RemittanceRepository>,
         reputation_service: Arc<ReputationService>,
+        risk_service: Arc<RiskService>,
+        review_service: Arc<ReviewService>,
+        screening_service: Arc<ScreeningService>,
+        kyc_service: Arc<KycService>,
+        beneficiary_service: Arc<BeneficiaryService>,
+        webhook_service: Arc<WebhookService>,
+        stellar_service: Arc<StellarService>,
+        quote_service: Arc<QuoteService>,
+        payouts: PayoutRouter,
+        cipher: FieldCipher,
+        tokens: Arc<TokenRegistry>,
+        settlement_account: Option<String>,
+        reject_fallback_rates: bool,
+        review_reputation_margin: u8,
     ) -> Self {
         Self {
             bank_transfer_repo,
             wallet_repo,
+            transaction_repo,
+            remittance_repo,
             reputation_service,
+            risk_service,
+            review_service,
+            screening_service,
+            kyc_service,
+            beneficiary_service,
+            webhook_service,
+            stellar_service,
+            quote_service,
+            payouts,
+            cipher,
+            tokens,
+            settlement_account,
+            reject_fallback_rates,
+            review_reputation_margin,
         }
     }
 
     pub async fn create_transfer(
         &self,
-        public_key: &str,
-        amount: f64,
-        currency: &str,
-        bank_account: &str,
+        request: &BankTransferRequest,
+        client_ip: Option<&str>,
     ) -> Result<(String, String, Option<BankTransferDetails>), AppError> {
+        self.open_transfer(request, client_ip, false).await
+    }
+
+    /// Like [`BankService::create_transfer`], but the transfer starts as
+    /// `Converting`, or goes there once its review is approved, out of the
+    /// payout queue until the remittance converting its escrow moves it to `Pending`.
+    pub async fn create_remittance_transfer(
+        &self,
+        request: &BankTransferRequest,
+        client_ip: Option<&str>,
+    ) -> Result<(String, String, Option<BankTransferDetails>), AppError> {
+        self.open_transfer(request, client_ip, true).await
+    }
+
+    async fn open_transfer(
+        &self,
+        request: &BankTransferRequest,
+        client_ip: Option<&str>,
+        hold_for_conversion: bool,
+    ) -> Result<(String, String, Option<BankTransferDetails>), AppError> {
+        let public_key = request.public_key.as_str();
+        let amount = request.amount_fiat;
+        let currency = request.currency.as_str();
+        let quote_id = request.quote_id.as_deref();
+        let source_asset = request.source_asset.as_deref();
+
         let wallet = self.wallet_repo.find_by_pubkey(public_key).await
             .map_err(|e| AppError::InternalError(e.to_string()))?
             .ok_or_else(|| AppError::WalletNotFound(public_key.to_string()))?;
 
+        // A saved beneficiary stands in for the account and holder name.
+        let (account, beneficiary_name) = match &request.beneficiary_id {
+            Some(beneficiary_id) => {
+                if !request.bank_account.trim().is_empty() {
+                    return Err(AppError::BadRequest(
+                        "Send either bank_account or beneficiary_id, not both".to_string(),
+                    ));
+                }
+                let (account, holder_name) = self.beneficiary_service
+                    .resolve(&wallet, beneficiary_id, currency)
+                    .await?;
+                (account, Some(holder_name))
+            }
+            None => {
+                let account = bank_account::validate(
+                    currency,
+                    request.bank_country.as_deref(),
+                    &request.bank_account,
+                    request.routing_number.as_deref(),
+                )
+                .map_err(AppError::Validation)?;
+                self.beneficiary_service.check_account(&wallet, &account).await?;
+                (account, request.beneficiary_name.clone())
+            }
+        };
+
+        let provider = self.payouts.select(currency, account.scheme).ok_or_else(|| {
+            AppError::BadRequest(format!(
+                "No payout provider for {} transfers to {} accounts in {}",
+                currency.to_uppercase(),
+                account.scheme,
+                account.country
+            ))
+        })?;
+
         let reputation = self.reputation_service
             .calculate_reputation(public_key, Some(&wallet.id))
             .await
@@ -49,25 +198,19 @@ impl BankService {
         let threshold = self.reputation_service.get_threshold();
         
         if reputation.trust_score < threshold {
-            let transfer = BankTransfer {
-                id: uuid::Uuid::new_v4().to_string(),
-                wallet_id: wallet.id.clone(),
-                public_key: public_key.to_string(),
-                amount_fiat: amount,
-                currency: currency.to_string(),
-                bank_account_masked: Self::mask_account(bank_account),
-                status: "rejected".to_string(),
-                rejection_reason: Some(format!(
-                    "Reputation score too low: {} (required: {})",
-                    reputation.trust_score, threshold
-                )),
-                reputation_score: Some(reputation.trust_score as i64),
-                created_at: Utc::now(),
-                completed_at: None,
-            };
-
-            self.bank_transfer_repo.create(&transfer).await
-                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+            let rejection_reason = format!(
+                "Reputation score too low: {} (required: {})",
+                reputation.trust_score, threshold
+            );
+            self.record_rejection(
+                uuid::Uuid::new_v4().to_string(),
+                &wallet,
+                request,
+                &account,
+                reputation.trust_score,
+                &rejection_reason,
+            )
+            .await?;
 
             tracing::warn!(
                 "Bank transfer rejected for {}: reputation {} < threshold {}",
@@ -83,6 +226,145 @@ impl BankService {
         }
 
         let transfer_id = uuid::Uuid::new_v4().to_string();
+
+        let screening = self.screening_service
+            .screen(&ScreeningSubject {
+                operation: RiskOperation::BankTransfer,
+                wallet_id: wallet.id.clone(),
+                reference: Some(transfer_id.clone()),
+                names: beneficiary_name.iter().cloned().collect(),
+                stellar_addresses: vec![public_key.to_string()],
+                bank_accounts: vec![account.account.clone()],
+            })
+            .await?;
+
+        if screening.action == RiskAction::Block {
+            self.record_rejection(
+                transfer_id,
+                &wallet,
+                request,
+                &account,
+                reputation.trust_score,
+                "Blocked by compliance screening",
+            )
+            .await?;
+
+            return Err(AppError::ScreeningBlocked(screening.result_id.unwrap_or_default()));
+        }
+
+        // Every transfer is funded at a quoted rate; without a quote_id one is
+        // issued here for the exact fiat amount.
+        let requested_source = source_asset.map(|asset| self.resolve_source_asset(asset)).transpose()?;
+        let quote = match quote_id {
+            Some(quote_id) => self.usable_quote(quote_id, public_key, amount, currency).await?,
+            None => {
+                let source = match &requested_source {
+                    Some(source) => source.clone(),
+                    None => self.resolve_source_asset(DEFAULT_SOURCE_ASSET)?,
+                };
+                let quote = self.price_transfer(&source, amount, currency, public_key).await?;
+                self.usable_quote(&quote.id, public_key, amount, currency).await?
+            }
+        };
+
+        let source = match QuoteAsset::parse(&quote.sell_asset) {
+            Some(source @ QuoteAsset::Stellar { .. }) => source,
+            _ => {
+                return Err(AppError::BadRequest(format!(
+                    "Bank transfers must be funded from a Stellar asset, quote sells {}",
+                    quote.sell_asset
+                )))
+            }
+        };
+        if requested_source.as_ref().is_some_and(|requested| requested != &source) {
+            return Err(AppError::BadRequest(format!(
+                "Quote sells {}, transfer is funded from {}",
+                quote.sell_asset,
+                source_asset.unwrap_or_default()
+            )));
+        }
+
+        // KYC caps the amount on top of the reputation gate.
+        if let Err(e) = self.kyc_service.check_transfer_limit(&wallet.id, quote.usd_amount).await {
+            if let AppError::KycLimitExceeded { .. } | AppError::KycRejected(_) = e {
+                self.record_rejection(
+                    transfer_id,
+                    &wallet,
+                    request,
+                    &account,
+                    reputation.trust_score,
+                    &e.to_string(),
+                )
+                .await?;
+            }
+            return Err(e);
+        }
+
+        // Checked again, authoritatively, when the escrow is inserted.
+        let balance = check_available(&self.stellar_service, &self.transaction_repo, &wallet, &source, quote.sell_amount).await?;
+
+        let risk = self.risk_service
+            .assess(&RiskSubject {
+                operation: RiskOperation::BankTransfer,
+                wallet_id: wallet.id.clone(),
+                public_key: public_key.to_string(),
+                reference: Some(transfer_id.clone()),
+                amount_usd: Some(quote.usd_amount),
+                bank_account_hash: Some(RiskService::bank_account_hash(&account)),
+                ip_address: client_ip.map(str::to_string),
+            })
+            .await?;
+
+        if risk.action == RiskAction::Block {
+            let rejection_reason = format!("Blocked by risk rules: {}", risk.rule_ids());
+            self.record_rejection(
+                transfer_id,
+                &wallet,
+                request,
+                &account,
+                reputation.trust_score,
+                &rejection_reason,
+            )
+            .await?;
+
+            return Err(AppError::RiskBlocked(risk.decision_id));
+        }
+
+        // Borderline reputation, a risk rule asking for review or a possible
+        // watchlist match holds the transfer, with its funds escrowed, until a
+        // reviewer decides.
+        let mut review_reasons = Vec::new();
+        if reputation.trust_score < threshold.saturating_add(self.review_reputation_margin) {
+            review_reasons.push(format!(
+                "Reputation {} is within {} of the threshold {}",
+                reputation.trust_score, self.review_reputation_margin, threshold
+            ));
+        }
+        if risk.action == RiskAction::Review {
+            review_reasons.push(format!("Flagged by risk rules: {}", risk.describe()));
+        }
+        if screening.action == RiskAction::Review {
+            review_reasons.push(format!("Possible watchlist match: {}", screening.describe()));
+        }
+        let review_reason = (!review_reasons.is_empty()).then(|| review_reasons.join("; "));
+
+        let status = match review_reason {
+            Some(_) => TransferStatus::Review,
+            None if hold_for_conversion => TransferStatus::Converting,
+            None => TransferStatus::Pending,
+        };
+        let created_reason = match &review_reason {
+            Some(reason) => format!("Held for review: {}", reason),
+            None if hold_for_conversion => "Created; waiting for the remittance's DEX conversion".to_string(),
+            None => "Created".to_string(),
+        };
+
+        let destination = PayoutDestination::new(&account, beneficiary_name.as_deref());
+        let payout_destination = serde_json::to_string(&destination)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| self.cipher.encrypt(&json))
+            .map_err(|e| AppError::InternalError(e.to_string()))?;
+
         let now = Utc::now();
         
         let transfer = BankTransfer {
@@ -91,19 +373,58 @@ impl BankService {
             public_key: public_key.to_string(),
             amount_fiat: amount,
             currency: currency.to_string(),
-            bank_account_masked: Self::mask_account(bank_account),
-            status: "completed".to_string(),
+            bank_account_masked: Self::mask_account(&account.account),
+            status: status.to_string(),
             rejection_reason: None,
             reputation_score: Some(reputation.trust_score as i64),
+            quote_id: Some(quote.id.clone()),
+            provider_reference: None,
+            payout_provider: Some(provider.name().to_string()),
+            payout_destination: Some(payout_destination),
+            beneficiary_id: request.beneficiary_id.clone(),
             created_at: now,
-            completed_at: Some(now),
+            completed_at: None,
         };
 
-        self.bank_transfer_repo.create(&transfer).await
+        // The quote is spent, the transfer stored and its funds escrowed
+        // together, or none of them.
+        let escrow = self.escrow_transaction(&transfer, &source, quote.sell_amount);
+        let review = review_reason
+            .as_deref()
+            .map(|reason| self.review_service.prepare(&transfer_id, reason, Some(quote.usd_amount)));
+        let outcome = self.bank_transfer_repo
+            .create_with_escrow(&transfer, &created_reason, &quote.id, &escrow, review.as_ref(), balance, now)
+            .await
             .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        match outcome {
+            CreateOutcome::Created => {}
+            CreateOutcome::QuoteUnavailable => {
+                return Err(AppError::QuoteUnavailable(format!("Quote {} is no longer available", quote.id)));
+            }
+            CreateOutcome::InsufficientFunds { available } => {
+                return Err(AppError::InsufficientBalance {
+                    required: format!("{} {}", quote.sell_amount, source),
+                    available: format!("{} {}", available, source),
+                });
+            }
+        }
+        tracing::info!(
+            "Quote {} redeemed by bank transfer {}, {} {} escrowed",
+            quote.id,
+            transfer_id,
+            escrow.amount,
+            source
+        );
+
+        if let Some(review) = &review {
+            tracing::info!("Bank transfer {} held for review {}: {}", transfer_id, review.id, review.reason);
+        }
+
+        self.publish_transfer(&transfer, &created_reason).await;
 
         tracing::info!(
-            "Bank transfer completed for {}: {} {} (reputation: {})",
+            "Bank transfer {} for {}: {} {} (reputation: {})",
+            status,
             public_key,
             amount,
             currency,
@@ -113,27 +434,723 @@ impl BankService {
         let details = BankTransferDetails {
             amount,
             currency: currency.to_string(),
-            bank_account_masked: Self::mask_account(bank_account),
+            bank_account_masked: Self::mask_account(&account.account),
+            bank_name: account.bank_name,
             reputation_score: reputation.trust_score,
+            quote_id: Some(quote.id),
+            source_asset: quote.sell_asset,
+            source_amount: quote.sell_amount,
+            escrow_tx_hash: escrow.tx_hash,
             created_at: now,
         };
 
         Ok((
             transfer_id,
-            "completed".to_string(),
+            status.to_string(),
             Some(details),
         ))
     }
 
-    pub async fn list_all_transfers(&self) -> Result<Vec<BankTransfer>> {
-        self.bank_transfer_repo.find_all().await
+    /// Stores a transfer refused before it entered the payout pipeline.
+    async fn record_rejection(
+        &self,
+        transfer_id: String,
+        wallet: &Wallet,
+        request: &BankTransferRequest,
+        account: &BankAccount,
+        reputation_score: u8,
+        reason: &str,
+    ) -> Result<(), AppError> {
+        let transfer = BankTransfer {
+            id: transfer_id,
+            wallet_id: wallet.id.clone(),
+            public_key: wallet.public_key.clone(),
+            amount_fiat: request.amount_fiat,
+            currency: request.currency.clone(),
+            bank_account_masked: Self::mask_account(&account.account),
+            status: TransferStatus::Rejected.to_string(),
+            rejection_reason: Some(reason.to_string()),
+            reputation_score: Some(reputation_score as i64),
+            quote_id: None,
+            provider_reference: None,
+            payout_provider: None,
+            payout_destination: None,
+            beneficiary_id: request.beneficiary_id.clone(),
+            created_at: Utc::now(),
+            completed_at: None,
+        };
+
+        self.bank_transfer_repo.create(&transfer, reason).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        self.publish_transfer(&transfer, reason).await;
+        Ok(())
+    }
+
+    pub async fn get_transfer(
+        &self,
+        id: &str,
+    ) -> Result<(BankTransfer, Vec<BankTransferTransition>, Vec<Transaction>), AppError> {
+        let transfer = self.bank_transfer_repo.find_by_id(id).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .ok_or_else(|| AppError::TransferNotFound(id.to_string()))?;
+
+        let transitions = self.bank_transfer_repo.find_transitions(id).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let transactions = self.transaction_repo.find_by_bank_transfer(id).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        Ok((transfer, transitions, transactions))
+    }
+
+    /// [`BankService::get_transfer`] for the wallet that made the transfer.
+    /// Other wallets get the same not-found as for an unknown id.
+    pub async fn get_own_transfer(
+        &self,
+        id: &str,
+        public_key: &str,
+    ) -> Result<(BankTransfer, Vec<BankTransferTransition>, Vec<Transaction>), AppError> {
+        let found = self.get_transfer(id).await?;
+        if found.0.public_key != public_key {
+            return Err(AppError::TransferNotFound(id.to_string()));
+        }
+        Ok(found)
+    }
+
+    /// Approves a held transfer's review; once fully approved the transfer
+    /// joins the payout queue, or, paying out a remittance, waits for its
+    /// DEX conversion.
+    pub async fn approve_review(
+        &self,
+        review_id: &str,
+        reviewer: &str,
+        note: Option<&str>,
+    ) -> Result<TransferReview, AppError> {
+        let (review, _) = self.review_service.get(review_id).await?;
+        let remittance = self.remittance_repo.exists_for_transfer(&review.transfer_id).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        let approved_to = if remittance { TransferStatus::Converting } else { TransferStatus::Pending };
+
+        let review = self.review_service.approve(review_id, reviewer, note, approved_to).await?;
+
+        if review.review_status() == Some(ReviewStatus::Approved) {
+            let (transfer, _, _) = self.get_transfer(&review.transfer_id).await?;
+            self.transitioned(transfer, &format!("Approved in review by {}", reviewer)).await;
+        }
+
+        Ok(review)
+    }
+
+    /// Rejects a held transfer's review, which rejects the transfer and
+    /// releases its escrow.
+    pub async fn reject_review(
+        &self,
+        review_id: &str,
+        reviewer: &str,
+        note: Option<&str>,
+    ) -> Result<TransferReview, AppError> {
+        let review = self.review_service.reject(review_id, reviewer, note).await?;
+
+        let (transfer, _, _) = self.get_transfer(&review.transfer_id).await?;
+        let reason = ReviewService::rejection_reason(reviewer, note.unwrap_or_default());
+        self.transitioned(transfer, &reason).await;
+
+        Ok(review)
+    }
+
+    /// Cancels a transfer for the signing wallet that created it, while it is
+    /// still held for review or waiting for the payout worker. The escrow is refunded.
+    pub async fn cancel_transfer(
+        &self,
+        id: &str,
+        public_key: &str,
+        reason: Option<&str>,
+    ) -> Result<BankTransfer, AppError> {
+        let (transfer, _, _) = self.get_own_transfer(id, public_key).await?;
+
+        let reason = reason.map(str::trim).filter(|reason| !reason.is_empty());
+        let description = match reason {
+            Some(reason) => format!("Cancelled by the wallet owner: {}", reason),
+            None => "Cancelled by the wallet owner".to_string(),
+        };
+
+        self.cancel(&transfer, public_key, reason, &description).await
+    }
+
+    /// Cancels the transfer of a remittance that cannot go on, refunding its escrow.
+    pub async fn cancel_remittance_transfer(&self, id: &str, reason: &str) -> Result<BankTransfer, AppError> {
+        let (transfer, _, _) = self.get_transfer(id).await?;
+
+        self.cancel(&transfer, "remittance", Some(reason), &format!("Remittance aborted: {}", reason))
+            .await
+    }
+
+    async fn cancel(
+        &self,
+        transfer: &BankTransfer,
+        actor: &str,
+        reason: Option<&str>,
+        description: &str,
+    ) -> Result<BankTransfer, AppError> {
+        let from = transfer.transfer_status();
+        if !is_cancellable(from) {
+            return Err(AppError::InvalidTransition(format!(
+                "Transfer {} is {} and can no longer be cancelled",
+                transfer.id, transfer.status
+            )));
+        }
+
+        let cancelled = self.transition(transfer, TransferStatus::Cancelled, description).await?;
+
+        if from == Some(TransferStatus::Review) {
+            if let Err(e) = self.review_service.cancel_for_transfer(&transfer.id, actor, reason).await {
+                tracing::error!("Review of bank transfer {} left open after cancellation: {}", transfer.id, e);
+            }
+        }
+
+        Ok(cancelled)
+    }
+
+    /// Reverses a completed transfer, e.g. after a dispute; the reversal
+    /// counts against the wallet's reputation. The fiat was paid out, so the
+    /// escrow is only refunded when the operator confirms it came back.
+    pub async fn reverse_transfer(
+        &self,
+        id: &str,
+        operator: &str,
+        reason: &str,
+        refund_escrow: bool,
+    ) -> Result<BankTransfer, AppError> {
+        let reason = reason.trim();
+        if reason.is_empty() {
+            return Err(AppError::BadRequest("A reason is required to reverse a transfer".to_string()));
+        }
+
+        let (transfer, _, _) = self.get_transfer(id).await?;
+        if transfer.transfer_status() != Some(TransferStatus::Completed) {
+            return Err(AppError::InvalidTransition(format!(
+                "Transfer {} is {}; only completed transfers can be reversed",
+                id, transfer.status
+            )));
+        }
+
+        let description = if refund_escrow {
+            format!("Reversed by {}, escrow refunded: {}", operator, reason)
+        } else {
+            format!("Reversed by {}: {}", operator, reason)
+        };
+        let reversed = self.transition(&transfer, TransferStatus::Reversed, &description).await?;
+        if refund_escrow {
+            self.refund_reversal(&reversed).await;
+        }
+
+        Ok(reversed)
+    }
+
+    /// Refunds the escrow of a reversed transfer whose fiat came back.
+    async fn refund_reversal(&self, transfer: &BankTransfer) {
+        if let Err(e) = self.release_escrow(transfer).await {
+            tracing::error!("Escrow of reversed bank transfer {} not refunded: {}", transfer.id, e);
+        }
+    }
+
+    /// Moves a transfer to `to`, refusing transitions the state machine does
+    /// not allow and transfers another worker or request changed first.
+    pub async fn transition(
+        &self,
+        transfer: &BankTransfer,
+        to: TransferStatus,
+        reason: &str,
+    ) -> Result<BankTransfer, AppError> {
+        let from = transfer.transfer_status().ok_or_else(|| {
+            AppError::InternalError(format!("Transfer {} has unknown status {}", transfer.id, transfer.status))
+        })?;
+
+        if !from.can_transition_to(to) {
+            return Err(AppError::InvalidTransition(format!(
+                "Transfer {} cannot go from {} to {}",
+                transfer.id, from, to
+            )));
+        }
+
+        let now = Utc::now();
+        let moved = self.bank_transfer_repo.transition(&transfer.id, from, to, reason, now).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        if !moved {
+            return Err(AppError::InvalidTransition(format!(
+                "Transfer {} is no longer {}",
+                transfer.id, from
+            )));
+        }
+
+        tracing::info!("Bank transfer {} {} -> {}: {}", transfer.id, from, to, reason);
+
+        let mut updated = transfer.clone();
+        updated.status = to.to_string();
+        if to == TransferStatus::Completed {
+            updated.completed_at = Some(now);
+        }
+
+        Ok(self.transitioned(updated, reason).await)
+    }
+
+    /// Settles or releases the escrow of a transfer that just reached its
+    /// current status and tells webhook subscribers.
+    async fn transitioned(&self, updated: BankTransfer, reason: &str) -> BankTransfer {
+        let Some(to) = updated.transfer_status() else { return updated };
+
+        // The transition is already stored; a failure here needs an operator,
+        // not a retry of the transition.
+        let escrow_result = match escrow_change(to) {
+            EscrowChange::Settle => self.settle_escrow(&updated).await,
+            EscrowChange::Release => self.release_escrow(&updated).await,
+            EscrowChange::Keep => Ok(()),
+        };
+        if let Err(e) = escrow_result {
+            tracing::error!("Escrow for bank transfer {} not updated after {}: {}", updated.id, to, e);
+        }
+
+        self.publish_transfer(&updated, reason).await;
+
+        updated
+    }
+
+    /// Tells webhook subscribers the transfer reached its current status.
+    async fn publish_transfer(&self, transfer: &BankTransfer, reason: &str) {
+        let Some(status) = transfer.transfer_status() else { return };
+        self.webhook_service
+            .publish(&bank_transfer_event(status), json!({ "transfer": transfer, "reason": reason }))
+            .await;
+    }
+
+    pub fn spawn_worker(self: Arc<Self>, every: std::time::Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(every);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = self.process_transfers().await {
+                    tracing::warn!("Bank transfer worker failed: {}", e);
+                }
+            }
+        })
+    }
+
+    /// Submits pending transfers to the payout provider and advances
+    /// submitted ones from what the provider reports.
+    pub async fn process_transfers(&self) -> Result<()> {
+        for transfer in self.bank_transfer_repo.find_by_status(TransferStatus::Pending, WORKER_BATCH_SIZE).await? {
+            if let Err(e) = self.submit_payout(&transfer).await {
+                tracing::warn!("Failed to submit bank transfer {}: {}", transfer.id, e);
+            }
+        }
+
+        for status in [TransferStatus::Processing, TransferStatus::Sent] {
+            for transfer in self.bank_transfer_repo.find_by_status(status, WORKER_BATCH_SIZE).await? {
+                if let Err(e) = self.poll_payout(&transfer).await {
+                    tracing::warn!("Failed to update bank transfer {}: {}", transfer.id, e);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn submit_payout(&self, transfer: &BankTransfer) -> Result<(), AppError> {
+        let (provider, payout) = match self.prepare_payout(transfer) {
+            Ok(prepared) => prepared,
+            Err(reason) => {
+                self.transition(transfer, TransferStatus::Failed, &reason).await?;
+                return Ok(());
+            }
+        };
+        let provider_name = provider.name();
+
+        // Claiming the transfer first keeps two workers from submitting it
+        // twice, and records the intent: if the reference is never stored
+        // (a crash, a timeout), `poll_payout` submits again under the same
+        // idempotency key and gets the same payout back.
+        let transfer = self
+            .transition(transfer, TransferStatus::Processing, &format!("Submitting to {}", provider_name))
+            .await?;
+
+        self.send_payout(&transfer, provider.as_ref(), &payout).await
+    }
+
+    async fn send_payout(
+        &self,
+        transfer: &BankTransfer,
+        provider: &dyn PayoutProvider,
+        payout: &PayoutRequest,
+    ) -> Result<(), AppError> {
+        match provider.submit(payout).await {
+            Ok(reference) => {
+                self.bank_transfer_repo.set_provider_reference(&transfer.id, &reference).await
+                    .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+                Ok(())
+            }
+            Err(e) if e.downcast_ref::<PayoutRejected>().is_some() => {
+                self.transition(transfer, TransferStatus::Failed, &format!("{} rejected the payout: {}", provider.name(), e))
+                    .await?;
+                Ok(())
+            }
+            Err(e) => {
+                // It may have been accepted; only the provider can tell.
+                tracing::warn!("Payout of bank transfer {} not confirmed by {}, will resubmit: {}", transfer.id, provider.name(), e);
+                Ok(())
+            }
+        }
+    }
+
+    /// Provider the transfer was routed to and the decrypted payout. Errors
+    /// are the reason the transfer cannot be paid out.
+    fn prepare_payout(&self, transfer: &BankTransfer) -> Result<(Arc<dyn PayoutProvider>, PayoutRequest), String> {
+        let name = transfer.payout_provider.as_deref()
+            .ok_or("No payout provider was chosen for the transfer")?;
+        let provider = self.payouts.get(name).cloned()
+            .ok_or_else(|| format!("Payout provider {} is not configured", name))?;
+
+        let encrypted = transfer.payout_destination.as_deref()
+            .ok_or("No payout destination stored for the transfer")?;
+        let destination: PayoutDestination = self.cipher
+            .decrypt(encrypted)
+            .and_then(|json| serde_json::from_str(&json).map_err(anyhow::Error::from))
+            .map_err(|e| format!("Payout destination unreadable: {}", e))?;
+
+        let payout = PayoutRequest {
+            transfer_id: transfer.id.clone(),
+            amount: transfer.amount_fiat,
+            currency: transfer.currency.to_uppercase(),
+            destination,
+        };
+
+        Ok((provider, payout))
+    }
+
+    async fn poll_payout(&self, transfer: &BankTransfer) -> Result<(), AppError> {
+        let Some(reference) = transfer.provider_reference.as_deref() else {
+            // Claimed but the submission was never confirmed: resubmit, which
+            // the idempotency key turns into a lookup if it did go through.
+            if transfer.transfer_status() != Some(TransferStatus::Processing) {
+                tracing::warn!("Bank transfer {} is {} without a provider reference", transfer.id, transfer.status);
+                return Ok(());
+            }
+            return match self.prepare_payout(transfer) {
+                Ok((provider, payout)) => self.send_payout(transfer, provider.as_ref(), &payout).await,
+                Err(reason) => {
+                    self.transition(transfer, TransferStatus::Failed, &reason).await?;
+                    Ok(())
+                }
+            };
+        };
+
+        let Some(payout_provider) = transfer.payout_provider.as_deref().and_then(|name| self.payouts.get(name)) else {
+            tracing::warn!("Bank transfer {} has no configured payout provider to poll", transfer.id);
+            return Ok(());
+        };
+
+        let state = payout_provider.status(reference).await
+            .map_err(|e| AppError::ExternalApiError(e.to_string()))?;
+        let provider = payout_provider.name();
+        let current = transfer.transfer_status();
+
+        match state {
+            PayoutState::Processing => {}
+            PayoutState::Sent => {
+                if current == Some(TransferStatus::Processing) {
+                    self.transition(transfer, TransferStatus::Sent, &format!("{} sent the payout", provider)).await?;
+                }
+            }
+            PayoutState::Completed => {
+                let transfer = if current == Some(TransferStatus::Processing) {
+                    self.transition(transfer, TransferStatus::Sent, &format!("{} sent the payout", provider)).await?
+                } else {
+                    transfer.clone()
+                };
+                self.transition(&transfer, TransferStatus::Completed, &format!("{} confirmed the payout", provider))
+                    .await?;
+            }
+            PayoutState::Failed(reason) => {
+                self.transition(transfer, TransferStatus::Failed, &format!("{}: {}", provider, reason)).await?;
+            }
+            PayoutState::Reversed(reason) => {
+                let to = if current == Some(TransferStatus::Processing) {
+                    TransferStatus::Failed
+                } else {
+                    TransferStatus::Reversed
+                };
+                let updated = self
+                    .transition(transfer, to, &format!("{} reversed the payout: {}", provider, reason))
+                    .await?;
+                // The provider returned the fiat, so the escrow goes back too.
+                if to == TransferStatus::Reversed {
+                    self.refund_reversal(&updated).await;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// A page of transfers matching the query. Without `public_key` it lists
+    /// every wallet's transfers, so only admin routes should allow that.
+    pub async fn list_transfers(&self, query: &TransferListQuery) -> Result<TransferListResponse, AppError> {
+        let mut errors = Vec::new();
+
+        let status = match query.status.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+            Some(status) => match TransferStatus::parse(status) {
+                Some(status) => Some(status.as_str().to_string()),
+                None => {
+                    errors.push(FieldError::new("status", "invalid", format!("Unknown transfer status: {}", status)));
+                    None
+                }
+            },
+            None => None,
+        };
+
+        let currency = query.currency.as_deref().map(str::trim).filter(|c| !c.is_empty()).map(str::to_uppercase);
+
+        if let (Some(from), Some(to)) = (query.from, query.to) {
+            if from >= to {
+                errors.push(FieldError::new("to", "invalid_range", "Must be later than from"));
+            }
+        }
+
+        let ascending = match query.sort.as_deref().map(str::trim) {
+            None | Some("") | Some("-created_at") => false,
+            Some("created_at") => true,
+            Some(other) => {
+                errors.push(FieldError::new(
+                    "sort",
+                    "invalid",
+                    format!("Cannot sort by {}; use created_at or -created_at", other),
+                ));
+                false
+            }
+        };
+
+        let limit = query.limit.unwrap_or(DEFAULT_TRANSFER_PAGE_SIZE);
+        if !(1..=MAX_TRANSFER_PAGE_SIZE).contains(&limit) {
+            errors.push(FieldError::new(
+                "limit",
+                "out_of_range",
+                format!("Must be between 1 and {}", MAX_TRANSFER_PAGE_SIZE),
+            ));
+        }
+
+        let cursor = match query.cursor.as_deref().filter(|c| !c.trim().is_empty()) {
+            Some(cursor) => match TransferCursor::decode(cursor) {
+                Ok(cursor) if cursor.ascending != ascending => {
+                    errors.push(FieldError::new("cursor", "sort_mismatch", "Cursor was issued for a different sort"));
+                    None
+                }
+                Ok(cursor) => Some(cursor),
+                Err(error) => {
+                    errors.push(error);
+                    None
+                }
+            },
+            None => None,
+        };
+
+        if !errors.is_empty() {
+            return Err(AppError::Validation(errors));
+        }
+
+        let wallet_id = match query.public_key.as_deref().map(str::trim).filter(|k| !k.is_empty()) {
+            Some(public_key) => {
+                let wallet = self.wallet_repo.find_by_pubkey(public_key).await
+                    .map_err(|e| AppError::InternalError(e.to_string()))?
+                    .ok_or_else(|| AppError::WalletNotFound(public_key.to_string()))?;
+                Some(wallet.id)
+            }
+            None => None,
+        };
+
+        let filter = TransferFilter {
+            wallet_id,
+            status,
+            currency,
+            from: query.from,
+            to: query.to,
+        };
+
+        // One extra row tells whether another page follows.
+        let mut transfers = self.bank_transfer_repo
+            .find_page(&filter, cursor.as_ref(), ascending, limit + 1)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        let next_cursor = if transfers.len() as i64 > limit {
+            transfers.truncate(limit as usize);
+            transfers.last().map(|last| TransferCursor::after(last, ascending).encode())
+        } else {
+            None
+        };
+
+        let total = self.bank_transfer_repo.count(&filter).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        Ok(TransferListResponse { transfers, total, next_cursor })
+    }
+
+    fn resolve_source_asset(&self, asset: &str) -> Result<QuoteAsset, AppError> {
+        if let Some(parsed @ QuoteAsset::Stellar { .. }) = QuoteAsset::parse(asset) {
+            return Ok(parsed);
+        }
+
+        let token = self.tokens.resolve_stellar(asset)?;
+        QuoteAsset::from_token(token).ok_or_else(|| AppError::UnsupportedAsset(asset.to_string()))
+    }
+
+    /// Issues a quote selling `source` for exactly `amount` of `currency`.
+    async fn price_transfer(
+        &self,
+        source: &QuoteAsset,
+        amount: FiatAmount,
+        currency: &str,
+        public_key: &str,
+    ) -> Result<Quote, AppError> {
+        let request = QuoteRequest {
+            sell_asset: source.to_string(),
+            buy_asset: QuoteAsset::Fiat(currency.to_uppercase()).to_string(),
+            sell_amount: None,
+            buy_amount: Some(amount.rescale(Rounding::Down)?),
+            context: "sep31".to_string(),
+            expire_after: None,
+        };
+
+        self.quote_service.create_quote(&request, public_key).await
+    }
+
+    /// The escrow locking the source funds for the transfer, or sending them
+    /// to the settlement account when one is configured. As with wallet sends,
+    /// submission to the network is simulated, so the funds stay in the
+    /// wallet's on-chain balance and are subtracted from it as committed.
+    fn escrow_transaction(&self, transfer: &BankTransfer, asset: &QuoteAsset, amount: Amount) -> Transaction {
+        Transaction {
+            id: uuid::Uuid::new_v4().to_string(),
+            wallet_id: transfer.wallet_id.clone(),
+            tx_hash: format!("tx_{}", uuid::Uuid::new_v4()),
+            tx_type: TransactionType::Escrow.to_string(),
+            from_address: Some(transfer.public_key.clone()),
+            to_address: self.settlement_account.clone(),
+            amount,
+            asset: asset.symbol().to_uppercase(),
+            asset_issuer: asset.issuer().map(str::to_string),
+            dest_amount: None,
+            dest_asset: None,
+            dest_asset_issuer: None,
+            fee_amount: None,
+            fee_asset: None,
+            simulated: true,
+            bank_transfer_id: Some(transfer.id.clone()),
+            status: TransactionStatus::Pending.to_string(),
+            created_at: Utc::now(),
+        }
+    }
+
+    async fn find_escrow(&self, transfer_id: &str) -> Result<(Option<Transaction>, bool)> {
+        let transactions = self.transaction_repo.find_by_bank_transfer(transfer_id).await?;
+        let released = transactions.iter().any(|tx| tx.tx_type == TransactionType::Release.to_string());
+        let escrow = transactions.into_iter().find(|tx| tx.tx_type == TransactionType::Escrow.to_string());
+        Ok((escrow, released))
     }
 
-    pub async fn list_transfers_by_pubkey(&self, public_key: &str) -> Result<Vec<BankTransfer>> {
-        self.bank_transfer_repo.find_by_public_key(public_key).await
+    async fn settle_escrow(&self, transfer: &BankTransfer) -> Result<()> {
+        let (escrow, _) = self.find_escrow(&transfer.id).await?;
+        if let Some(escrow) = escrow.filter(|tx| tx.status == TransactionStatus::Pending.to_string()) {
+            self.transaction_repo.update_status(&escrow.id, &TransactionStatus::Completed.to_string()).await?;
+        }
+        Ok(())
     }
 
-    fn mask_account(account: &str) -> String {
+    /// Refunds escrowed funds to the wallet with a `release` transaction
+    /// linked to the transfer. Transfers created before escrow existed have
+    /// nothing to release.
+    async fn release_escrow(&self, transfer: &BankTransfer) -> Result<()> {
+        let (escrow, released) = self.find_escrow(&transfer.id).await?;
+        let Some(escrow) = escrow else { return Ok(()) };
+        if released {
+            return Ok(());
+        }
+
+        if escrow.status == TransactionStatus::Pending.to_string() {
+            self.transaction_repo.update_status(&escrow.id, &TransactionStatus::Failed.to_string()).await?;
+        }
+
+        let release = Transaction {
+            id: uuid::Uuid::new_v4().to_string(),
+            wallet_id: transfer.wallet_id.clone(),
+            tx_hash: format!("tx_{}", uuid::Uuid::new_v4()),
+            tx_type: TransactionType::Release.to_string(),
+            from_address: escrow.to_address.clone(),
+            to_address: Some(transfer.public_key.clone()),
+            amount: escrow.amount,
+            asset: escrow.asset.clone(),
+            asset_issuer: escrow.asset_issuer.clone(),
+            dest_amount: None,
+            dest_asset: None,
+            dest_asset_issuer: None,
+            fee_amount: None,
+            fee_asset: None,
+            simulated: true,
+            bank_transfer_id: Some(transfer.id.clone()),
+            status: TransactionStatus::Completed.to_string(),
+            created_at: Utc::now(),
+        };
+
+        self.transaction_repo.create(&release).await
+            .context("Failed to record escrow release")?;
+
+        tracing::info!(
+            "Released {} {} escrowed for bank transfer {}",
+            release.amount,
+            release.asset,
+            transfer.id
+        );
+
+        Ok(())
+    }
+
+    /// The wallet's quote for exactly `amount` of `currency`, still unused.
+    /// It is redeemed together with the transfer insert.
+    async fn usable_quote(
+        &self,
+        quote_id: &str,
+        public_key: &str,
+        amount: FiatAmount,
+        currency: &str,
+    ) -> Result<Quote, AppError> {
+        let expected_asset = QuoteAsset::Fiat(currency.to_uppercase());
+        let expected_amount: Amount = amount.rescale(Rounding::Down)?;
+
+        self.quote_service
+            .usable(quote_id, public_key, |quote| {
+                if QuoteAsset::parse(&quote.buy_asset).as_ref() != Some(&expected_asset) {
+                    return Err(AppError::BadRequest(format!(
+                        "Quote buys {}, transfer is in {}",
+                        quote.buy_asset, currency
+                    )));
+                }
+                if quote.buy_amount != expected_amount {
+                    return Err(AppError::BadRequest(format!(
+                        "Quote is for {} {}, transfer is for {}",
+                        quote.buy_amount, currency, amount
+                    )));
+                }
+                if self.reject_fallback_rates && (quote.rate_fallback || quote.rate_stale) {
+                    return Err(AppError::RateUnavailable(format!(
+                        "Quote {} was priced from fallback rates ({}); request a new quote",
+                        quote.id, quote.rate_source
+                    )));
+                }
+                Ok(())
+            })
+            .await
+    }
+
+    pub fn mask_account(account: &str) -> String {
         if account.len() <= 4 {
             return "*".repeat(account.len());
         }
@@ -141,4 +1158,211 @@ impl BankService {
         let last_four = &account[account.len() - 4..];
         format!("****{}", last_four)
     }
+}
+
+/// Nothing was paid out yet while a transfer is held, converting or queued.
+fn is_cancellable(status: Option<TransferStatus>) -> bool {
+    matches!(status, Some(TransferStatus::Review | TransferStatus::Converting | TransferStatus::Pending))
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum EscrowChange {
+    Settle,
+    Release,
+    Keep,
+}
+
+/// What reaching `to` does to a transfer's escrow. A reversal keeps it:
+/// the fiat was paid out, and only comes back when the provider returns it
+/// or an operator confirms it.
+fn escrow_change(to: TransferStatus) -> EscrowChange {
+    match to {
+        TransferStatus::Completed => EscrowChange::Settle,
+        TransferStatus::Failed | TransferStatus::Cancelled | TransferStatus::Rejected => EscrowChange::Release,
+        _ => EscrowChange::Keep,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+    use crate::state::AppState;
+
+    #[test]
+    fn test_only_unpaid_transfers_are_cancellable() {
+        assert!(is_cancellable(Some(TransferStatus::Review)));
+        assert!(is_cancellable(Some(TransferStatus::Converting)));
+        assert!(is_cancellable(Some(TransferStatus::Pending)));
+        assert!(!is_cancellable(Some(TransferStatus::Processing)));
+        assert!(!is_cancellable(Some(TransferStatus::Sent)));
+        assert!(!is_cancellable(Some(TransferStatus::Completed)));
+        assert!(!is_cancellable(Some(TransferStatus::Cancelled)));
+        assert!(!is_cancellable(None));
+    }
+
+    #[test]
+    fn test_reversal_keeps_the_escrow() {
+        assert_eq!(escrow_change(TransferStatus::Completed), EscrowChange::Settle);
+        assert_eq!(escrow_change(TransferStatus::Cancelled), EscrowChange::Release);
+        assert_eq!(escrow_change(TransferStatus::Rejected), EscrowChange::Release);
+        assert_eq!(escrow_change(TransferStatus::Failed), EscrowChange::Release);
+        assert_eq!(escrow_change(TransferStatus::Reversed), EscrowChange::Keep);
+        assert!(TransferStatus::Completed.can_transition_to(TransferStatus::Reversed));
+        assert!(!TransferStatus::Pending.can_transition_to(TransferStatus::Reversed));
+    }
+
+    /// Stores a transfer of wallet `w{public_key}`, creating the wallet on first use.
+    async fn insert_transfer(
+        state: &AppState,
+        id: &str,
+        public_key: &str,
+        status: TransferStatus,
+        currency: &str,
+        created_at: DateTime<Utc>,
+    ) {
+        let wallet_id = format!("w{}", public_key);
+        let wallet_repo = WalletRepository::new(state.db_pool.clone());
+        if wallet_repo.find_by_pubkey(public_key).await.unwrap().is_none() {
+            wallet_repo
+                .create(&Wallet {
+                    id: wallet_id.clone(),
+                    public_key: public_key.to_string(),
+                    is_aa_wallet: false,
+                    created_at,
+                    updated_at: created_at,
+                })
+                .await
+                .unwrap();
+        }
+
+        let transfer = BankTransfer {
+            id: id.to_string(),
+            wallet_id,
+            public_key: public_key.to_string(),
+            amount_fiat: "1000".parse().unwrap(),
+            currency: currency.to_string(),
+            bank_account_masked: "****1234".to_string(),
+            status: status.to_string(),
+            rejection_reason: None,
+            reputation_score: None,
+            quote_id: None,
+            provider_reference: Some(format!("ref_{}", id)),
+            payout_provider: Some("mock".to_string()),
+            payout_destination: None,
+            beneficiary_id: None,
+            created_at,
+            completed_at: (status == TransferStatus::Completed).then_some(created_at),
+        };
+        BankTransferRepository::new(state.db_pool.clone()).create(&transfer, "created").await.unwrap();
+    }
+
+    /// A completed transfer of wallet `wGA` whose escrow of 60 USDC was settled.
+    async fn completed_transfer(state: &AppState, id: &str) {
+        let now = Utc::now();
+        insert_transfer(state, id, "GA", TransferStatus::Completed, "MXN", now).await;
+
+        TransactionRepository::new(state.db_pool.clone())
+            .create(&Transaction {
+                id: format!("escrow_{}", id),
+                wallet_id: "wGA".to_string(),
+                tx_hash: format!("tx_{}", id),
+                tx_type: TransactionType::Escrow.to_string(),
+                from_address: Some("GA".to_string()),
+                to_address: None,
+                amount: "60".parse().unwrap(),
+                asset: "USDC".to_string(),
+                asset_issuer: Some("GISSUER".to_string()),
+                dest_amount: None,
+                dest_asset: None,
+                dest_asset_issuer: None,
+                fee_amount: None,
+                fee_asset: None,
+                simulated: true,
+                bank_transfer_id: Some(id.to_string()),
+                status: TransactionStatus::Completed.to_string(),
+                created_at: now,
+            })
+            .await
+            .unwrap();
+    }
+
+    async fn committed(state: &AppState) -> Amount {
+        TransactionRepository::new(state.db_pool.clone())
+            .sum_committed("wGA", "USDC", Some("GISSUER"))
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_operator_reversal_refunds_only_when_asked() {
+        let state = AppState::for_tests().await;
+        completed_transfer(&state, "t1").await;
+        completed_transfer(&state, "t2").await;
+
+        let reversed = state.bank_service.reverse_transfer("t1", "ops", "chargeback", false).await.unwrap();
+        assert_eq!(reversed.status, TransferStatus::Reversed.as_str());
+        assert_eq!(committed(&state).await.to_string(), "120.0000000");
+
+        state.bank_service.reverse_transfer("t2", "ops", "funds returned", true).await.unwrap();
+        assert_eq!(committed(&state).await.to_string(), "60.0000000");
+
+        let (_, _, transactions) = state.bank_service.get_transfer("t2").await.unwrap();
+        assert!(transactions.iter().any(|tx| tx.tx_type == TransactionType::Release.to_string()));
+
+        // Only completed transfers can be reversed.
+        assert!(matches!(
+            state.bank_service.reverse_transfer("t1", "ops", "again", true).await,
+            Err(AppError::InvalidTransition(_))
+        ));
+    }
+    #[tokio::test]
+    async fn test_wallet_transfers_page_by_cursor() {
+        let state = AppState::for_tests().await;
+        let start = Utc::now() - chrono::Duration::hours(1);
+        let minutes = |m| start + chrono::Duration::minutes(m);
+
+        insert_transfer(&state, "t1", "GA", TransferStatus::Completed, "MXN", minutes(1)).await;
+        insert_transfer(&state, "t2", "GA", TransferStatus::Pending, "USD", minutes(2)).await;
+        insert_transfer(&state, "t3", "GA", TransferStatus::Completed, "MXN", minutes(3)).await;
+        insert_transfer(&state, "t4", "GB", TransferStatus::Completed, "MXN", minutes(4)).await;
+        insert_transfer(&state, "t5", "GA", TransferStatus::Failed, "MXN", minutes(5)).await;
+
+        let ids = |page: &TransferListResponse| page.transfers.iter().map(|t| t.id.clone()).collect::<Vec<_>>();
+        let mut query = TransferListQuery {
+            public_key: Some("GA".to_string()),
+            limit: Some(2),
+            ..Default::default()
+        };
+
+        // Newest first, never another wallet's transfers.
+        let first = state.bank_service.list_transfers(&query).await.unwrap();
+        assert_eq!(ids(&first), ["t5", "t3"]);
+        assert_eq!(first.total, 4);
+
+        query.cursor = first.next_cursor.clone();
+        let second = state.bank_service.list_transfers(&query).await.unwrap();
+        assert_eq!(ids(&second), ["t2", "t1"]);
+        assert!(second.next_cursor.is_none());
+
+        // A cursor only continues the sort it was issued for.
+        query.sort = Some("created_at".to_string());
+        query.cursor = first.next_cursor.clone();
+        assert!(matches!(state.bank_service.list_transfers(&query).await, Err(AppError::Validation(_))));
+
+        let filtered = state
+            .bank_service
+            .list_transfers(&TransferListQuery {
+                public_key: Some("GA".to_string()),
+                status: Some("completed".to_string()),
+                currency: Some("mxn".to_string()),
+                from: Some(minutes(2)),
+                sort: Some("created_at".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(ids(&filtered), ["t3"]);
+        assert_eq!(filtered.total, 1);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,302 @@
+use std::sync::Arc;
+use chrono::{DateTime, Duration, Utc};
+
+use crate::error::{AppError, FieldError};
+use crate::modules::models::beneficiary::{
+    Beneficiary, BeneficiaryResponse, CreateBeneficiaryRequest, VerificationStatus,
+};
+use crate::modules::models::wallet::Wallet;
+use crate::modules::repositories::{beneficiary_repo::BeneficiaryRepository, wallet_repo::WalletRepository};
+use crate::modules::services::{
+    bank_service::BankService, payout_provider::PayoutDestination, risk_service::RiskService,
+};
+use crate::utils::bank_account::{self, BankAccount};
+use crate::utils::encryption::FieldCipher;
+
+const MAX_NICKNAME_LENGTH: usize = 64;
+const MAX_HOLDER_NAME_LENGTH: usize = 140;
+
+/// Saved bank accounts per wallet. Accounts are validated once when saved and
+/// kept encrypted; a new beneficiary can only be paid after a cooling-off period.
+#[derive(Clone)]
+pub struct BeneficiaryService {
+    beneficiary_repo: Arc<BeneficiaryRepository>,
+    wallet_repo: Arc<WalletRepository>,
+    cipher: FieldCipher,
+    cooling_off: Duration,
+}
+
+impl BeneficiaryService {
+    pub fn new(
+        beneficiary_repo: Arc<BeneficiaryRepository>,
+        wallet_repo: Arc<WalletRepository>,
+        cipher: FieldCipher,
+        cooling_off_hours: i64,
+    ) -> Self {
+        Self {
+            beneficiary_repo,
+            wallet_repo,
+            cipher,
+            cooling_off: Duration::hours(cooling_off_hours),
+        }
+    }
+
+    pub async fn create(
+        &self,
+        public_key: &str,
+        request: &CreateBeneficiaryRequest,
+    ) -> Result<BeneficiaryResponse, AppError> {
+        let nickname = Self::text("nickname", &request.nickname, MAX_NICKNAME_LENGTH)?;
+        let holder_name = Self::text("holder_name", &request.holder_name, MAX_HOLDER_NAME_LENGTH)?;
+        let currency = request.currency.trim().to_uppercase();
+
+        let account = bank_account::validate(
+            &currency,
+            request.bank_country.as_deref(),
+            &request.bank_account,
+            request.routing_number.as_deref(),
+        )
+        .map_err(AppError::Validation)?;
+
+        let wallet = self.wallet(public_key).await?;
+
+        let details = serde_json::to_string(&PayoutDestination::new(&account, Some(holder_name)))
+            .map_err(anyhow::Error::from)
+            .and_then(|json| self.cipher.encrypt(&json))
+            .map_err(|e| AppError::InternalError(e.to_string()))?;
+
+        let now = Utc::now();
+        let beneficiary = Beneficiary {
+            id: uuid::Uuid::new_v4().to_string(),
+            wallet_id: wallet.id,
+            nickname: nickname.to_string(),
+            currency,
+            country: account.country.clone(),
+            scheme: account.scheme.to_string(),
+            bank_name: account.bank_name.clone(),
+            account_masked: BankService::mask_account(&account.account),
+            account_hash: RiskService::bank_account_hash(&account),
+            details,
+            verification_status: VerificationStatus::Unverified.to_string(),
+            verification_note: None,
+            available_at: now + self.cooling_off,
+            created_at: now,
+            updated_at: now,
+            deleted_at: None,
+        };
+
+        let created = self.beneficiary_repo.create(&beneficiary).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        if !created {
+            return Err(AppError::BeneficiaryExists(beneficiary.account_masked));
+        }
+        // A previously deleted entry for the account comes back as it was.
+        let beneficiary = self.beneficiary_repo
+            .find_by_account_hash(&beneficiary.wallet_id, &beneficiary.account_hash)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .ok_or_else(|| AppError::InternalError(format!("Beneficiary {} was not stored", beneficiary.id)))?;
+
+        tracing::info!(
+            "Beneficiary {} saved for {} ({} {}), usable from {}",
+            beneficiary.id,
+            public_key,
+            beneficiary.scheme,
+            beneficiary.account_masked,
+            beneficiary.available_at
+        );
+
+        Ok(Self::respond(beneficiary))
+    }
+
+    pub async fn list(&self, public_key: &str) -> Result<Vec<BeneficiaryResponse>, AppError> {
+        let wallet = self.wallet(public_key).await?;
+
+        let beneficiaries = self.beneficiary_repo.find_by_wallet(&wallet.id).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        Ok(beneficiaries.into_iter().map(Self::respond).collect())
+    }
+
+    pub async fn get(&self, public_key: &str, id: &str) -> Result<BeneficiaryResponse, AppError> {
+        let wallet = self.wallet(public_key).await?;
+        Ok(Self::respond(self.find_owned(&wallet, id).await?))
+    }
+
+    /// Removes the beneficiary from the wallet's list. Its verification state
+    /// is kept and still applies to transfers to the account.
+    pub async fn delete(&self, public_key: &str, id: &str) -> Result<(), AppError> {
+        let wallet = self.wallet(public_key).await?;
+
+        let deleted = self.beneficiary_repo.delete(id, &wallet.id, Utc::now()).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        if !deleted {
+            return Err(AppError::BeneficiaryNotFound(id.to_string()));
+        }
+
+        tracing::info!("Beneficiary {} deleted by {}", id, public_key);
+        Ok(())
+    }
+
+    /// Marks an unverified beneficiary verified, which also ends its
+    /// cooling-off, or rejected, which stops transfers to it. A verified one
+    /// can still be rejected; a rejection is final.
+    pub async fn set_verification(
+        &self,
+        id: &str,
+        status: &str,
+        note: Option<&str>,
+        operator: &str,
+    ) -> Result<BeneficiaryResponse, AppError> {
+        let to = VerificationStatus::parse(status)
+            .filter(|status| *status != VerificationStatus::Unverified)
+            .ok_or_else(|| AppError::BadRequest("status must be verified or rejected".to_string()))?;
+        let note = note.map(str::trim).filter(|note| !note.is_empty());
+
+        let beneficiary = self.find(id).await?;
+        let from = beneficiary
+            .status()
+            .filter(|from| Self::can_verify(*from, to))
+            .ok_or_else(|| {
+                AppError::BeneficiaryConflict(format!(
+                    "Beneficiary {} is {} and cannot be marked {}",
+                    id, beneficiary.verification_status, to
+                ))
+            })?;
+
+        let updated = self.beneficiary_repo.set_verification(id, from, to, note, Utc::now()).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        if !updated {
+            return Err(AppError::BeneficiaryConflict(format!("Beneficiary {} changed while it was being verified", id)));
+        }
+
+        tracing::info!("Beneficiary {} marked {} by {}", id, to, operator);
+
+        Ok(Self::respond(self.find(id).await?))
+    }
+
+    fn can_verify(from: VerificationStatus, to: VerificationStatus) -> bool {
+        matches!(
+            (from, to),
+            (VerificationStatus::Unverified, _) | (VerificationStatus::Verified, VerificationStatus::Rejected)
+        )
+    }
+
+    /// Refuses a transfer to a raw account the wallet saved as a beneficiary
+    /// that it could not pay by id: still cooling off or rejected, whether or
+    /// not the wallet deleted it since.
+    pub async fn check_account(&self, wallet: &Wallet, account: &BankAccount) -> Result<(), AppError> {
+        let saved = self.beneficiary_repo
+            .find_by_account_hash(&wallet.id, &RiskService::bank_account_hash(account))
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        match saved {
+            Some(beneficiary) => Self::check_usable(&beneficiary, Utc::now()),
+            None => Ok(()),
+        }
+    }
+
+    fn check_usable(beneficiary: &Beneficiary, now: DateTime<Utc>) -> Result<(), AppError> {
+        if beneficiary.is_usable(now) {
+            return Ok(());
+        }
+        let reason = match beneficiary.status() {
+            Some(VerificationStatus::Unverified) => format!(
+                "Beneficiary {} is in its cooling-off period until {}",
+                beneficiary.id, beneficiary.available_at
+            ),
+            _ => format!("Beneficiary {} is {}", beneficiary.id, beneficiary.verification_status),
+        };
+        Err(AppError::BeneficiaryUnavailable(reason))
+    }
+
+    /// The account and holder name of a beneficiary `wallet` may pay in `currency` now.
+    pub async fn resolve(
+        &self,
+        wallet: &Wallet,
+        id: &str,
+        currency: &str,
+    ) -> Result<(BankAccount, String), AppError> {
+        let beneficiary = self.find_owned(wallet, id).await?;
+
+        if !beneficiary.currency.eq_ignore_ascii_case(currency) {
+            return Err(AppError::BadRequest(format!(
+                "Beneficiary {} receives {}, transfer is in {}",
+                id,
+                beneficiary.currency,
+                currency.to_uppercase()
+            )));
+        }
+
+        Self::check_usable(&beneficiary, Utc::now())?;
+
+        let destination: PayoutDestination = self.cipher
+            .decrypt(&beneficiary.details)
+            .and_then(|json| Ok(serde_json::from_str(&json)?))
+            .map_err(|e| AppError::InternalError(format!("Failed to read beneficiary {}: {}", id, e)))?;
+
+        // Saved accounts were validated when added; validating again restores
+        // the bank name and catches a corridor whose rules changed since.
+        let account = bank_account::validate(
+            currency,
+            Some(&destination.country),
+            &destination.account,
+            destination.routing_number.as_deref(),
+        )
+        .map_err(AppError::Validation)?;
+
+        Ok((account, destination.beneficiary_name.unwrap_or_default()))
+    }
+
+    async fn wallet(&self, public_key: &str) -> Result<Wallet, AppError> {
+        self.wallet_repo.find_by_pubkey(public_key).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .ok_or_else(|| AppError::WalletNotFound(public_key.to_string()))
+    }
+
+    async fn find(&self, id: &str) -> Result<Beneficiary, AppError> {
+        self.beneficiary_repo.find_by_id(id).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .filter(|beneficiary| beneficiary.deleted_at.is_none())
+            .ok_or_else(|| AppError::BeneficiaryNotFound(id.to_string()))
+    }
+
+    async fn find_owned(&self, wallet: &Wallet, id: &str) -> Result<Beneficiary, AppError> {
+        Some(self.find(id).await?)
+            .filter(|beneficiary| beneficiary.wallet_id == wallet.id)
+            .ok_or_else(|| AppError::BeneficiaryNotFound(id.to_string()))
+    }
+
+    fn respond(beneficiary: Beneficiary) -> BeneficiaryResponse {
+        let usable = beneficiary.is_usable(Utc::now());
+        BeneficiaryResponse { beneficiary, usable }
+    }
+
+    fn text<'a>(field: &str, value: &'a str, max_length: usize) -> Result<&'a str, AppError> {
+        let value = value.trim();
+        if value.is_empty() || value.chars().count() > max_length {
+            return Err(AppError::Validation(vec![FieldError::new(
+                field,
+                "invalid",
+                format!("Must be 1 to {} characters", max_length),
+            )]));
+        }
+        Ok(value)
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use VerificationStatus::*;
+
+    #[test]
+    fn test_can_verify() {
+        assert!(BeneficiaryService::can_verify(Unverified, Verified));
+        assert!(BeneficiaryService::can_verify(Unverified, Rejected));
+        assert!(BeneficiaryService::can_verify(Verified, Rejected));
+        assert!(!BeneficiaryService::can_verify(Verified, Verified));
+        assert!(!BeneficiaryService::can_verify(Rejected, Verified));
+        assert!(!BeneficiaryService::can_verify(Rejected, Rejected));
+    }
+}
\ No newline at end of file
@@ -1,111 +1,122 @@
-use anyhow::{Context, Result};
-use reqwest::Client;
-use serde_json::Value;
+use anyhow::Result;
+use std::sync::Arc;
+
+use crate::error::AppError;
+use crate::modules::models::amount::{Amount, FiatAmount, Rounding};
+use crate::modules::models::convert::ConversionLeg;
+use crate::modules::models::token::Token;
+use crate::modules::services::price_oracle::{AggregatedRate, PriceOracle};
+use crate::modules::services::rate_history_service::RateHistoryService;
+use crate::modules::services::token_registry::TokenRegistry;
 
 #[derive(Clone)]
 pub struct ConvertService {
-    coingecko_url: String,
-    client: Client,
+    oracle: Arc<PriceOracle>,
+    rate_history: Arc<RateHistoryService>,
+    tokens: Arc<TokenRegistry>,
+    supported_fiats: Vec<String>,
+    default_fiat: String,
 }
 
 impl ConvertService {
-    pub fn new(coingecko_url: String) -> Self {
+    pub fn new(
+        oracle: Arc<PriceOracle>,
+        rate_history: Arc<RateHistoryService>,
+        tokens: Arc<TokenRegistry>,
+        supported_fiats: Vec<String>,
+        default_fiat: String,
+    ) -> Self {
         Self {
-            coingecko_url,
-            client: Client::new(),
+            oracle,
+            rate_history,
+            tokens,
+            supported_fiats,
+            default_fiat: default_fiat.to_uppercase(),
+        }
+    }
+
+    /// Normalizes the requested ISO 4217 code, falling back to the default fiat.
+    pub fn resolve_fiat(&self, requested: Option<&str>) -> Result<String, AppError> {
+        let code = match requested {
+            Some(code) => code.trim().to_uppercase(),
+            None => return Ok(self.default_fiat.clone()),
+        };
+
+        if !self.is_supported_fiat(&code) {
+            return Err(AppError::UnsupportedCurrency(format!(
+                "{} (supported: {})",
+                code,
+                self.supported_fiats.join(", ")
+            )));
         }
+
+        Ok(code)
+    }
+
+    /// Looks up an enabled token that can be converted to fiat.
+    pub fn resolve_token(&self, symbol: &str) -> Result<&Token, AppError> {
+        self.tokens.resolve(symbol)
+    }
+
+    pub fn is_supported_fiat(&self, code: &str) -> bool {
+        self.supported_fiats.iter().any(|c| c.eq_ignore_ascii_case(code))
     }
 
-    pub async fn convert_to_usdc(
+    /// Converts `amount` of `from_token` to USDC and then to `fiat`, which must
+    /// already have been resolved with [`ConvertService::resolve_fiat`].
+    pub async fn convert_to_fiat(
         &self,
         from_token: &str,
-        amount: &str,
-    ) -> Result<(String, String, String)> {
-        let amount_f64: f64 = amount.parse().context("Invalid amount format")?;
-        
-        let from_token_lower = from_token.to_lowercase();
-        let coin_id = match from_token_lower.as_str() {
-            "xlm" => "stellar",
-            "eth" => "ethereum",
-            "btc" => "bitcoin",
-            _ => from_token_lower.as_str(),
-        };
+        amount: Amount,
+        fiat: &str,
+    ) -> Result<(Amount, FiatAmount, ConversionLeg, ConversionLeg)> {
+        let to_usd = self.leg(from_token, "usd").await?;
+        let usdc_amount: Amount = to_usd.rate.apply(amount, Rounding::Down)?;
+
+        let usd_to_fiat = self.leg("usd", fiat).await?;
+        let fiat_amount: FiatAmount = usd_to_fiat.rate.apply(usdc_amount, Rounding::Down)?;
 
-        let xlm_to_usd = self.get_exchange_rate(coin_id, "usd").await?;
-        
-        let usdc_amount = amount_f64 * xlm_to_usd;
-        
-        let usd_to_mxn = self.get_exchange_rate("usd", "mxn").await
-            .unwrap_or(20.0);
-        
-        let fiat_amount = usdc_amount * usd_to_mxn;
-        
         tracing::info!(
-            "Conversion: {} {} = ${:.2} USDC = ${:.2} MXN",
+            "Conversion: {} {} = ${} USDC = {} {}",
             amount,
             from_token,
             usdc_amount,
-            fiat_amount
+            fiat_amount,
+            fiat.to_uppercase()
         );
-        
-        Ok((
-            format!("{:.6}", usdc_amount),
-            format!("{:.2}", fiat_amount),
-            "CoinGecko".to_string(),
-        ))
-    }
 
-    pub async fn get_exchange_rate(&self, from: &str, to: &str) -> Result<f64> {
-        let url = if from == "usd" {
-            format!("{}/simple/price?ids=tether&vs_currencies={}", self.coingecko_url, to)
-        } else {
-            format!(
-                "{}/simple/price?ids={}&vs_currencies={}",
-                self.coingecko_url, from, to
-            )
-        };
+        Ok((usdc_amount, fiat_amount, to_usd, usd_to_fiat))
+    }
 
-        let response = self.client
-            .get(&url)
-            .send()
-            .await
-            .context("Failed to fetch exchange rate from CoinGecko")?;
+    pub async fn get_exchange_rate(&self, from: &str, to: &str) -> Result<AggregatedRate> {
+        let rate = self.oracle.get_rate(from, to).await?;
 
-        if !response.status().is_success() {
-            return Err(anyhow::anyhow!("CoinGecko API returned error status"));
+        // History only keeps rates observed from live sources.
+        if !rate.fallback {
+            if let Err(e) = self.rate_history.record(from, to, &rate).await {
+                tracing::warn!("Failed to record {}/{} rate snapshot: {}", from, to, e);
+            }
         }
 
-        let json: Value = response.json().await.context("Failed to parse CoinGecko response")?;
-        
-        let rate = if from == "usd" {
-            json["tether"][to]
-                .as_f64()
-                .context("Rate not found in response")?
-        } else {
-            json[from][to]
-                .as_f64()
-                .context("Rate not found in response")?
-        };
-
         Ok(rate)
     }
 
-    pub fn mock_convert(&self, from_token: &str, amount: &str) -> Result<(String, String)> {
-        let amount_f64: f64 = amount.parse().context("Invalid amount format")?;
-        
-        let mock_rate = match from_token.to_lowercase().as_str() {
-            "xlm" => 0.12,
-            "eth" => 2000.0,
-            "btc" => 40000.0,
-            _ => 1.0,
-        };
-        
-        let usdc_amount = amount_f64 * mock_rate;
-        let fiat_amount = usdc_amount * 20.0;
-        
-        Ok((
-            format!("{:.6}", usdc_amount),
-            format!("{:.2}", fiat_amount),
-        ))
+    /// Prices one hop through the oracle, carrying over its provenance.
+    pub async fn leg(&self, from: &str, to: &str) -> Result<ConversionLeg> {
+        if from.eq_ignore_ascii_case(to) {
+            return Ok(ConversionLeg::par(from, to));
+        }
+
+        let aggregated = self.get_exchange_rate(&from.to_lowercase(), &to.to_lowercase()).await?;
+
+        Ok(ConversionLeg {
+            from: from.to_uppercase(),
+            to: to.to_uppercase(),
+            rate: aggregated.rate,
+            source: aggregated.describe(),
+            fetched_at: aggregated.fetched_at,
+            fallback: aggregated.fallback,
+            stale: aggregated.stale,
+        })
     }
 }
\ No newline at end of file
@@ -0,0 +1,106 @@
+use std::sync::Arc;
+use chrono::{Duration, Utc};
+
+use crate::error::AppError;
+use crate::modules::models::idempotency::IdempotencyRecord;
+use crate::modules::repositories::idempotency_repo::IdempotencyRepository;
+
+/// Outcome of presenting an `Idempotency-Key`.
+pub enum IdempotencyClaim {
+    /// First time the key is seen, or its previous holder let the lease run
+    /// out; the caller runs the request and then calls `complete` or `release`.
+    Started,
+    /// The key already finished with the same request; replay its response.
+    Replay(IdempotencyRecord),
+}
+
+#[derive(Clone)]
+pub struct IdempotencyService {
+    repo: Arc<IdempotencyRepository>,
+    ttl: Duration,
+    lease: Duration,
+}
+
+impl IdempotencyService {
+    pub fn new(repo: Arc<IdempotencyRepository>, ttl_hours: i64, lease_seconds: i64) -> Self {
+        Self {
+            repo,
+            ttl: Duration::hours(ttl_hours),
+            lease: Duration::seconds(lease_seconds),
+        }
+    }
+
+    /// Deletes expired keys every `every`.
+    pub fn spawn_cleaner(self: Arc<Self>, every: std::time::Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(every);
+            loop {
+                ticker.tick().await;
+                match self.repo.delete_older_than(Utc::now() - self.ttl).await {
+                    Ok(0) => {}
+                    Ok(deleted) => tracing::debug!("Deleted {} expired idempotency keys", deleted),
+                    Err(e) => tracing::warn!("Idempotency key cleanup failed: {}", e),
+                }
+            }
+        })
+    }
+
+    /// `scope` identifies the route and the caller, so the same key sent by
+    /// another wallet or to another endpoint is a separate request.
+    pub async fn begin(&self, scope: &str, key: &str, fingerprint: &str) -> Result<IdempotencyClaim, AppError> {
+        // A released key can disappear between the insert and the lookup; try once more.
+        for _ in 0..2 {
+            let now = Utc::now();
+            let claimed = self.repo.claim(scope, key, fingerprint, now, now + self.lease).await
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+            if claimed {
+                return Ok(IdempotencyClaim::Started);
+            }
+
+            let Some(record) = self.repo.find(scope, key).await
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            else {
+                continue;
+            };
+
+            if record.fingerprint != fingerprint {
+                return Err(AppError::IdempotencyKeyReused(key.to_string()));
+            }
+            if record.is_in_flight() {
+                return Err(AppError::IdempotencyKeyInUse(key.to_string()));
+            }
+            return Ok(IdempotencyClaim::Replay(record));
+        }
+
+        Err(AppError::IdempotencyKeyInUse(key.to_string()))
+    }
+
+    pub async fn complete(
+        &self,
+        scope: &str,
+        key: &str,
+        status: u16,
+        content_type: Option<&str>,
+        body: &[u8],
+    ) -> Result<(), AppError> {
+        self.repo.complete(scope, key, status, content_type, body, Utc::now()).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))
+    }
+
+    /// How often a running request renews its lease: well before it runs out.
+    pub fn renew_every(&self) -> std::time::Duration {
+        (self.lease / 3).to_std().unwrap_or(std::time::Duration::from_secs(1))
+    }
+
+    /// Keeps an in-flight key's lease from running out while its request is
+    /// still executing, so a retry cannot take it over and run it twice.
+    pub async fn renew(&self, scope: &str, key: &str) -> Result<(), AppError> {
+        self.repo.renew(scope, key, Utc::now() + self.lease).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))
+    }
+
+    pub async fn release(&self, scope: &str, key: &str) -> Result<(), AppError> {
+        self.repo.release(scope, key).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))
+    }
+}
\ No newline at end of file
@@ -0,0 +1,285 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use chrono::{Duration, Utc};
+
+use crate::error::AppError;
+use crate::modules::models::amount::{Amount, FixedPoint};
+use crate::modules::models::kyc::{
+    self, CustomerFieldInfo, GetCustomerQuery, GetCustomerResponse, KycCustomer, KycStatus, PutCustomerRequest,
+    KYC_FIELDS,
+};
+use crate::modules::repositories::{
+    bank_transfer_repo::BankTransferRepository, kyc_repo::KycRepository, wallet_repo::WalletRepository,
+};
+use crate::utils::encryption::FieldCipher;
+
+/// SEP-12 customer records for wallets. The PII fields are only ever stored
+/// encrypted and are never returned, only which ones were provided.
+#[derive(Clone)]
+pub struct KycService {
+    kyc_repo: Arc<KycRepository>,
+    wallet_repo: Arc<WalletRepository>,
+    bank_transfer_repo: Arc<BankTransferRepository>,
+    cipher: FieldCipher,
+    /// Most a customer not yet accepted can transfer, in USD, per `limit_window`.
+    unverified_limit_usd: Option<Amount>,
+    /// Most an accepted customer can transfer, in USD, per `limit_window`.
+    accepted_limit_usd: Option<Amount>,
+    limit_window: Duration,
+}
+
+impl KycService {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        kyc_repo: Arc<KycRepository>,
+        wallet_repo: Arc<WalletRepository>,
+        bank_transfer_repo: Arc<BankTransferRepository>,
+        cipher: FieldCipher,
+        unverified_limit_usd: Option<Amount>,
+        accepted_limit_usd: Option<Amount>,
+        limit_window_hours: i64,
+    ) -> Self {
+        Self {
+            kyc_repo,
+            wallet_repo,
+            bank_transfer_repo,
+            cipher,
+            unverified_limit_usd,
+            accepted_limit_usd,
+            limit_window: Duration::hours(limit_window_hours),
+        }
+    }
+
+    /// Creates or updates the customer of `request.account`, merging the new
+    /// fields into the stored ones. Changing the fields of an accepted
+    /// customer sends it back for review; a rejected customer is final and
+    /// cannot be changed.
+    pub async fn put_customer(&self, request: &PutCustomerRequest) -> Result<String, AppError> {
+        let values = request
+            .fields
+            .iter()
+            .map(|(name, value)| (name.clone(), value.trim().to_string()))
+            .collect::<BTreeMap<_, _>>();
+        kyc::validate_fields(&values).map_err(AppError::Validation)?;
+
+        let wallet = self.wallet_repo.find_by_pubkey(&request.account).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .ok_or_else(|| AppError::WalletNotFound(request.account.clone()))?;
+
+        let existing = self.kyc_repo.find_by_wallet(&wallet.id).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        if let Some(id) = &request.id {
+            if existing.as_ref().map(|customer| &customer.id) != Some(id) {
+                return Err(AppError::CustomerNotFound(id.clone()));
+            }
+        }
+        if existing.as_ref().and_then(KycCustomer::kyc_status) == Some(KycStatus::Rejected) {
+            return Err(AppError::KycRejected(request.account.clone()));
+        }
+
+        let mut fields = match &existing {
+            Some(customer) => self.decrypt_fields(customer)?,
+            None => BTreeMap::new(),
+        };
+        let changed = values.iter().any(|(name, value)| fields.get(name) != Some(value));
+        fields.extend(values);
+
+        let decided = existing
+            .as_ref()
+            .and_then(KycCustomer::kyc_status)
+            .filter(|status| *status == KycStatus::Accepted);
+        let (status, message) = match (decided, changed) {
+            (Some(status), false) => (status, existing.as_ref().and_then(|customer| customer.message.clone())),
+            _ if kyc::missing_fields(&fields).is_empty() => (KycStatus::Processing, None),
+            _ => (KycStatus::NeedsInfo, None),
+        };
+
+        let now = Utc::now();
+        let customer = KycCustomer {
+            id: existing.as_ref().map(|customer| customer.id.clone()).unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+            wallet_id: wallet.id,
+            account: request.account.clone(),
+            status: status.to_string(),
+            message,
+            fields: self.encrypt_fields(&fields)?,
+            created_at: existing.as_ref().map(|customer| customer.created_at).unwrap_or(now),
+            updated_at: now,
+        };
+
+        self.kyc_repo.upsert(&customer).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        tracing::info!("KYC customer {} for {} is {}", customer.id, customer.account, status);
+
+        Ok(customer.id)
+    }
+
+    /// SEP-12 `GET /customer`. An account without a customer gets every field
+    /// it still has to provide.
+    pub async fn get_customer(&self, query: &GetCustomerQuery) -> Result<GetCustomerResponse, AppError> {
+        let customer = match (&query.id, &query.account) {
+            (Some(id), _) => Some(self.find(id).await?),
+            (None, Some(account)) => self.kyc_repo.find_by_account(account).await
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?,
+            (None, None) => return Err(AppError::BadRequest("id or account is required".to_string())),
+        };
+
+        let Some(customer) = customer else {
+            return Ok(GetCustomerResponse {
+                id: None,
+                status: KycStatus::NeedsInfo,
+                fields: KYC_FIELDS.iter().map(|field| (field.name, CustomerFieldInfo::new(field, None))).collect(),
+                provided_fields: BTreeMap::new(),
+                message: None,
+            });
+        };
+
+        if query.account.as_ref().is_some_and(|account| account != &customer.account) {
+            return Err(AppError::CustomerNotFound(customer.id));
+        }
+
+        let status = customer.kyc_status().unwrap_or(KycStatus::NeedsInfo);
+        let provided = self.decrypt_fields(&customer)?;
+
+        let mut fields = BTreeMap::new();
+        let mut provided_fields = BTreeMap::new();
+        for field in KYC_FIELDS {
+            if provided.contains_key(field.name) {
+                let field_status = match status {
+                    KycStatus::NeedsInfo => KycStatus::Processing,
+                    status => status,
+                };
+                provided_fields.insert(field.name, CustomerFieldInfo::new(field, Some(field_status)));
+            } else {
+                fields.insert(field.name, CustomerFieldInfo::new(field, None));
+            }
+        }
+
+        Ok(GetCustomerResponse {
+            id: Some(customer.id),
+            status,
+            fields,
+            provided_fields,
+            message: customer.message,
+        })
+    }
+
+    /// Deletes the customer of `account` and all its PII. A rejected customer
+    /// is kept, so the wallet cannot start over with a clean record.
+    pub async fn delete_customer(&self, account: &str) -> Result<(), AppError> {
+        let customer = self.kyc_repo.find_by_account(account).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .ok_or_else(|| AppError::CustomerNotFound(account.to_string()))?;
+        if customer.kyc_status() == Some(KycStatus::Rejected) {
+            return Err(AppError::KycRejected(account.to_string()));
+        }
+
+        self.kyc_repo.delete(&customer.id).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        tracing::info!("KYC customer {} for {} deleted", customer.id, account);
+        Ok(())
+    }
+
+    /// Customers in `status`, or every one awaiting a decision.
+    pub async fn list(&self, status: Option<&str>) -> Result<Vec<KycCustomer>, AppError> {
+        let status = match status {
+            Some(status) => KycStatus::parse(status)
+                .ok_or_else(|| AppError::BadRequest(format!("Unknown KYC status: {}", status)))?,
+            None => KycStatus::Processing,
+        };
+
+        self.kyc_repo.find_by_status(status).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))
+    }
+
+    /// Accepts or rejects a customer with every required field provided.
+    pub async fn decide(
+        &self,
+        id: &str,
+        status: &str,
+        message: Option<&str>,
+        operator: &str,
+    ) -> Result<KycCustomer, AppError> {
+        let to = KycStatus::parse(status)
+            .filter(|status| matches!(status, KycStatus::Accepted | KycStatus::Rejected))
+            .ok_or_else(|| AppError::BadRequest("status must be ACCEPTED or REJECTED".to_string()))?;
+        let message = message.map(str::trim).filter(|message| !message.is_empty());
+        if to == KycStatus::Rejected && message.is_none() {
+            return Err(AppError::BadRequest("A message is required to reject a customer".to_string()));
+        }
+
+        let customer = self.find(id).await?;
+        let from = customer
+            .kyc_status()
+            .filter(|status| *status != KycStatus::NeedsInfo)
+            .ok_or_else(|| AppError::KycConflict(format!("Customer {} is {}", id, customer.status)))?;
+
+        let updated = self.kyc_repo.update_status(id, from, to, message, Utc::now()).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        if !updated {
+            return Err(AppError::KycConflict(format!("Customer {} changed while it was being reviewed", id)));
+        }
+
+        tracing::info!("KYC customer {} moved from {} to {} by {}", id, from, to, operator);
+
+        self.find(id).await
+    }
+
+    /// Checks a bank transfer of `amount_usd`, together with what the wallet
+    /// transferred in the last `limit_window`, against its KYC limit. A
+    /// rejected customer cannot transfer at all.
+    pub async fn check_transfer_limit(&self, wallet_id: &str, amount_usd: Amount) -> Result<(), AppError> {
+        let status = self.kyc_repo.find_by_wallet(wallet_id).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .and_then(|customer| customer.kyc_status())
+            .unwrap_or(KycStatus::NeedsInfo);
+
+        let limit = match status {
+            KycStatus::Rejected => return Err(AppError::KycRejected(wallet_id.to_string())),
+            KycStatus::Accepted => self.accepted_limit_usd,
+            KycStatus::NeedsInfo | KycStatus::Processing => self.unverified_limit_usd,
+        };
+
+        let Some(limit) = limit else {
+            return Ok(());
+        };
+
+        let since = Utc::now() - self.limit_window;
+        let recent = self.bank_transfer_repo.usd_amounts_since(wallet_id, since).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        let total = recent
+            .into_iter()
+            .try_fold(amount_usd, |total, amount| total.checked_add(amount))?;
+
+        if total > limit {
+            return Err(AppError::KycLimitExceeded {
+                status: status.to_string(),
+                limit: limit.format_with(2),
+                window_hours: self.limit_window.num_hours(),
+            });
+        }
+        Ok(())
+    }
+
+    async fn find(&self, id: &str) -> Result<KycCustomer, AppError> {
+        self.kyc_repo.find_by_id(id).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .ok_or_else(|| AppError::CustomerNotFound(id.to_string()))
+    }
+
+    fn encrypt_fields(&self, fields: &BTreeMap<String, String>) -> Result<String, AppError> {
+        serde_json::to_string(fields)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| self.cipher.encrypt(&json))
+            .map_err(|e| AppError::InternalError(e.to_string()))
+    }
+
+    fn decrypt_fields(&self, customer: &KycCustomer) -> Result<BTreeMap<String, String>, AppError> {
+        self.cipher
+            .decrypt(&customer.fields)
+            .and_then(|json| Ok(serde_json::from_str(&json)?))
+            .map_err(|e| AppError::InternalError(format!("Failed to read KYC customer {}: {}", customer.id, e)))
+    }
+}
\ No newline at end of file
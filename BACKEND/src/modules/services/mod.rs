@@ -1,6 +1,22 @@
 pub mod aa_service;
 pub mod bank_service;
+pub mod beneficiary_service;
 pub mod convert_service;
+pub mod idempotency_service;
+pub mod kyc_service;
+pub mod payout_provider;
+pub mod price_oracle;
+pub mod price_sources;
+pub mod quote_service;
+pub mod rate_history_service;
+pub mod rate_stream_service;
+pub mod reconciliation_service;
+pub mod remittance_service;
 pub mod reputation_service;
+pub mod review_service;
+pub mod risk_service;
+pub mod screening_service;
 pub mod stellar_service;
-pub mod wallet_service;
\ No newline at end of file
+pub mod token_registry;
+pub mod wallet_service;
+pub mod webhook_service;
\ No newline at end of file
@@ -0,0 +1,590 @@
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::modules::models::amount::{FiatAmount, FixedPoint};
+use crate::utils::bank_account::{AccountScheme, BankAccount};
+
+/// What a payout provider reports for a submitted transfer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PayoutState {
+    Processing,
+    Sent,
+    Completed,
+    Failed(String),
+    Reversed(String),
+}
+
+/// The provider answered and refused the request (a 4xx), as opposed to not
+/// being reachable or failing on its side. Only a rejection fails a transfer;
+/// anything else is retried under the same idempotency key.
+#[derive(Debug, thiserror::Error)]
+#[error("{provider} returned {status}: {body}")]
+pub struct PayoutRejected {
+    pub provider: String,
+    pub status: u16,
+    pub body: String,
+}
+
+/// Full beneficiary details, kept encrypted on the transfer until submission.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PayoutDestination {
+    pub scheme: AccountScheme,
+    pub country: String,
+    pub account: String,
+    pub routing_number: Option<String>,
+    pub beneficiary_name: Option<String>,
+}
+
+impl PayoutDestination {
+    pub fn new(account: &BankAccount, beneficiary_name: Option<&str>) -> Self {
+        Self {
+            scheme: account.scheme,
+            country: account.country.clone(),
+            account: account.account.clone(),
+            routing_number: account.routing_number.clone(),
+            beneficiary_name: beneficiary_name.map(str::to_string),
+        }
+    }
+
+    fn last4(&self) -> &str {
+        &self.account[self.account.len().saturating_sub(4)..]
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PayoutRequest {
+    /// Also used as the idempotency key with providers that take one.
+    pub transfer_id: String,
+    pub amount: FiatAmount,
+    pub currency: String,
+    pub destination: PayoutDestination,
+}
+
+#[async_trait]
+pub trait PayoutProvider: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Whether the provider pays out `currency` to accounts of `scheme`.
+    fn supports(&self, currency: &str, scheme: AccountScheme) -> bool;
+
+    /// Hands the payout to the provider and returns its reference for it.
+    async fn submit(&self, payout: &PayoutRequest) -> Result<String>;
+
+    async fn status(&self, reference: &str) -> Result<PayoutState>;
+
+    /// Stops a payout that has not been sent yet.
+    async fn cancel(&self, reference: &str) -> Result<()>;
+}
+
+/// Picks the provider for a corridor. Providers are tried in configured
+/// order; the first one supporting the currency and account scheme wins.
+#[derive(Clone, Default)]
+pub struct PayoutRouter {
+    providers: Vec<Arc<dyn PayoutProvider>>,
+}
+
+impl PayoutRouter {
+    pub fn new(providers: Vec<Arc<dyn PayoutProvider>>) -> Self {
+        Self { providers }
+    }
+
+    pub fn select(&self, currency: &str, scheme: AccountScheme) -> Option<&Arc<dyn PayoutProvider>> {
+        self.providers.iter().find(|p| p.supports(currency, scheme))
+    }
+
+    /// Provider a transfer was routed to when it was created.
+    pub fn get(&self, name: &str) -> Option<&Arc<dyn PayoutProvider>> {
+        self.providers.iter().find(|p| p.name() == name)
+    }
+}
+
+/// SPEI payouts through a CLABE-based provider API (STP style):
+/// `POST /payouts`, `GET /payouts/{id}`, `POST /payouts/{id}/cancel`.
+#[derive(Clone)]
+pub struct SpeiPayoutProvider {
+    base_url: String,
+    api_key: String,
+    client: Client,
+}
+
+impl SpeiPayoutProvider {
+    pub fn new(base_url: String, api_key: String) -> Self {
+        Self {
+            base_url,
+            api_key,
+            client: Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl PayoutProvider for SpeiPayoutProvider {
+    fn name(&self) -> &str {
+        "spei"
+    }
+
+    fn supports(&self, currency: &str, scheme: AccountScheme) -> bool {
+        currency.eq_ignore_ascii_case("MXN") && scheme == AccountScheme::Clabe
+    }
+
+    async fn submit(&self, payout: &PayoutRequest) -> Result<String> {
+        let body = json!({
+            "reference": payout.transfer_id,
+            "clabe": payout.destination.account,
+            "beneficiary_name": payout.destination.beneficiary_name,
+            "amount": payout.amount.to_string(),
+            "currency": "MXN",
+            "concept": format!("Transfer {}", payout.transfer_id),
+        });
+
+        let json = send_json(
+            self.client.post(format!("{}/payouts", self.base_url)).bearer_auth(&self.api_key).json(&body),
+            "SPEI",
+        )
+        .await?;
+
+        json["id"].as_str().map(str::to_string).context("SPEI response has no payout id")
+    }
+
+    async fn status(&self, reference: &str) -> Result<PayoutState> {
+        let json = send_json(
+            self.client.get(format!("{}/payouts/{}", self.base_url, reference)).bearer_auth(&self.api_key),
+            "SPEI",
+        )
+        .await?;
+
+        let reason = json["reason"].as_str().unwrap_or("no reason given").to_string();
+        match json["status"].as_str() {
+            Some("pending") | Some("queued") => Ok(PayoutState::Processing),
+            Some("sent") => Ok(PayoutState::Sent),
+            Some("settled") => Ok(PayoutState::Completed),
+            Some("failed") | Some("cancelled") => Ok(PayoutState::Failed(reason)),
+            Some("returned") => Ok(PayoutState::Reversed(reason)),
+            other => Err(anyhow!("Unknown SPEI payout status: {:?}", other)),
+        }
+    }
+
+    async fn cancel(&self, reference: &str) -> Result<()> {
+        send_json(
+            self.client.post(format!("{}/payouts/{}/cancel", self.base_url, reference)).bearer_auth(&self.api_key),
+            "SPEI",
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+/// Circle wants a UUID; derived from the transfer and account so a resubmitted
+/// payout registers the same bank instead of a new one.
+fn bank_idempotency_key(payout: &PayoutRequest) -> String {
+    let digest = Sha256::new()
+        .chain_update(payout.transfer_id.as_bytes())
+        .chain_update(b":")
+        .chain_update(payout.destination.account.as_bytes())
+        .finalize();
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&digest[..16]);
+    uuid::Builder::from_sha1_bytes(bytes).into_uuid().to_string()
+}
+
+/// USD wires through Circle's business account API. The beneficiary bank is
+/// registered first, then paid out to.
+#[derive(Clone)]
+pub struct CirclePayoutProvider {
+    base_url: String,
+    api_key: String,
+    client: Client,
+}
+
+impl CirclePayoutProvider {
+    pub fn new(base_url: String, api_key: String) -> Self {
+        Self {
+            base_url,
+            api_key,
+            client: Client::new(),
+        }
+    }
+
+    async fn register_bank(&self, payout: &PayoutRequest) -> Result<String> {
+        let destination = &payout.destination;
+        let mut body = json!({
+            "idempotencyKey": bank_idempotency_key(payout),
+            "billingDetails": {
+                "name": destination.beneficiary_name.clone().unwrap_or_default(),
+                "country": destination.country,
+            },
+            "bankAddress": { "country": destination.country },
+        });
+        match destination.scheme {
+            AccountScheme::Iban => body["iban"] = json!(destination.account),
+            _ => {
+                body["accountNumber"] = json!(destination.account);
+                body["routingNumber"] = json!(destination.routing_number);
+            }
+        }
+
+        let json = send_json(
+            self.client
+                .post(format!("{}/v1/businessAccount/banks/wire", self.base_url))
+                .bearer_auth(&self.api_key)
+                .json(&body),
+            "Circle",
+        )
+        .await?;
+
+        json["data"]["id"].as_str().map(str::to_string).context("Circle response has no bank id")
+    }
+}
+
+#[async_trait]
+impl PayoutProvider for CirclePayoutProvider {
+    fn name(&self) -> &str {
+        "circle"
+    }
+
+    fn supports(&self, currency: &str, scheme: AccountScheme) -> bool {
+        currency.eq_ignore_ascii_case("USD") && matches!(scheme, AccountScheme::Aba | AccountScheme::Iban)
+    }
+
+    async fn submit(&self, payout: &PayoutRequest) -> Result<String> {
+        let bank_id = self.register_bank(payout).await?;
+
+        let body = json!({
+            "idempotencyKey": payout.transfer_id,
+            "destination": { "type": "wire", "id": bank_id },
+            "amount": { "amount": payout.amount.to_string(), "currency": "USD" },
+        });
+
+        let json = send_json(
+            self.client
+                .post(format!("{}/v1/businessAccount/payouts", self.base_url))
+                .bearer_auth(&self.api_key)
+                .json(&body),
+            "Circle",
+        )
+        .await?;
+
+        json["data"]["id"].as_str().map(str::to_string).context("Circle response has no payout id")
+    }
+
+    async fn status(&self, reference: &str) -> Result<PayoutState> {
+        let json = send_json(
+            self.client
+                .get(format!("{}/v1/businessAccount/payouts/{}", self.base_url, reference))
+                .bearer_auth(&self.api_key),
+            "Circle",
+        )
+        .await?;
+
+        let data = &json["data"];
+        if let Some(return_status) = data["return"]["status"].as_str() {
+            return Ok(PayoutState::Reversed(format!("Wire returned ({})", return_status)));
+        }
+
+        match data["status"].as_str() {
+            Some("pending") => Ok(PayoutState::Processing),
+            Some("complete") => Ok(PayoutState::Completed),
+            Some("failed") => Ok(PayoutState::Failed(
+                data["errorCode"].as_str().unwrap_or("payout failed").to_string(),
+            )),
+            other => Err(anyhow!("Unknown Circle payout status: {:?}", other)),
+        }
+    }
+
+    async fn cancel(&self, _reference: &str) -> Result<()> {
+        Err(anyhow!("Circle payouts cannot be cancelled once submitted"))
+    }
+}
+
+/// US ACH payouts through Stripe Treasury outbound payments from the
+/// configured financial account.
+#[derive(Clone)]
+pub struct StripePayoutProvider {
+    base_url: String,
+    secret_key: String,
+    financial_account: String,
+    client: Client,
+}
+
+impl StripePayoutProvider {
+    pub fn new(base_url: String, secret_key: String, financial_account: String) -> Self {
+        Self {
+            base_url,
+            secret_key,
+            financial_account,
+            client: Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl PayoutProvider for StripePayoutProvider {
+    fn name(&self) -> &str {
+        "stripe"
+    }
+
+    fn supports(&self, currency: &str, scheme: AccountScheme) -> bool {
+        currency.eq_ignore_ascii_case("USD") && scheme == AccountScheme::Aba
+    }
+
+    async fn submit(&self, payout: &PayoutRequest) -> Result<String> {
+        let destination = &payout.destination;
+        let form = [
+            ("financial_account", self.financial_account.clone()),
+            ("amount", payout.amount.units().to_string()),
+            ("currency", "usd".to_string()),
+            ("destination_payment_method_data[type]", "us_bank_account".to_string()),
+            ("destination_payment_method_data[us_bank_account][account_number]", destination.account.clone()),
+            (
+                "destination_payment_method_data[us_bank_account][routing_number]",
+                destination.routing_number.clone().unwrap_or_default(),
+            ),
+            ("destination_payment_method_data[us_bank_account][account_holder_type]", "individual".to_string()),
+            (
+                "destination_payment_method_data[billing_details][name]",
+                destination.beneficiary_name.clone().unwrap_or_default(),
+            ),
+            ("metadata[transfer_id]", payout.transfer_id.clone()),
+        ];
+
+        let json = send_json(
+            self.client
+                .post(format!("{}/v1/treasury/outbound_payments", self.base_url))
+                .bearer_auth(&self.secret_key)
+                .header("Idempotency-Key", &payout.transfer_id)
+                .form(&form),
+            "Stripe",
+        )
+        .await?;
+
+        json["id"].as_str().map(str::to_string).context("Stripe response has no payment id")
+    }
+
+    async fn status(&self, reference: &str) -> Result<PayoutState> {
+        let json = send_json(
+            self.client
+                .get(format!("{}/v1/treasury/outbound_payments/{}", self.base_url, reference))
+                .bearer_auth(&self.secret_key),
+            "Stripe",
+        )
+        .await?;
+
+        match json["status"].as_str() {
+            Some("processing") => Ok(PayoutState::Processing),
+            Some("posted") => Ok(PayoutState::Completed),
+            Some("failed") => Ok(PayoutState::Failed("Outbound payment failed".to_string())),
+            Some("canceled") => Ok(PayoutState::Failed("Outbound payment canceled".to_string())),
+            Some("returned") => Ok(PayoutState::Reversed(
+                json["returned_details"]["code"].as_str().unwrap_or("returned").to_string(),
+            )),
+            other => Err(anyhow!("Unknown Stripe outbound payment status: {:?}", other)),
+        }
+    }
+
+    async fn cancel(&self, reference: &str) -> Result<()> {
+        send_json(
+            self.client
+                .post(format!("{}/v1/treasury/outbound_payments/{}/cancel", self.base_url, reference))
+                .bearer_auth(&self.secret_key),
+            "Stripe",
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+/// Sandbox provider that moves no money and accepts every corridor.
+///
+/// With a `driver_url`, a local stand-in HTTP server decides what happens:
+/// payouts are posted to `{driver_url}/payouts`, `GET {driver_url}/payouts/{ref}`
+/// answers `{"status": "processing|sent|completed|failed|reversed", "reason": ..}`
+/// and cancellations go to `POST {driver_url}/payouts/{ref}/cancel`.
+///
+/// Without one, a payout is sent after half of `settle_after` and completed
+/// after all of it. Like bank sandboxes, account numbers ending in `0000` fail
+/// and ones ending in `9999` are reversed once sent.
+pub struct MockPayoutProvider {
+    settle_after: Duration,
+    driver_url: Option<String>,
+    client: Client,
+    submitted: Mutex<HashMap<String, MockPayout>>,
+}
+
+struct MockPayout {
+    at: Instant,
+    last4: String,
+    cancelled: bool,
+}
+
+impl MockPayoutProvider {
+    pub fn new(settle_after: Duration, driver_url: Option<String>) -> Self {
+        Self {
+            settle_after,
+            driver_url,
+            client: Client::new(),
+            submitted: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn local_status(&self, reference: &str) -> Result<PayoutState> {
+        let submitted = self.submitted.lock().map_err(|_| anyhow!("Mock payout state poisoned"))?;
+        // Submissions are kept in memory only, so after a restart nothing is
+        // known about them; that must not read as paid.
+        let Some(payout) = submitted.get(reference) else {
+            return Ok(PayoutState::Failed(format!("Unknown payout {}", reference)));
+        };
+
+        if payout.cancelled {
+            return Ok(PayoutState::Failed("Cancelled".to_string()));
+        }
+
+        if payout.last4 == "0000" {
+            return Ok(PayoutState::Failed("Beneficiary account rejected by the bank".to_string()));
+        }
+
+        let elapsed = payout.at.elapsed();
+        if elapsed < self.settle_after / 2 {
+            Ok(PayoutState::Processing)
+        } else if elapsed < self.settle_after {
+            Ok(PayoutState::Sent)
+        } else if payout.last4 == "9999" {
+            Ok(PayoutState::Reversed("Returned by the beneficiary bank".to_string()))
+        } else {
+            Ok(PayoutState::Completed)
+        }
+    }
+}
+
+#[async_trait]
+impl PayoutProvider for MockPayoutProvider {
+    fn name(&self) -> &str {
+        "mock"
+    }
+
+    fn supports(&self, _currency: &str, _scheme: AccountScheme) -> bool {
+        true
+    }
+
+    async fn submit(&self, payout: &PayoutRequest) -> Result<String> {
+        let reference = format!("mock_{}", payout.transfer_id);
+
+        if let Some(driver_url) = &self.driver_url {
+            let body = json!({
+                "reference": reference,
+                "transfer_id": payout.transfer_id,
+                "amount": payout.amount.to_string(),
+                "currency": payout.currency,
+                "scheme": payout.destination.scheme,
+                "account_last4": payout.destination.last4(),
+            });
+            send_json(self.client.post(format!("{}/payouts", driver_url)).json(&body), "Mock payout driver").await?;
+            return Ok(reference);
+        }
+
+        self.submitted
+            .lock()
+            .map_err(|_| anyhow!("Mock payout state poisoned"))?
+            .insert(
+                reference.clone(),
+                MockPayout {
+                    at: Instant::now(),
+                    last4: payout.destination.last4().to_string(),
+                    cancelled: false,
+                },
+            );
+        Ok(reference)
+    }
+
+    async fn status(&self, reference: &str) -> Result<PayoutState> {
+        let Some(driver_url) = &self.driver_url else {
+            return self.local_status(reference);
+        };
+
+        let json = send_json(
+            self.client.get(format!("{}/payouts/{}", driver_url, reference)),
+            "Mock payout driver",
+        )
+        .await?;
+
+        let reason = json["reason"].as_str().unwrap_or("set by mock driver").to_string();
+        match json["status"].as_str() {
+            Some("processing") => Ok(PayoutState::Processing),
+            Some("sent") => Ok(PayoutState::Sent),
+            Some("completed") => Ok(PayoutState::Completed),
+            Some("failed") => Ok(PayoutState::Failed(reason)),
+            Some("reversed") => Ok(PayoutState::Reversed(reason)),
+            other => Err(anyhow!("Unknown mock payout status: {:?}", other)),
+        }
+    }
+
+    async fn cancel(&self, reference: &str) -> Result<()> {
+        if let Some(driver_url) = &self.driver_url {
+            send_json(
+                self.client.post(format!("{}/payouts/{}/cancel", driver_url, reference)),
+                "Mock payout driver",
+            )
+            .await?;
+            return Ok(());
+        }
+
+        let mut submitted = self.submitted.lock().map_err(|_| anyhow!("Mock payout state poisoned"))?;
+        match submitted.get_mut(reference) {
+            Some(payout) if payout.at.elapsed() < self.settle_after / 2 => {
+                payout.cancelled = true;
+                Ok(())
+            }
+            Some(_) => Err(anyhow!("Payout {} has already been sent", reference)),
+            None => Err(anyhow!("Unknown payout {}", reference)),
+        }
+    }
+}
+
+async fn send_json(request: reqwest::RequestBuilder, provider: &str) -> Result<Value> {
+    let response = request
+        .send()
+        .await
+        .with_context(|| format!("Failed to reach {}", provider))?;
+
+    let status = response.status();
+    let body: Value = response.json().await.unwrap_or(Value::Null);
+    if status.is_client_error() {
+        return Err(PayoutRejected {
+            provider: provider.to_string(),
+            status: status.as_u16(),
+            body: body.to_string(),
+        }
+        .into());
+    }
+    if !status.is_success() {
+        return Err(anyhow!("{} returned {}: {}", provider, status, body));
+    }
+
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_router_picks_first_supporting_provider() {
+        let router = PayoutRouter::new(vec![
+            Arc::new(SpeiPayoutProvider::new("http://spei".to_string(), "key".to_string())),
+            Arc::new(StripePayoutProvider::new("http://stripe".to_string(), "sk".to_string(), "fa".to_string())),
+            Arc::new(MockPayoutProvider::new(Duration::ZERO, None)),
+        ]);
+
+        assert_eq!(router.select("MXN", AccountScheme::Clabe).map(|p| p.name()), Some("spei"));
+        assert_eq!(router.select("USD", AccountScheme::Aba).map(|p| p.name()), Some("stripe"));
+        assert_eq!(router.select("EUR", AccountScheme::Iban).map(|p| p.name()), Some("mock"));
+        assert!(router.get("circle").is_none());
+        assert!(PayoutRouter::default().select("MXN", AccountScheme::Clabe).is_none());
+    }
+}
\ No newline at end of file
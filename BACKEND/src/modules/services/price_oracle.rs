@@ -0,0 +1,309 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use tokio::task::JoinSet;
+
+use crate::modules::models::amount::{FixedPoint, Rate};
+use crate::modules::services::price_sources::PriceSource;
+
+#[derive(Debug, Clone)]
+pub struct PriceSample {
+    pub source: String,
+    pub rate: Rate,
+    pub fallback: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct AggregatedRate {
+    pub rate: Rate,
+    pub used: Vec<PriceSample>,
+    pub dropped: Vec<PriceSample>,
+    /// (max - min) / median of the samples that were kept.
+    pub spread: f64,
+    pub fetched_at: DateTime<Utc>,
+    /// No live source answered: the rate is a cached live rate or comes from
+    /// fallback sources.
+    pub fallback: bool,
+    /// The rate is a cached one older than the oracle's staleness limit.
+    pub stale: bool,
+}
+
+impl AggregatedRate {
+    pub fn describe(&self) -> String {
+        let used = self
+            .used
+            .iter()
+            .map(|s| s.source.as_str())
+            .collect::<Vec<_>>()
+            .join("+");
+
+        let mut description = format!("median({}) spread {:.2}%", used, self.spread * 100.0);
+
+        if !self.dropped.is_empty() {
+            let dropped = self
+                .dropped
+                .iter()
+                .map(|s| s.source.as_str())
+                .collect::<Vec<_>>()
+                .join("+");
+            description.push_str(&format!(", dropped outliers: {}", dropped));
+        }
+
+        if self.stale {
+            description.push_str(&format!(", stale since {}", self.fetched_at.to_rfc3339()));
+        } else if self.fallback {
+            description.push_str(", fallback");
+        }
+
+        description
+    }
+}
+
+pub struct PriceOracle {
+    sources: Vec<Arc<dyn PriceSource>>,
+    max_deviation: f64,
+    stale_after: Duration,
+    /// Last rate obtained from live sources, per pair.
+    last_live: RwLock<HashMap<String, AggregatedRate>>,
+}
+
+impl PriceOracle {
+    pub fn new(sources: Vec<Arc<dyn PriceSource>>, max_deviation: f64, stale_after: Duration) -> Self {
+        Self {
+            sources,
+            max_deviation,
+            stale_after,
+            last_live: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub async fn get_rate(&self, from: &str, to: &str) -> Result<AggregatedRate> {
+        let from = from.to_lowercase();
+        let to = to.to_lowercase();
+
+        let mut tasks = JoinSet::new();
+        for source in &self.sources {
+            let source = source.clone();
+            let (from, to) = (from.clone(), to.clone());
+            tasks.spawn(async move {
+                let result = source.get_rate(&from, &to).await;
+                (source.name().to_string(), source.is_fallback(), result)
+            });
+        }
+
+        let mut samples = Vec::new();
+        while let Some(joined) = tasks.join_next().await {
+            match joined {
+                Ok((source, fallback, Ok(rate))) if !rate.is_zero() => {
+                    samples.push(PriceSample { source, rate, fallback });
+                }
+                Ok((source, _, Ok(rate))) => {
+                    tracing::warn!("Price source {} returned invalid rate {} for {}/{}", source, rate, from, to);
+                }
+                Ok((source, _, Err(e))) => {
+                    tracing::debug!("Price source {} failed for {}/{}: {}", source, from, to, e);
+                }
+                Err(e) => {
+                    tracing::error!("Price source task panicked: {}", e);
+                }
+            }
+        }
+
+        let pair = format!("{}/{}", from, to);
+        let (fallback_samples, live_samples): (Vec<_>, Vec<_>) =
+            samples.into_iter().partition(|s| s.fallback);
+
+        let aggregated = match aggregate(live_samples, self.max_deviation) {
+            Some(live) => {
+                if let Ok(mut cache) = self.last_live.write() {
+                    cache.insert(pair.clone(), live.clone());
+                }
+                live
+            }
+            None => self
+                .fallback_rate(&pair, fallback_samples)
+                .ok_or_else(|| anyhow::anyhow!("No price source could quote {}", pair))?,
+        };
+
+        tracing::debug!("Oracle rate {} = {} ({})", pair, aggregated.rate, aggregated.describe());
+
+        Ok(aggregated)
+    }
+
+    /// Used when no live source answered. Prefers a recent live rate, then the
+    /// fallback sources, then a live rate past the staleness limit.
+    fn fallback_rate(&self, pair: &str, fallback_samples: Vec<PriceSample>) -> Option<AggregatedRate> {
+        let cached = self
+            .last_live
+            .read()
+            .ok()
+            .and_then(|cache| cache.get(pair).cloned())
+            .map(|mut cached| {
+                cached.fallback = true;
+                cached.stale = Utc::now() - cached.fetched_at > self.stale_after;
+                cached
+            });
+
+        let chosen = match cached {
+            Some(cached) if !cached.stale => Some(cached),
+            cached => aggregate(fallback_samples, self.max_deviation).or(cached),
+        };
+
+        if let Some(rate) = &chosen {
+            tracing::warn!("No live price source could quote {}; using {}", pair, rate.describe());
+        }
+
+        chosen
+    }
+}
+
+/// Takes the median of all samples, drops those further than `max_deviation`
+/// (relative) from it and returns the median of what remains.
+fn aggregate(mut samples: Vec<PriceSample>, max_deviation: f64) -> Option<AggregatedRate> {
+    if samples.is_empty() {
+        return None;
+    }
+
+    samples.sort_by_key(|s| s.rate);
+
+    let all_rates: Vec<Rate> = samples.iter().map(|s| s.rate).collect();
+    let initial_median = median(&all_rates).to_f64();
+
+    let fallback = samples.iter().all(|s| s.fallback);
+
+    let (mut used, mut dropped): (Vec<_>, Vec<_>) = samples
+        .into_iter()
+        .partition(|s| ((s.rate.to_f64() - initial_median) / initial_median).abs() <= max_deviation);
+
+    // With no consensus (e.g. two sources far apart) there is nothing to call
+    // an outlier, so keep everything and let the spread show the disagreement.
+    if used.is_empty() {
+        used = std::mem::take(&mut dropped);
+    }
+
+    let used_rates: Vec<Rate> = used.iter().map(|s| s.rate).collect();
+    let rate = median(&used_rates);
+    let spread = (used_rates[used_rates.len() - 1].to_f64() - used_rates[0].to_f64()) / rate.to_f64();
+
+    Some(AggregatedRate {
+        rate,
+        used,
+        dropped,
+        spread,
+        fetched_at: Utc::now(),
+        fallback,
+        stale: false,
+    })
+}
+
+fn median(sorted: &[Rate]) -> Rate {
+    let mid = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        sorted[mid - 1].midpoint(sorted[mid]).unwrap_or(sorted[mid])
+    } else {
+        sorted[mid]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(source: &str, rate: &str) -> PriceSample {
+        PriceSample {
+            source: source.to_string(),
+            rate: rate.parse().unwrap(),
+            fallback: false,
+        }
+    }
+
+    struct FixedSource {
+        name: &'static str,
+        rate: Option<&'static str>,
+        fallback: bool,
+    }
+
+    #[async_trait::async_trait]
+    impl PriceSource for FixedSource {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn is_fallback(&self) -> bool {
+            self.fallback
+        }
+
+        async fn get_rate(&self, _from: &str, _to: &str) -> Result<Rate> {
+            match self.rate {
+                Some(rate) => Ok(rate.parse()?),
+                None => Err(anyhow::anyhow!("unavailable")),
+            }
+        }
+    }
+
+    fn oracle(live: Option<&'static str>, stale_after: Duration) -> PriceOracle {
+        PriceOracle::new(
+            vec![
+                Arc::new(FixedSource { name: "live", rate: live, fallback: false }),
+                Arc::new(FixedSource { name: "static", rate: Some("20"), fallback: true }),
+            ],
+            0.05,
+            stale_after,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_fallback_only_without_live_sources() {
+        let live = oracle(Some("17.5"), Duration::minutes(5)).get_rate("usd", "mxn").await.unwrap();
+        assert_eq!(live.rate.to_string(), "17.500000000000");
+        assert!(!live.fallback);
+
+        let fallback = oracle(None, Duration::minutes(5)).get_rate("usd", "mxn").await.unwrap();
+        assert_eq!(fallback.rate.to_string(), "20.000000000000");
+        assert!(fallback.fallback && !fallback.stale);
+    }
+
+    #[tokio::test]
+    async fn test_cached_live_rate_goes_stale() {
+        let oracle = oracle(Some("17.5"), Duration::zero());
+        oracle.get_rate("usd", "mxn").await.unwrap();
+
+        let mut cached = oracle.last_live.read().unwrap().get("usd/mxn").cloned().unwrap();
+        cached.fetched_at -= Duration::minutes(1);
+        oracle.last_live.write().unwrap().insert("usd/mxn".to_string(), cached);
+
+        // Without fallback samples the stale rate is still served, flagged.
+        let rate = oracle.fallback_rate("usd/mxn", vec![]).unwrap();
+        assert!(rate.fallback && rate.stale);
+        assert_eq!(rate.rate.to_string(), "17.500000000000");
+
+        let rate = oracle.fallback_rate("usd/mxn", vec![PriceSample { fallback: true, ..sample("static", "20") }]).unwrap();
+        assert!(rate.fallback && !rate.stale);
+        assert_eq!(rate.rate.to_string(), "20.000000000000");
+    }
+
+    #[test]
+    fn test_aggregate_drops_outliers() {
+        let samples = vec![sample("a", "0.120"), sample("b", "0.122"), sample("c", "0.30")];
+        let result = aggregate(samples, 0.05).unwrap();
+
+        assert_eq!(result.used.len(), 2);
+        assert_eq!(result.dropped[0].source, "c");
+        assert_eq!(result.rate.to_string(), "0.121000000000");
+    }
+
+    #[test]
+    fn test_aggregate_without_consensus_keeps_all() {
+        let samples = vec![sample("a", "1"), sample("b", "2")];
+        let result = aggregate(samples, 0.05).unwrap();
+
+        assert_eq!(result.used.len(), 2);
+        assert_eq!(result.rate.to_string(), "1.500000000000");
+    }
+
+    #[test]
+    fn test_aggregate_empty() {
+        assert!(aggregate(vec![], 0.05).is_none());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,416 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::modules::models::amount::{FixedPoint, Rate, Rounding};
+use crate::modules::services::token_registry::TokenRegistry;
+
+#[async_trait]
+pub trait PriceSource: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Fallback sources (the static last-resort table) are only used when no
+    /// live source can quote a pair. The offline script is the primary source
+    /// of its mode, so it is not one.
+    fn is_fallback(&self) -> bool {
+        false
+    }
+
+    /// Returns how many units of `to` one unit of `from` is worth.
+    async fn get_rate(&self, from: &str, to: &str) -> Result<Rate>;
+}
+
+#[derive(Clone)]
+pub struct CoinGeckoSource {
+    base_url: String,
+    tokens: Arc<TokenRegistry>,
+    client: Client,
+}
+
+impl CoinGeckoSource {
+    pub fn new(base_url: String, tokens: Arc<TokenRegistry>) -> Self {
+        Self {
+            base_url,
+            tokens,
+            client: Client::new(),
+        }
+    }
+
+    fn coin_id(&self, symbol: &str) -> Result<String> {
+        // USD -> fiat rates are read off a USD stablecoin.
+        if symbol == "usd" {
+            return Ok("tether".to_string());
+        }
+
+        self.tokens
+            .coingecko_id(symbol)
+            .map(str::to_string)
+            .with_context(|| format!("No CoinGecko id registered for {}", symbol))
+    }
+
+    fn vs_currency(symbol: &str) -> &str {
+        match symbol {
+            "usdc" => "usd",
+            other => other,
+        }
+    }
+}
+
+#[async_trait]
+impl PriceSource for CoinGeckoSource {
+    fn name(&self) -> &str {
+        "coingecko"
+    }
+
+    async fn get_rate(&self, from: &str, to: &str) -> Result<Rate> {
+        let coin_id = self.coin_id(from)?;
+        let vs_currency = Self::vs_currency(to);
+
+        let url = format!(
+            "{}/simple/price?ids={}&vs_currencies={}",
+            self.base_url, coin_id, vs_currency
+        );
+
+        let response = self.client
+            .get(&url)
+            .send()
+            .await
+            .context("Failed to fetch exchange rate from CoinGecko")?;
+
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("CoinGecko API returned error status"));
+        }
+
+        let json: Value = response.json().await.context("Failed to parse CoinGecko response")?;
+
+        let rate = json[coin_id.as_str()][vs_currency]
+            .as_f64()
+            .context("Rate not found in response")?;
+
+        Ok(Rate::from_f64(rate)?)
+    }
+}
+
+/// Prices pairs of Stellar assets from the token registry using the mid price
+/// of the Horizon order book. USD is read as the registry's USDC.
+#[derive(Clone)]
+pub struct StellarDexSource {
+    horizon_url: String,
+    tokens: Arc<TokenRegistry>,
+    client: Client,
+}
+
+impl StellarDexSource {
+    pub fn new(horizon_url: String, tokens: Arc<TokenRegistry>) -> Self {
+        Self {
+            horizon_url,
+            tokens,
+            client: Client::new(),
+        }
+    }
+
+    /// Horizon query parameters for one side of an order book.
+    fn asset_params(&self, side: &str, symbol: &str) -> Result<String> {
+        let symbol = if symbol == "usd" { "usdc" } else { symbol };
+        let token = self.tokens
+            .get(symbol)
+            .filter(|token| token.enabled)
+            .with_context(|| format!("{} is not in the token registry", symbol))?;
+        let code = token.stellar_code.as_deref()
+            .with_context(|| format!("{} is not a Stellar asset", symbol))?;
+
+        Ok(match &token.stellar_issuer {
+            None => format!("{}_asset_type=native", side),
+            Some(issuer) => format!(
+                "{side}_asset_type={}&{side}_asset_code={}&{side}_asset_issuer={}",
+                if code.len() <= 4 { "credit_alphanum4" } else { "credit_alphanum12" },
+                code,
+                issuer,
+                side = side
+            ),
+        })
+    }
+
+    async fn mid_price(&self, from: &str, to: &str) -> Result<Rate> {
+        // Offers selling `from` for `to` are priced in units of `to` per `from`.
+        let url = format!(
+            "{}/order_book?{}&{}&limit=1",
+            self.horizon_url,
+            self.asset_params("selling", from)?,
+            self.asset_params("buying", to)?
+        );
+
+        let response = self.client
+            .get(&url)
+            .send()
+            .await
+            .context("Failed to fetch order book from Horizon")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            return Err(anyhow::anyhow!("Horizon order book failed with status {}", status));
+        }
+
+        let json: Value = response.json().await.context("Failed to parse order book response")?;
+
+        let best_price = |side: &str| -> Option<Rate> {
+            let price = json[side].as_array()?.first()?["price"].as_str()?;
+            Rate::parse_rounded(price, Rounding::HalfEven).ok()
+        };
+
+        match (best_price("bids"), best_price("asks")) {
+            (Some(bid), Some(ask)) => Ok(bid.midpoint(ask)?),
+            (Some(price), None) | (None, Some(price)) => Ok(price),
+            (None, None) => Err(anyhow::anyhow!("Order book for {}/{} is empty", from, to)),
+        }
+    }
+}
+
+#[async_trait]
+impl PriceSource for StellarDexSource {
+    fn name(&self) -> &str {
+        "stellar_dex"
+    }
+
+    async fn get_rate(&self, from: &str, to: &str) -> Result<Rate> {
+        self.mid_price(from, to)
+            .await
+            .with_context(|| format!("Pair {}/{} is not quoted on the Stellar DEX", from, to))
+    }
+}
+
+/// Fixed rates keyed by `"from/to"`, either the built-in defaults or loaded
+/// from a JSON file of the same shape.
+#[derive(Clone)]
+pub struct StaticPriceSource {
+    rates: HashMap<String, Rate>,
+}
+
+impl StaticPriceSource {
+    pub fn new(rates: HashMap<String, Rate>) -> Self {
+        Self { rates }
+    }
+
+    pub fn from_file(path: &str) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read static rates file {}", path))?;
+
+        let rates: HashMap<String, Rate> = serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse static rates file {}", path))?;
+
+        Ok(Self::new(
+            rates
+                .into_iter()
+                .map(|(pair, rate)| (pair.to_lowercase(), rate))
+                .collect(),
+        ))
+    }
+
+    pub fn default_rates() -> HashMap<String, Rate> {
+        [
+            ("xlm/usd", "0.12"),
+            ("eth/usd", "2000"),
+            ("btc/usd", "40000"),
+            ("usdc/usd", "1"),
+            ("usd/mxn", "20"),
+            ("usd/eur", "0.92"),
+            ("usd/brl", "5"),
+            ("usd/ars", "900"),
+        ]
+        .into_iter()
+        .filter_map(|(pair, rate)| Some((pair.to_string(), rate.parse().ok()?)))
+        .collect()
+    }
+
+    pub fn lookup(&self, from: &str, to: &str) -> Option<Rate> {
+        if from == to {
+            return Some(Rate::ONE);
+        }
+
+        if let Some(rate) = self.rates.get(&format!("{}/{}", from, to)) {
+            return Some(*rate);
+        }
+
+        if let Some(rate) = self.rates.get(&format!("{}/{}", to, from)) {
+            return rate.inverse(Rounding::HalfEven).ok();
+        }
+
+        if from != "usd" && to != "usd" {
+            let from_usd = self.lookup(from, "usd")?;
+            let usd_to = self.lookup("usd", to)?;
+            return from_usd.mul(usd_to, Rounding::HalfEven).ok();
+        }
+
+        None
+    }
+}
+
+impl Default for StaticPriceSource {
+    fn default() -> Self {
+        Self::new(Self::default_rates())
+    }
+}
+
+#[async_trait]
+impl PriceSource for StaticPriceSource {
+    fn name(&self) -> &str {
+        "static"
+    }
+
+    fn is_fallback(&self) -> bool {
+        true
+    }
+
+    async fn get_rate(&self, from: &str, to: &str) -> Result<Rate> {
+        self.lookup(from, to)
+            .ok_or_else(|| anyhow::anyhow!("No static rate for {}/{}", from, to))
+    }
+}
+
+/// A step change applied to `pair` once `at_seconds` have passed since the
+/// source was created.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RateMovement {
+    pub pair: String,
+    pub at_seconds: i64,
+    pub rate: Rate,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScriptedRatesFile {
+    pub rates: HashMap<String, Rate>,
+    #[serde(default)]
+    pub movements: Vec<RateMovement>,
+    /// Restarts the script after this many seconds when set.
+    pub repeat_every_seconds: Option<i64>,
+}
+
+/// Deterministic rates for offline mode: a fixed table plus scripted
+/// movements over time. Never makes outbound calls.
+#[derive(Clone)]
+pub struct ScriptedPriceSource {
+    rates: HashMap<String, Rate>,
+    movements: Vec<RateMovement>,
+    repeat_every_seconds: Option<i64>,
+    started_at: DateTime<Utc>,
+}
+
+impl ScriptedPriceSource {
+    pub fn new(script: ScriptedRatesFile, started_at: DateTime<Utc>) -> Result<Self> {
+        if script.repeat_every_seconds.is_some_and(|s| s <= 0) {
+            return Err(anyhow::anyhow!("repeat_every_seconds must be positive"));
+        }
+
+        let mut movements: Vec<RateMovement> = script
+            .movements
+            .into_iter()
+            .map(|m| RateMovement { pair: m.pair.to_lowercase(), ..m })
+            .collect();
+        movements.sort_by_key(|m| m.at_seconds);
+
+        Ok(Self {
+            rates: script
+                .rates
+                .into_iter()
+                .map(|(pair, rate)| (pair.to_lowercase(), rate))
+                .collect(),
+            movements,
+            repeat_every_seconds: script.repeat_every_seconds,
+            started_at,
+        })
+    }
+
+    pub fn from_file(path: &str, started_at: DateTime<Utc>) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read offline rates file {}", path))?;
+
+        let script: ScriptedRatesFile = serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse offline rates file {}", path))?;
+
+        Self::new(script, started_at)
+    }
+
+    /// Rate table in effect `elapsed_seconds` after start.
+    pub fn rates_at(&self, elapsed_seconds: i64) -> StaticPriceSource {
+        let elapsed = match self.repeat_every_seconds {
+            Some(period) => elapsed_seconds.rem_euclid(period),
+            None => elapsed_seconds,
+        };
+
+        let mut rates = self.rates.clone();
+        for movement in self.movements.iter().take_while(|m| m.at_seconds <= elapsed) {
+            rates.insert(movement.pair.clone(), movement.rate);
+        }
+
+        StaticPriceSource::new(rates)
+    }
+}
+
+impl Default for ScriptedPriceSource {
+    fn default() -> Self {
+        Self {
+            rates: StaticPriceSource::default_rates(),
+            movements: Vec::new(),
+            repeat_every_seconds: None,
+            started_at: Utc::now(),
+        }
+    }
+}
+
+#[async_trait]
+impl PriceSource for ScriptedPriceSource {
+    fn name(&self) -> &str {
+        "offline"
+    }
+
+    async fn get_rate(&self, from: &str, to: &str) -> Result<Rate> {
+        let elapsed = (Utc::now() - self.started_at).num_seconds();
+
+        self.rates_at(elapsed)
+            .lookup(from, to)
+            .ok_or_else(|| anyhow::anyhow!("No offline rate for {}/{}", from, to))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn script(json: &str) -> ScriptedPriceSource {
+        ScriptedPriceSource::new(serde_json::from_str(json).unwrap(), Utc::now()).unwrap()
+    }
+
+    #[test]
+    fn test_scripted_movements() {
+        let source = script(
+            r#"{
+                "rates": { "XLM/USD": "0.10", "usd/mxn": "20" },
+                "movements": [
+                    { "pair": "xlm/usd", "at_seconds": 120, "rate": "0.12" },
+                    { "pair": "xlm/usd", "at_seconds": 60, "rate": "0.11" }
+                ],
+                "repeat_every_seconds": 300
+            }"#,
+        );
+
+        let xlm_usd = |elapsed| source.rates_at(elapsed).lookup("xlm", "usd").unwrap().to_string();
+        assert_eq!(xlm_usd(0), "0.100000000000");
+        assert_eq!(xlm_usd(60), "0.110000000000");
+        assert_eq!(xlm_usd(299), "0.120000000000");
+        assert_eq!(xlm_usd(300), "0.100000000000");
+
+        let xlm_mxn = source.rates_at(90).lookup("xlm", "mxn").unwrap();
+        assert_eq!(xlm_mxn.to_string(), "2.200000000000");
+    }
+
+    #[test]
+    fn test_only_static_source_is_fallback() {
+        assert!(!ScriptedPriceSource::default().is_fallback());
+        assert!(StaticPriceSource::default().is_fallback());
+    }
+}
\ No newline at end of file
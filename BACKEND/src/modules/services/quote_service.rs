@@ -0,0 +1,261 @@
+use std::sync::Arc;
+use chrono::{Duration, Utc};
+
+use crate::error::AppError;
+use crate::modules::models::amount::{Amount, FixedPoint, Rate, Rounding};
+use crate::modules::models::convert::ConversionLeg;
+use crate::modules::models::quote::{Quote, QuoteAsset, QuoteRequest};
+use crate::modules::repositories::quote_repo::QuoteRepository;
+use crate::modules::services::convert_service::ConvertService;
+use crate::modules::services::token_registry::TokenRegistry;
+
+#[derive(Clone)]
+pub struct QuoteService {
+    quote_repo: Arc<QuoteRepository>,
+    convert_service: Arc<ConvertService>,
+    tokens: Arc<TokenRegistry>,
+    ttl_seconds: i64,
+    fee_bps: u32,
+}
+
+impl QuoteService {
+    pub fn new(
+        quote_repo: Arc<QuoteRepository>,
+        convert_service: Arc<ConvertService>,
+        tokens: Arc<TokenRegistry>,
+        ttl_seconds: i64,
+        fee_bps: u32,
+    ) -> Self {
+        Self {
+            quote_repo,
+            convert_service,
+            tokens,
+            ttl_seconds,
+            fee_bps,
+        }
+    }
+
+    /// Issues a quote that only `requested_by` (a wallet public key) can redeem.
+    pub async fn create_quote(&self, request: &QuoteRequest, requested_by: &str) -> Result<Quote, AppError> {
+        let sell = QuoteAsset::parse(&request.sell_asset)
+            .ok_or_else(|| AppError::BadRequest(format!("Invalid sell_asset: {}", request.sell_asset)))?;
+        let buy = QuoteAsset::parse(&request.buy_asset)
+            .ok_or_else(|| AppError::BadRequest(format!("Invalid buy_asset: {}", request.buy_asset)))?;
+
+        if sell == buy {
+            return Err(AppError::BadRequest("sell_asset and buy_asset must differ".to_string()));
+        }
+
+        for asset in [&sell, &buy] {
+            match asset {
+                QuoteAsset::Fiat(code) => {
+                    if !self.convert_service.is_supported_fiat(code) {
+                        return Err(AppError::UnsupportedCurrency(code.clone()));
+                    }
+                }
+                QuoteAsset::Stellar { code, issuer } => {
+                    let registered = self.tokens
+                        .find_stellar(code, issuer.as_deref())
+                        .is_some_and(|token| token.enabled);
+                    if !registered {
+                        return Err(AppError::UnsupportedAsset(match issuer {
+                            Some(issuer) => format!("{}:{}", code, issuer),
+                            None => code.clone(),
+                        }));
+                    }
+                }
+            }
+        }
+
+        if !matches!(request.context.as_str(), "sep6" | "sep24" | "sep31") {
+            return Err(AppError::BadRequest(format!("Unsupported context: {}", request.context)));
+        }
+
+        let now = Utc::now();
+        let expires_at = now + Duration::seconds(self.ttl_seconds);
+        if let Some(expire_after) = request.expire_after {
+            if expire_after > expires_at {
+                return Err(AppError::BadRequest(format!(
+                    "expire_after cannot be more than {} seconds in the future",
+                    self.ttl_seconds
+                )));
+            }
+        }
+
+        // Both legs go through USD, same as the indicative conversion.
+        let (sell_usd_rate, sell_leg) = self.usd_rate(&sell).await?;
+        let (buy_usd_rate, buy_leg) = self.usd_rate(&buy).await?;
+        let rate = sell_usd_rate.div(buy_usd_rate, Rounding::HalfEven)?;
+
+        let fee_bps = self.fee_bps as i128;
+
+        // The user pays fees and rounding: buy amounts round down, sell amounts and fees round up.
+        let (sell_amount, buy_amount, fee_total) = match (request.sell_amount, request.buy_amount) {
+            (Some(sell_amount), None) => {
+                Self::check_decimals(sell_amount, &sell, "sell_amount")?;
+                let fee = sell_amount
+                    .scale_by(fee_bps, 10_000, Rounding::Up)?
+                    .round_to(sell.decimals(), Rounding::Up)?;
+                let buy_amount: Amount = rate
+                    .apply(sell_amount.checked_sub(fee)?, Rounding::Down)?;
+                (sell_amount, buy_amount.round_to(buy.decimals(), Rounding::Down)?, fee)
+            }
+            (None, Some(buy_amount)) => {
+                Self::check_decimals(buy_amount, &buy, "buy_amount")?;
+                let net_sell: Amount = rate.apply_inverse(buy_amount, Rounding::Up)?;
+                let sell_amount = net_sell
+                    .scale_by(10_000, 10_000 - fee_bps, Rounding::Up)?
+                    .round_to(sell.decimals(), Rounding::Up)?;
+                let fee = sell_amount.checked_sub(net_sell)?;
+                (sell_amount, buy_amount, fee)
+            }
+            _ => {
+                return Err(AppError::BadRequest(
+                    "Exactly one of sell_amount or buy_amount is required".to_string(),
+                ))
+            }
+        };
+
+        if buy_amount.is_zero() {
+            return Err(AppError::BadRequest("Amount too small to quote".to_string()));
+        }
+
+        let quote = Quote {
+            id: uuid::Uuid::new_v4().to_string(),
+            context: request.context.clone(),
+            sell_asset: request.sell_asset.clone(),
+            sell_amount,
+            buy_asset: request.buy_asset.clone(),
+            buy_amount,
+            // SEP-38: sell_amount - fee = price * buy_amount
+            price: Rate::from_ratio(sell_amount.checked_sub(fee_total)?, buy_amount, Rounding::HalfEven)?,
+            total_price: Rate::from_ratio(sell_amount, buy_amount, Rounding::HalfEven)?,
+            fee_total,
+            fee_asset: request.sell_asset.clone(),
+            usd_amount: sell_usd_rate.apply(sell_amount.checked_sub(fee_total)?, Rounding::Down)?,
+            rate_source: format!("{}; {}", sell_leg.describe(), buy_leg.describe()),
+            rate_fallback: sell_leg.fallback || buy_leg.fallback,
+            rate_stale: sell_leg.stale || buy_leg.stale,
+            created_at: now,
+            expires_at,
+            used_at: None,
+            used_by: None,
+            requested_by: Some(requested_by.to_string()),
+        };
+
+        self.quote_repo.create(&quote, &[sell_leg, buy_leg]).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        tracing::info!(
+            "Quote {} issued: {} {} -> {} {} (expires {})",
+            quote.id,
+            quote.sell_amount,
+            quote.sell_asset,
+            quote.buy_amount,
+            quote.buy_asset,
+            quote.expires_at
+        );
+
+        Ok(quote)
+    }
+
+    pub async fn get_quote(&self, id: &str) -> Result<Quote, AppError> {
+        self.quote_repo.find_by_id(id).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .ok_or_else(|| AppError::QuoteNotFound(id.to_string()))
+    }
+
+    /// The market legs the quote was priced from, sell side first, each in the
+    /// direction its source quotes it (fiat as USD -> fiat).
+    pub async fn legs(&self, id: &str) -> Result<Vec<ConversionLeg>, AppError> {
+        self.quote_repo.find_legs(id).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))
+    }
+
+    /// A quote issued to `requester` that can still be redeemed, without
+    /// redeeming it. `check` validates that the quote matches the operation
+    /// it is meant for.
+    pub async fn usable<F>(&self, id: &str, requester: &str, check: F) -> Result<Quote, AppError>
+    where
+        F: FnOnce(&Quote) -> Result<(), AppError>,
+    {
+        let quote = self.get_quote(id).await?;
+
+        if quote.requested_by.as_deref() != Some(requester) {
+            return Err(AppError::QuoteUnavailable(format!("Quote {} was issued to another wallet", id)));
+        }
+
+        if quote.used_at.is_some() {
+            return Err(AppError::QuoteUnavailable(format!("Quote {} has already been used", id)));
+        }
+
+        if quote.is_expired(Utc::now()) {
+            return Err(AppError::QuoteUnavailable(format!("Quote {} expired at {}", id, quote.expires_at)));
+        }
+
+        check(&quote)?;
+        Ok(quote)
+    }
+
+    /// Consumes a quote issued to `requester` exactly once. `check` validates
+    /// that the quote matches the operation it is being redeemed for before it
+    /// is marked as used.
+    pub async fn redeem<F>(&self, id: &str, requester: &str, used_by: &str, check: F) -> Result<Quote, AppError>
+    where
+        F: FnOnce(&Quote) -> Result<(), AppError>,
+    {
+        let quote = self.usable(id, requester, check).await?;
+
+        let marked = self.quote_repo.mark_used(id, requester, used_by, Utc::now()).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        if !marked {
+            return Err(AppError::QuoteUnavailable(format!("Quote {} is no longer available", id)));
+        }
+
+        tracing::info!("Quote {} redeemed by {}", id, used_by);
+        Ok(quote)
+    }
+
+    /// USD value of one unit of `asset`, with the leg it was priced from.
+    async fn usd_rate(&self, asset: &QuoteAsset) -> Result<(Rate, ConversionLeg), AppError> {
+        if asset.is_usd() {
+            return Ok((Rate::ONE, ConversionLeg::par(&asset.symbol(), "usd")));
+        }
+
+        let symbol = asset.symbol();
+        let is_fiat = matches!(asset, QuoteAsset::Fiat(_));
+
+        // Fiat is priced as USD -> fiat, which is how the sources quote it.
+        let (from, to) = if is_fiat { ("usd", symbol.as_str()) } else { (symbol.as_str(), "usd") };
+
+        let leg = self.convert_service
+            .leg(from, to)
+            .await
+            .map_err(|e| AppError::ExternalApiError(e.to_string()))?;
+
+        let usd_rate = if is_fiat {
+            leg.rate.inverse(Rounding::HalfEven)?
+        } else {
+            leg.rate
+        };
+
+        Ok((usd_rate, leg))
+    }
+
+    fn check_decimals(amount: Amount, asset: &QuoteAsset, field: &str) -> Result<(), AppError> {
+        if amount.is_zero() {
+            return Err(AppError::BadRequest(format!("{} must be positive", field)));
+        }
+
+        if amount.round_to(asset.decimals(), Rounding::Down)? != amount {
+            return Err(AppError::BadRequest(format!(
+                "{} has more than {} decimals",
+                field,
+                asset.decimals()
+            )));
+        }
+
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,211 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::error::AppError;
+use crate::modules::models::rate_history::{
+    CandleInterval, RateCandle, RateHistoryQuery, RateHistoryResponse, RateSnapshot,
+};
+use crate::modules::repositories::rate_history_repo::RateHistoryRepository;
+use crate::modules::services::price_oracle::AggregatedRate;
+
+const DEFAULT_HISTORY_LIMIT: i64 = 200;
+const MAX_HISTORY_LIMIT: i64 = 1000;
+
+#[derive(Clone)]
+pub struct RateHistoryService {
+    repo: Arc<RateHistoryRepository>,
+    retention_days: i64,
+}
+
+impl RateHistoryService {
+    pub fn new(repo: Arc<RateHistoryRepository>, retention_days: i64) -> Self {
+        Self { repo, retention_days }
+    }
+
+    pub async fn record(&self, from: &str, to: &str, rate: &AggregatedRate) -> Result<()> {
+        let snapshot = RateSnapshot {
+            id: uuid::Uuid::new_v4().to_string(),
+            base: from.to_lowercase(),
+            quote: to.to_lowercase(),
+            rate: rate.rate,
+            source: rate.describe(),
+            observed_at: Utc::now(),
+        };
+
+        self.repo.create_snapshot(&snapshot).await
+    }
+
+    /// Rolls raw snapshots up into candles for every interval, starting from the
+    /// newest candle of each series so the bucket in progress is refreshed, then
+    /// drops snapshots older than the retention period.
+    pub async fn downsample(&self, now: DateTime<Utc>) -> Result<usize> {
+        let mut written = 0;
+
+        for (base, quote) in self.repo.find_pairs().await? {
+            for interval in CandleInterval::ALL {
+                let label = interval.to_string();
+                let since = self.repo
+                    .latest_candle_start(&base, &quote, &label)
+                    .await?
+                    .unwrap_or_default();
+
+                let snapshots = self.repo
+                    .find_snapshots(&base, &quote, since, now, i64::MAX)
+                    .await?;
+
+                for candle in Self::build_candles(&snapshots, interval) {
+                    self.repo.upsert_candle(&candle).await?;
+                    written += 1;
+                }
+            }
+        }
+
+        let pruned = self.repo
+            .delete_snapshots_before(now - Duration::days(self.retention_days))
+            .await?;
+
+        tracing::debug!("Rate history downsampled: {} candles written, {} snapshots pruned", written, pruned);
+        Ok(written)
+    }
+
+    pub fn spawn_downsampler(self: Arc<Self>, every: std::time::Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(every);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = self.downsample(Utc::now()).await {
+                    tracing::warn!("Rate history downsampling failed: {}", e);
+                }
+            }
+        })
+    }
+
+    pub async fn history(&self, query: &RateHistoryQuery) -> Result<RateHistoryResponse, AppError> {
+        let base = query.from.trim().to_lowercase();
+        let quote = query.to.trim().to_lowercase();
+        if base.is_empty() || quote.is_empty() {
+            return Err(AppError::BadRequest("from and to are required".to_string()));
+        }
+
+        let limit = query.limit.unwrap_or(DEFAULT_HISTORY_LIMIT);
+        if !(1..=MAX_HISTORY_LIMIT).contains(&limit) {
+            return Err(AppError::BadRequest(format!("limit must be between 1 and {}", MAX_HISTORY_LIMIT)));
+        }
+
+        let interval = query.interval.clone().unwrap_or_else(|| CandleInterval::Hour.to_string());
+        let end = query.end.unwrap_or_else(Utc::now);
+
+        let mut response = RateHistoryResponse {
+            from: base.clone(),
+            to: quote.clone(),
+            interval: interval.clone(),
+            candles: Vec::new(),
+            snapshots: Vec::new(),
+        };
+
+        if interval == "raw" {
+            let start = query.start.unwrap_or(end - Duration::days(1));
+            Self::check_range(start, end)?;
+            response.snapshots = self.repo
+                .find_snapshots(&base, &quote, start, end, limit)
+                .await
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+            return Ok(response);
+        }
+
+        let candle_interval = CandleInterval::parse(&interval)
+            .ok_or_else(|| AppError::BadRequest(format!("Unsupported interval: {} (use 1m, 1h, 1d or raw)", interval)))?;
+        let start = query.start.unwrap_or(end - candle_interval.duration() * limit as i32);
+        Self::check_range(start, end)?;
+
+        response.candles = self.repo
+            .find_candles(&base, &quote, &interval, candle_interval.bucket_start(start), end, limit)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        Ok(response)
+    }
+
+    fn check_range(start: DateTime<Utc>, end: DateTime<Utc>) -> Result<(), AppError> {
+        if start >= end {
+            return Err(AppError::BadRequest("start must be before end".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Groups snapshots, which must be sorted by `observed_at`, into candles.
+    fn build_candles(snapshots: &[RateSnapshot], interval: CandleInterval) -> Vec<RateCandle> {
+        let mut candles: BTreeMap<DateTime<Utc>, RateCandle> = BTreeMap::new();
+
+        for snapshot in snapshots {
+            let bucket_start = interval.bucket_start(snapshot.observed_at);
+            candles
+                .entry(bucket_start)
+                .and_modify(|candle| {
+                    candle.high = candle.high.max(snapshot.rate);
+                    candle.low = candle.low.min(snapshot.rate);
+                    candle.close = snapshot.rate;
+                    candle.samples += 1;
+                })
+                .or_insert_with(|| RateCandle {
+                    base: snapshot.base.clone(),
+                    quote: snapshot.quote.clone(),
+                    interval: interval.to_string(),
+                    bucket_start,
+                    open: snapshot.rate,
+                    high: snapshot.rate,
+                    low: snapshot.rate,
+                    close: snapshot.rate,
+                    samples: 1,
+                });
+        }
+
+        candles.into_values().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn snapshot(minute: u32, second: u32, rate: &str) -> RateSnapshot {
+        RateSnapshot {
+            id: format!("{}-{}", minute, second),
+            base: "xlm".to_string(),
+            quote: "usd".to_string(),
+            rate: rate.parse().unwrap(),
+            source: "static".to_string(),
+            observed_at: Utc.with_ymd_and_hms(2024, 1, 1, 10, minute, second).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_build_candles() {
+        let snapshots = vec![
+            snapshot(0, 5, "0.12"),
+            snapshot(0, 30, "0.15"),
+            snapshot(0, 50, "0.11"),
+            snapshot(0, 55, "0.13"),
+            snapshot(1, 10, "0.14"),
+        ];
+
+        let candles = RateHistoryService::build_candles(&snapshots, CandleInterval::Minute);
+        assert_eq!(candles.len(), 2);
+
+        let first = &candles[0];
+        assert_eq!(first.bucket_start, Utc.with_ymd_and_hms(2024, 1, 1, 10, 0, 0).unwrap());
+        assert_eq!(first.open.to_string(), "0.120000000000");
+        assert_eq!(first.high.to_string(), "0.150000000000");
+        assert_eq!(first.low.to_string(), "0.110000000000");
+        assert_eq!(first.close.to_string(), "0.130000000000");
+        assert_eq!(first.samples, 4);
+
+        let hourly = RateHistoryService::build_candles(&snapshots, CandleInterval::Hour);
+        assert_eq!(hourly.len(), 1);
+        assert_eq!(hourly[0].close.to_string(), "0.140000000000");
+        assert_eq!(hourly[0].samples, 5);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,299 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+use tokio::sync::{broadcast, Notify};
+
+use crate::error::AppError;
+use crate::modules::models::rate_stream::RateStreamMessage;
+use crate::modules::services::convert_service::ConvertService;
+
+const BROADCAST_CAPACITY: usize = 256;
+
+/// Polls each subscribed pair once per interval, however many clients follow
+/// it, and fans the results out to every WebSocket connection.
+pub struct RateStreamService {
+    convert_service: Arc<ConvertService>,
+    poll_interval: Duration,
+    max_pairs_per_client: usize,
+    /// Number of connections subscribed to each pair.
+    subscribers: Mutex<HashMap<String, usize>>,
+    latest: RwLock<HashMap<String, RateStreamMessage>>,
+    sender: broadcast::Sender<RateStreamMessage>,
+    wake: Notify,
+}
+
+impl RateStreamService {
+    pub fn new(convert_service: Arc<ConvertService>, poll_interval: Duration, max_pairs_per_client: usize) -> Self {
+        let (sender, _) = broadcast::channel(BROADCAST_CAPACITY);
+
+        Self {
+            convert_service,
+            poll_interval,
+            max_pairs_per_client,
+            subscribers: Mutex::new(HashMap::new()),
+            latest: RwLock::new(HashMap::new()),
+            sender,
+            wake: Notify::new(),
+        }
+    }
+
+    pub fn connect(self: &Arc<Self>) -> RateStreamClient {
+        RateStreamClient {
+            service: self.clone(),
+            receiver: self.sender.subscribe(),
+            pairs: HashSet::new(),
+        }
+    }
+
+    pub fn spawn_poller(self: Arc<Self>) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(self.poll_interval);
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {}
+                    _ = self.wake.notified() => {}
+                }
+                self.poll().await;
+            }
+        })
+    }
+
+    async fn poll(&self) {
+        let pairs: Vec<String> = match self.subscribers.lock() {
+            Ok(subscribers) => subscribers.keys().cloned().collect(),
+            Err(_) => return,
+        };
+
+        for pair in pairs {
+            let Some((from, to)) = pair.split_once('/') else { continue };
+
+            let message = match self.convert_service.leg(from, to).await {
+                Ok(leg) => RateStreamMessage::Rate(leg),
+                Err(e) => {
+                    tracing::debug!("Rate stream could not price {}: {}", pair, e);
+                    RateStreamMessage::Error {
+                        pair: Some(pair.clone()),
+                        message: format!("No rate available for {}", pair),
+                    }
+                }
+            };
+
+            if let Ok(mut latest) = self.latest.write() {
+                latest.insert(pair, message.clone());
+            }
+            // Fails only when nobody is connected.
+            let _ = self.sender.send(message);
+        }
+    }
+
+    /// Lowercases `pair` and checks that both sides are USD, an enabled
+    /// registry token or a supported fiat, so unknown pairs are never polled.
+    pub fn normalize_pair(&self, pair: &str) -> Result<String, AppError> {
+        let pair = pair.trim().to_lowercase();
+        let (from, to) = match pair.split_once('/') {
+            Some((from, to)) if !from.is_empty() && !to.is_empty() && !to.contains('/') => (from, to),
+            _ => return Err(AppError::BadRequest(format!("Invalid pair {}, expected from/to", pair))),
+        };
+
+        for asset in [from, to] {
+            if asset != "usd" && !self.convert_service.is_supported_fiat(asset) {
+                self.convert_service.resolve_token(asset)?;
+            }
+        }
+
+        Ok(pair)
+    }
+
+    pub fn normalize_pairs(&self, pairs: &[String]) -> Result<Vec<String>, AppError> {
+        pairs.iter().map(|pair| self.normalize_pair(pair)).collect()
+    }
+
+    fn acquire(&self, pair: &str) {
+        let newly_polled = match self.subscribers.lock() {
+            Ok(mut subscribers) => {
+                let count = subscribers.entry(pair.to_string()).or_insert(0);
+                *count += 1;
+                *count == 1
+            }
+            Err(_) => false,
+        };
+
+        if newly_polled {
+            self.wake.notify_one();
+        }
+    }
+
+    fn release(&self, pair: &str) {
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            if let Some(count) = subscribers.get_mut(pair) {
+                *count -= 1;
+                if *count == 0 {
+                    subscribers.remove(pair);
+                    if let Ok(mut latest) = self.latest.write() {
+                        latest.remove(pair);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// One WebSocket connection's view of the stream. Subscriptions are released
+/// when it is dropped.
+pub struct RateStreamClient {
+    service: Arc<RateStreamService>,
+    receiver: broadcast::Receiver<RateStreamMessage>,
+    pairs: HashSet<String>,
+}
+
+impl RateStreamClient {
+    /// Subscribes to `pairs` and returns the normalized pairs together with the
+    /// latest known rate for each, if any.
+    pub fn subscribe(&mut self, pairs: &[String]) -> Result<(Vec<String>, Vec<RateStreamMessage>), AppError> {
+        let pairs = self.service.normalize_pairs(pairs)?;
+
+        let new_pairs: HashSet<&String> = pairs.iter().filter(|p| !self.pairs.contains(*p)).collect();
+        if self.pairs.len() + new_pairs.len() > self.service.max_pairs_per_client {
+            return Err(AppError::BadRequest(format!(
+                "At most {} pairs per connection",
+                self.service.max_pairs_per_client
+            )));
+        }
+
+        for pair in new_pairs {
+            self.service.acquire(pair);
+            self.pairs.insert(pair.clone());
+        }
+
+        let latest = match self.service.latest.read() {
+            Ok(latest) => pairs.iter().filter_map(|p| latest.get(p).cloned()).collect(),
+            Err(_) => Vec::new(),
+        };
+
+        Ok((pairs, latest))
+    }
+
+    pub fn unsubscribe(&mut self, pairs: &[String]) {
+        for pair in pairs {
+            let Ok(pair) = self.service.normalize_pair(pair) else { continue };
+            if self.pairs.remove(&pair) {
+                self.service.release(&pair);
+            }
+        }
+    }
+
+    /// Next update for one of this client's pairs. `None` once the stream closes.
+    pub async fn next(&mut self) -> Option<RateStreamMessage> {
+        loop {
+            match self.receiver.recv().await {
+                Ok(message) => {
+                    if message.pair().is_some_and(|p| self.pairs.contains(&p)) {
+                        return Some(message);
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::debug!("Rate stream client lagged, skipped {} updates", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+impl Drop for RateStreamClient {
+    fn drop(&mut self) {
+        for pair in self.pairs.drain() {
+            self.service.release(&pair);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use sqlx::sqlite::SqlitePoolOptions;
+    use crate::modules::models::amount::Rate;
+    use crate::modules::repositories::rate_history_repo::RateHistoryRepository;
+    use crate::modules::services::price_oracle::PriceOracle;
+    use crate::modules::services::price_sources::PriceSource;
+    use crate::modules::services::rate_history_service::RateHistoryService;
+    use crate::modules::services::token_registry::TokenRegistry;
+
+    struct CountingSource {
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait::async_trait]
+    impl PriceSource for CountingSource {
+        fn name(&self) -> &str {
+            "counting"
+        }
+
+        async fn get_rate(&self, _from: &str, _to: &str) -> anyhow::Result<Rate> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok("17.5".parse()?)
+        }
+    }
+
+    async fn service(calls: Arc<AtomicUsize>) -> Arc<RateStreamService> {
+        let pool = SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap();
+        sqlx::raw_sql(include_str!("../../../migrations/007_rate_snapshots.sql"))
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let oracle = PriceOracle::new(vec![Arc::new(CountingSource { calls })], 0.05, chrono::Duration::minutes(5));
+        let convert_service = ConvertService::new(
+            Arc::new(oracle),
+            Arc::new(RateHistoryService::new(Arc::new(RateHistoryRepository::new(pool)), 30)),
+            Arc::new(TokenRegistry::new(TokenRegistry::default_tokens("GISSUER")).unwrap()),
+            vec!["MXN".to_string()],
+            "MXN".to_string(),
+        );
+
+        Arc::new(RateStreamService::new(Arc::new(convert_service), Duration::from_secs(60), 4))
+    }
+
+    #[tokio::test]
+    async fn test_subscribers_share_one_poll_per_pair() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let service = service(calls.clone()).await;
+
+        let mut first = service.connect();
+        let mut second = service.connect();
+        first.subscribe(&["USD/MXN".to_string()]).unwrap();
+        second.subscribe(&["usd/mxn".to_string()]).unwrap();
+
+        service.poll().await;
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        for client in [&mut first, &mut second] {
+            match client.next().await {
+                Some(RateStreamMessage::Rate(leg)) => assert_eq!(leg.rate.to_string(), "17.500000000000"),
+                other => panic!("expected a rate, got {:?}", other),
+            }
+        }
+
+        // A late subscriber gets the latest rate without another upstream call.
+        let mut third = service.connect();
+        let (_, latest) = third.subscribe(&["usd/mxn".to_string()]).unwrap();
+        assert_eq!(latest.len(), 1);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        drop(first);
+        drop(second);
+        third.unsubscribe(&["usd/mxn".to_string()]);
+        service.poll().await;
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_pairs_are_rejected() {
+        let service = service(Arc::new(AtomicUsize::new(0))).await;
+
+        assert_eq!(service.normalize_pair(" USDC/MXN ").unwrap(), "usdc/mxn");
+        assert!(service.normalize_pair("usd/xyz").is_err());
+        assert!(service.normalize_pair("usd").is_err());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,224 @@
+use std::sync::Arc;
+use chrono::Utc;
+use sha2::{Digest, Sha256};
+
+use crate::error::AppError;
+use crate::modules::models::reconciliation::{
+    parse_statement, reconcile, ReconciliationItem, ReconciliationReport, ReconciliationRun, ReconciliationStatus,
+    SettlementRow, SettlementStatement,
+};
+use crate::modules::repositories::{
+    bank_transfer_repo::BankTransferRepository, reconciliation_repo::ReconciliationRepository,
+};
+
+/// Runs returned by the listing endpoint.
+const MAX_LISTED_RUNS: i64 = 100;
+
+/// Checks what payout providers report as settled against the transfers we
+/// marked completed. Statements are stored as imported; every run compares
+/// all of a provider's transfers with all of its rows, so a payout settled in
+/// a later file stops showing as missing.
+#[derive(Clone)]
+pub struct ReconciliationService {
+    reconciliation_repo: Arc<ReconciliationRepository>,
+    bank_transfer_repo: Arc<BankTransferRepository>,
+    /// Payout providers statements can be imported for.
+    providers: Vec<String>,
+}
+
+impl ReconciliationService {
+    pub fn new(
+        reconciliation_repo: Arc<ReconciliationRepository>,
+        bank_transfer_repo: Arc<BankTransferRepository>,
+        providers: Vec<String>,
+    ) -> Self {
+        Self {
+            reconciliation_repo,
+            bank_transfer_repo,
+            providers,
+        }
+    }
+
+    /// Stores a settlement CSV and reconciles the provider against it.
+    pub async fn import_statement(
+        &self,
+        provider: &str,
+        filename: Option<&str>,
+        contents: &str,
+    ) -> Result<ReconciliationReport, AppError> {
+        let provider = self.provider(provider)?;
+
+        let checksum = hex::encode(Sha256::digest(contents.as_bytes()));
+        let existing = self.reconciliation_repo.find_statement_by_checksum(&provider, &checksum).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        if let Some(existing) = existing {
+            return Err(AppError::StatementExists(existing.id));
+        }
+
+        let lines = parse_statement(contents).map_err(AppError::Validation)?;
+
+        let statement = SettlementStatement {
+            id: uuid::Uuid::new_v4().to_string(),
+            provider: provider.clone(),
+            filename: filename.map(str::trim).filter(|name| !name.is_empty()).map(str::to_string),
+            checksum,
+            row_count: lines.len() as i64,
+            imported_at: Utc::now(),
+        };
+        let rows: Vec<SettlementRow> = lines
+            .into_iter()
+            .map(|line| SettlementRow {
+                id: uuid::Uuid::new_v4().to_string(),
+                statement_id: statement.id.clone(),
+                provider: provider.clone(),
+                line: line.line,
+                reference: line.reference,
+                amount: line.amount,
+                currency: line.currency,
+                settled_at: line.settled_at,
+            })
+            .collect();
+
+        self.reconciliation_repo.create_statement(&statement, &rows).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        tracing::info!(
+            "Imported {} settlement rows for {} ({})",
+            statement.row_count,
+            provider,
+            statement.filename.as_deref().unwrap_or(&statement.id)
+        );
+
+        self.reconcile(&provider, Some(statement.id)).await
+    }
+
+    pub async fn run(&self, provider: &str) -> Result<ReconciliationReport, AppError> {
+        let provider = self.provider(provider)?;
+        self.reconcile(&provider, None).await
+    }
+
+    pub async fn list_runs(&self, provider: Option<&str>) -> Result<Vec<ReconciliationRun>, AppError> {
+        let provider = provider.map(|p| p.trim().to_lowercase());
+
+        self.reconciliation_repo.find_runs(provider.as_deref(), MAX_LISTED_RUNS).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))
+    }
+
+    pub async fn get_report(&self, run_id: &str) -> Result<ReconciliationReport, AppError> {
+        let run = self.reconciliation_repo.find_run(run_id).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .ok_or_else(|| AppError::ReconciliationRunNotFound(run_id.to_string()))?;
+
+        let items = self.reconciliation_repo.find_items(run_id).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        Ok(ReconciliationReport { run, items })
+    }
+
+    /// The run's items as CSV, for finance to work through.
+    pub async fn report_csv(&self, run_id: &str) -> Result<String, AppError> {
+        let report = self.get_report(run_id).await?;
+
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        let write = |writer: &mut csv::Writer<Vec<u8>>, record: &[&str]| {
+            writer.write_record(record).map_err(|e| AppError::InternalError(e.to_string()))
+        };
+
+        write(
+            &mut writer,
+            &["status", "transfer_id", "reference", "expected_amount", "settled_amount", "currency", "detail"],
+        )?;
+        for item in &report.items {
+            let expected = item.expected_amount.map(|amount| amount.to_string()).unwrap_or_default();
+            let settled = item.settled_amount.map(|amount| amount.to_string()).unwrap_or_default();
+            write(
+                &mut writer,
+                &[
+                    &item.status,
+                    item.transfer_id.as_deref().unwrap_or(""),
+                    item.reference.as_deref().unwrap_or(""),
+                    &expected,
+                    &settled,
+                    item.currency.as_deref().unwrap_or(""),
+                    &item.detail,
+                ],
+            )?;
+        }
+
+        let bytes = writer.into_inner().map_err(|e| AppError::InternalError(e.to_string()))?;
+        String::from_utf8(bytes).map_err(|e| AppError::InternalError(e.to_string()))
+    }
+
+    async fn reconcile(&self, provider: &str, statement_id: Option<String>) -> Result<ReconciliationReport, AppError> {
+        let transfers = self.bank_transfer_repo.find_by_payout_provider(provider).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        let rows = self.reconciliation_repo.find_rows_by_provider(provider).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let findings = reconcile(&transfers, &rows);
+        let count = |status: ReconciliationStatus| findings.iter().filter(|f| f.status == status).count() as i64;
+
+        let run = ReconciliationRun {
+            id: uuid::Uuid::new_v4().to_string(),
+            provider: provider.to_string(),
+            statement_id,
+            matched: count(ReconciliationStatus::Matched),
+            missing: count(ReconciliationStatus::Missing),
+            duplicate: count(ReconciliationStatus::Duplicate),
+            amount_mismatch: count(ReconciliationStatus::AmountMismatch),
+            unmatched: count(ReconciliationStatus::Unmatched),
+            reversed_settled: count(ReconciliationStatus::ReversedSettled),
+            created_at: Utc::now(),
+        };
+        let items: Vec<ReconciliationItem> = findings
+            .into_iter()
+            .map(|finding| ReconciliationItem {
+                id: uuid::Uuid::new_v4().to_string(),
+                run_id: run.id.clone(),
+                status: finding.status.to_string(),
+                transfer_id: finding.transfer_id,
+                reference: finding.reference,
+                expected_amount: finding.expected_amount,
+                settled_amount: finding.settled_amount,
+                currency: finding.currency,
+                detail: finding.detail,
+            })
+            .collect();
+
+        self.reconciliation_repo.create_run(&run, &items).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let problems = run.missing + run.duplicate + run.amount_mismatch + run.unmatched + run.reversed_settled;
+        if problems > 0 {
+            tracing::warn!(
+                "Reconciliation {} for {}: {} matched, {} missing, {} duplicate, {} amount mismatch, {} unmatched, \
+                 {} reversed but settled",
+                run.id,
+                provider,
+                run.matched,
+                run.missing,
+                run.duplicate,
+                run.amount_mismatch,
+                run.unmatched,
+                run.reversed_settled
+            );
+        } else {
+            tracing::info!("Reconciliation {} for {}: all {} transfers matched", run.id, provider, run.matched);
+        }
+
+        // Report order: problems first, as stored.
+        self.get_report(&run.id).await
+    }
+
+    fn provider(&self, provider: &str) -> Result<String, AppError> {
+        let provider = provider.trim().to_lowercase();
+        if !self.providers.contains(&provider) {
+            return Err(AppError::BadRequest(format!(
+                "Unknown payout provider: {} (expected one of {})",
+                provider,
+                self.providers.join(", ")
+            )));
+        }
+        Ok(provider)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,667 @@
+use anyhow::Result;
+use std::sync::Arc;
+use chrono::{Duration, Utc};
+use serde_json::json;
+
+use crate::error::AppError;
+use crate::modules::models::amount::{Amount, FixedPoint, Rounding};
+use crate::modules::models::bank::{BankTransfer, BankTransferRequest, TransferStatus};
+use crate::modules::models::quote::{QuoteAsset, QuoteRequest};
+use crate::modules::models::remittance::{
+    ConversionAction, CreateRemittanceRequest, Remittance, RemittanceResponse, RemittanceStatus, RemittanceStep,
+    SagaStep, TransferOutcome,
+};
+use crate::modules::models::transaction::TransactionType;
+use crate::modules::models::webhook::remittance_event;
+use crate::modules::repositories::{remittance_repo::RemittanceRepository, wallet_repo::WalletRepository};
+use crate::modules::services::{
+    bank_service::BankService, quote_service::QuoteService, stellar_service::StellarService,
+    token_registry::TokenRegistry, webhook_service::WebhookService,
+};
+
+/// Remittances advanced on each worker pass.
+const WORKER_BATCH_SIZE: i64 = 50;
+
+/// An escrowed or converting remittance untouched this long was held for
+/// review or lost its conversion to a restart, and is converted by the
+/// worker instead.
+const STALE_CONVERSION_SECONDS: i64 = 120;
+
+/// Runs "receive USDC, cash out to a bank" as one saga: quote the payout,
+/// escrow the source funds in a bank transfer held out of the payout queue
+/// (and out of the conversion while it is in review), convert the escrow on
+/// the DEX, release the transfer to the payout provider and settle once it is
+/// paid. A failure after the escrow cancels the transfer if it was not paid
+/// out yet, unwinds the conversion and relies on the transfer's failure to
+/// refund the escrow.
+#[derive(Clone)]
+pub struct RemittanceService {
+    remittance_repo: Arc<RemittanceRepository>,
+    wallet_repo: Arc<WalletRepository>,
+    bank_service: Arc<BankService>,
+    quote_service: Arc<QuoteService>,
+    stellar_service: Arc<StellarService>,
+    webhook_service: Arc<WebhookService>,
+    tokens: Arc<TokenRegistry>,
+    /// Registry symbol of the asset remittances are funded from.
+    source_asset: String,
+    /// Fiat currency paid out.
+    currency: String,
+    /// Registry symbol of the Stellar asset the escrow is converted into;
+    /// remittances are disabled without one.
+    dex_asset: Option<String>,
+    max_slippage_bps: u32,
+}
+
+impl RemittanceService {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        remittance_repo: Arc<RemittanceRepository>,
+        wallet_repo: Arc<WalletRepository>,
+        bank_service: Arc<BankService>,
+        quote_service: Arc<QuoteService>,
+        stellar_service: Arc<StellarService>,
+        webhook_service: Arc<WebhookService>,
+        tokens: Arc<TokenRegistry>,
+        source_asset: String,
+        currency: String,
+        dex_asset: Option<String>,
+        max_slippage_bps: u32,
+    ) -> Self {
+        Self {
+            remittance_repo,
+            wallet_repo,
+            bank_service,
+            quote_service,
+            stellar_service,
+            webhook_service,
+            tokens,
+            source_asset,
+            currency: currency.to_uppercase(),
+            dex_asset,
+            max_slippage_bps,
+        }
+    }
+
+    /// Runs the saga up to the payout, which the worker then follows. Errors
+    /// before anything was escrowed are returned as they are; after that the
+    /// remittance is returned with its outcome in the timeline.
+    pub async fn create(
+        &self,
+        request: &CreateRemittanceRequest,
+        client_ip: Option<&str>,
+    ) -> Result<RemittanceResponse, AppError> {
+        let (source, dex) = self.assets()?;
+        let public_key = request.public_key.as_str();
+
+        if request.amount_fiat.is_zero() {
+            return Err(AppError::BadRequest("amount_fiat must be positive".to_string()));
+        }
+
+        let wallet = self.wallet_repo.find_by_pubkey(public_key).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .ok_or_else(|| AppError::WalletNotFound(public_key.to_string()))?;
+
+        let quote = self.quote_service
+            .create_quote(&QuoteRequest {
+                sell_asset: source.to_string(),
+                buy_asset: QuoteAsset::Fiat(self.currency.clone()).to_string(),
+                sell_amount: None,
+                buy_amount: Some(request.amount_fiat.rescale(Rounding::Down)?),
+                context: "sep31".to_string(),
+                expire_after: None,
+            }, public_key)
+            .await?;
+
+        let now = Utc::now();
+        let mut remittance = Remittance {
+            id: uuid::Uuid::new_v4().to_string(),
+            wallet_id: wallet.id.clone(),
+            public_key: public_key.to_string(),
+            amount_fiat: request.amount_fiat,
+            currency: self.currency.clone(),
+            source_asset: source.symbol().to_uppercase(),
+            source_amount: Some(quote.sell_amount),
+            dex_asset: dex.symbol().to_uppercase(),
+            dex_amount: None,
+            quote_id: Some(quote.id.clone()),
+            transfer_id: None,
+            status: RemittanceStatus::Quoted.to_string(),
+            failure_reason: None,
+            created_at: now,
+            updated_at: now,
+            completed_at: None,
+        };
+        let quoted = Self::step(
+            &remittance,
+            SagaStep::Quote,
+            true,
+            format!(
+                "Quote {}: {} {} for {} {}, valid until {}",
+                quote.id,
+                quote.sell_amount,
+                remittance.source_asset,
+                remittance.amount_fiat,
+                remittance.currency,
+                quote.expires_at
+            ),
+            None,
+        );
+
+        self.remittance_repo.create(&remittance, std::slice::from_ref(&quoted)).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        self.publish(&remittance, &[quoted]).await;
+
+        let transfer_request = BankTransferRequest {
+            public_key: public_key.to_string(),
+            amount_fiat: request.amount_fiat,
+            currency: self.currency.clone(),
+            bank_account: request.bank_account.clone(),
+            beneficiary_id: request.beneficiary_id.clone(),
+            beneficiary_name: request.beneficiary_name.clone(),
+            routing_number: None,
+            bank_country: None,
+            quote_id: Some(quote.id.clone()),
+            source_asset: Some(self.source_asset.clone()),
+        };
+
+        let (transfer_id, status, details) = match self.bank_service
+            .create_remittance_transfer(&transfer_request, client_ip)
+            .await
+        {
+            Ok(created) => created,
+            Err(e) => {
+                // Nothing was escrowed, so there is nothing to compensate.
+                let failed = Self::step(&remittance, SagaStep::Escrow, false, e.to_string(), None);
+                remittance.status = RemittanceStatus::Failed.to_string();
+                remittance.failure_reason = Some(e.to_string());
+                if let Err(save_error) = self.advance(&mut remittance, RemittanceStatus::Quoted, vec![failed]).await {
+                    tracing::error!("Failed to record escrow failure of remittance {}: {}", remittance.id, save_error);
+                }
+                return Err(e);
+            }
+        };
+
+        let escrowed = Self::step(
+            &remittance,
+            SagaStep::Escrow,
+            true,
+            format!("Escrowed {} {} in bank transfer {} ({})", quote.sell_amount, remittance.source_asset, transfer_id, status),
+            details.map(|details| details.escrow_tx_hash),
+        );
+        remittance.transfer_id = Some(transfer_id);
+        remittance.status = RemittanceStatus::Escrowed.to_string();
+        if self.advance(&mut remittance, RemittanceStatus::Quoted, vec![escrowed]).await? {
+            self.convert(&mut remittance).await?;
+        }
+
+        self.get(&remittance.id).await
+    }
+
+    pub async fn get(&self, id: &str) -> Result<RemittanceResponse, AppError> {
+        let remittance = self.find(id).await?;
+
+        let transfer_status = match &remittance.transfer_id {
+            Some(transfer_id) => Some(self.bank_service.get_transfer(transfer_id).await?.0.status),
+            None => None,
+        };
+
+        let timeline = self.remittance_repo.find_steps(id).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        Ok(RemittanceResponse { remittance, transfer_status, timeline })
+    }
+
+    /// [`RemittanceService::get`] for the wallet that sent the remittance.
+    /// Other wallets get the same not-found as for an unknown id.
+    pub async fn get_own(&self, id: &str, public_key: &str) -> Result<RemittanceResponse, AppError> {
+        let found = self.get(id).await?;
+        if found.remittance.public_key != public_key {
+            return Err(AppError::RemittanceNotFound(id.to_string()));
+        }
+        Ok(found)
+    }
+
+    pub async fn list(&self, public_key: &str) -> Result<Vec<Remittance>, AppError> {
+        let wallet = self.wallet_repo.find_by_pubkey(public_key).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .ok_or_else(|| AppError::WalletNotFound(public_key.to_string()))?;
+
+        self.remittance_repo.find_by_wallet(&wallet.id).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))
+    }
+
+    pub fn spawn_worker(self: Arc<Self>, every: std::time::Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(every);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = self.process_remittances().await {
+                    tracing::warn!("Remittance worker failed: {}", e);
+                }
+            }
+        })
+    }
+
+    /// Moves every in-flight remittance on from its transfer's status.
+    pub async fn process_remittances(&self) -> Result<()> {
+        let stale_before = Utc::now() - Duration::seconds(STALE_CONVERSION_SECONDS);
+
+        for mut remittance in self.remittance_repo.find_in_flight(stale_before, WORKER_BATCH_SIZE).await? {
+            let result = match remittance.remittance_status() {
+                Some(RemittanceStatus::Escrowed | RemittanceStatus::Converting) => self.convert(&mut remittance).await,
+                Some(RemittanceStatus::Converted | RemittanceStatus::PayingOut | RemittanceStatus::Completed) => {
+                    self.follow_transfer(&mut remittance).await
+                }
+                Some(RemittanceStatus::Compensating) => self.compensate(&mut remittance).await,
+                _ => Ok(()),
+            };
+            if let Err(e) = result {
+                tracing::warn!("Failed to advance remittance {}: {}", remittance.id, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Claims the remittance and converts its escrow into the DEX asset, then
+    /// releases the transfer to the payout queue. A transfer still in review
+    /// is left for the worker to convert once approved. Without a good enough
+    /// path, or once the transfer failed or left `converting` on its own, the
+    /// remittance is compensated.
+    async fn convert(&self, remittance: &mut Remittance) -> Result<(), AppError> {
+        let (source, dex) = self.assets()?;
+        let transfer_id = Self::transfer_id(remittance)?;
+        let source_amount = remittance.source_amount.ok_or_else(|| {
+            AppError::InternalError(format!("Remittance {} has no escrowed amount", remittance.id))
+        })?;
+        let from = remittance.remittance_status().ok_or_else(|| {
+            AppError::InternalError(format!("Remittance {} has unknown status {}", remittance.id, remittance.status))
+        })?;
+
+        let (transfer, transitions, _) = self.bank_service.get_transfer(&transfer_id).await?;
+        let Some(transfer_status) = transfer.transfer_status() else {
+            return Ok(());
+        };
+        match ConversionAction::of(transfer_status) {
+            ConversionAction::Wait => return Ok(()),
+            ConversionAction::Convert => {}
+            ConversionAction::Abort => {
+                let reason = match TransferOutcome::of(transfer_status) {
+                    TransferOutcome::Failed => format!(
+                        "Bank transfer {}: {}",
+                        transfer_status,
+                        transitions.last().map(|t| t.reason.as_str()).unwrap_or_default()
+                    ),
+                    _ => format!("Bank transfer {} left converting before the DEX conversion", transfer_status),
+                };
+                return self.abort_conversion(remittance, from, reason).await;
+            }
+        }
+
+        let now = Utc::now();
+        let stale_before = now - Duration::seconds(STALE_CONVERSION_SECONDS);
+        let claimed = self.remittance_repo.claim_conversion(&remittance.id, now, stale_before).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        if !claimed {
+            tracing::info!("Conversion of remittance {} is already under way", remittance.id);
+            return Ok(());
+        }
+        remittance.status = RemittanceStatus::Converting.to_string();
+        remittance.updated_at = now;
+        if from != RemittanceStatus::Converting {
+            self.publish(remittance, &[]).await;
+        }
+
+        let target: Amount = remittance.amount_fiat.rescale(Rounding::Down)?;
+        let min = target
+            .scale_by((10_000 - self.max_slippage_bps) as i128, 10_000, Rounding::Up)?
+            .round_to(dex.decimals(), Rounding::Up)?;
+
+        match self.swap(&source, source_amount, &dex, min).await {
+            Ok((received, tx_hash)) => {
+                let converted = RemittanceStep {
+                    simulated: true,
+                    ..Self::step(
+                        remittance,
+                        SagaStep::Convert,
+                        true,
+                        format!(
+                            "Converted {} {} into {} {} on the DEX (minimum {}); simulated, not submitted",
+                            source_amount, remittance.source_asset, received, remittance.dex_asset, min
+                        ),
+                        Some(tx_hash),
+                    )
+                };
+                remittance.dex_amount = Some(received);
+                remittance.status = RemittanceStatus::Converted.to_string();
+                if self.advance(remittance, RemittanceStatus::Converting, vec![converted]).await? {
+                    if let Err(e) = self.release_transfer(&transfer_id).await {
+                        tracing::warn!("Transfer {} of remittance {} not released yet: {}", transfer_id, remittance.id, e);
+                    }
+                }
+                Ok(())
+            }
+            Err(reason) => self.abort_conversion(remittance, RemittanceStatus::Converting, reason).await,
+        }
+    }
+
+    /// Records why the conversion cannot go on and compensates the remittance.
+    async fn abort_conversion(
+        &self,
+        remittance: &mut Remittance,
+        from: RemittanceStatus,
+        reason: String,
+    ) -> Result<(), AppError> {
+        let failed = Self::step(remittance, SagaStep::Convert, false, reason.clone(), None);
+        remittance.status = RemittanceStatus::Compensating.to_string();
+        remittance.failure_reason = Some(reason);
+        if !self.advance(remittance, from, vec![failed]).await? {
+            return Ok(());
+        }
+        self.compensate(remittance).await
+    }
+
+    /// Moves a converted transfer held for its remittance into the payout queue.
+    async fn release_transfer(&self, transfer_id: &str) -> Result<(), AppError> {
+        let (transfer, _, _) = self.bank_service.get_transfer(transfer_id).await?;
+        if transfer.transfer_status() == Some(TransferStatus::Converting) {
+            self.bank_service
+                .transition(&transfer, TransferStatus::Pending, "Remittance escrow converted on the DEX")
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn follow_transfer(&self, remittance: &mut Remittance) -> Result<(), AppError> {
+        let from = remittance.remittance_status().ok_or_else(|| {
+            AppError::InternalError(format!("Remittance {} has unknown status {}", remittance.id, remittance.status))
+        })?;
+        let transfer_id = Self::transfer_id(remittance)?;
+        let (transfer, transitions, _) = self.bank_service.get_transfer(&transfer_id).await?;
+        let Some(status) = transfer.transfer_status() else {
+            return Ok(());
+        };
+
+        match TransferOutcome::of(status) {
+            TransferOutcome::Waiting => {
+                if status == TransferStatus::Converting {
+                    self.release_transfer(&transfer_id).await?;
+                }
+                Ok(())
+            }
+            TransferOutcome::PayingOut => {
+                if from != RemittanceStatus::Converted {
+                    return Ok(());
+                }
+                let submitted = Self::step(remittance, SagaStep::Payout, true, Self::describe_payout(&transfer), None);
+                remittance.status = RemittanceStatus::PayingOut.to_string();
+                self.advance(remittance, from, vec![submitted]).await?;
+                Ok(())
+            }
+            TransferOutcome::Settled => {
+                if from == RemittanceStatus::Completed {
+                    return Ok(());
+                }
+                let mut steps = Vec::new();
+                if from == RemittanceStatus::Converted {
+                    steps.push(Self::step(remittance, SagaStep::Payout, true, Self::describe_payout(&transfer), None));
+                }
+                steps.push(Self::step(
+                    remittance,
+                    SagaStep::Settle,
+                    true,
+                    format!(
+                        "{} paid {} {} to {}; escrow settled",
+                        transfer.payout_provider.as_deref().unwrap_or("The provider"),
+                        transfer.amount_fiat,
+                        remittance.currency,
+                        transfer.bank_account_masked
+                    ),
+                    None,
+                ));
+                remittance.status = RemittanceStatus::Completed.to_string();
+                remittance.completed_at = transfer.completed_at.or(Some(Utc::now()));
+                self.advance(remittance, from, steps).await?;
+                Ok(())
+            }
+            TransferOutcome::Failed => {
+                let reason = transitions.last().map(|t| t.reason.clone()).unwrap_or_default();
+                let reason = format!("Bank transfer {}: {}", status, reason);
+                let failed = Self::step(remittance, SagaStep::Payout, false, reason.clone(), None);
+                remittance.status = RemittanceStatus::Compensating.to_string();
+                remittance.failure_reason = Some(reason);
+                if self.advance(remittance, from, vec![failed]).await? {
+                    self.compensate(remittance).await?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Cancels the transfer if it was not paid out yet, swaps a conversion
+    /// back and records the escrow refund. A transfer already with the
+    /// provider is waited for; one paid out completes the remittance for an
+    /// operator to look at. A swap that cannot be made now is retried by the
+    /// worker.
+    async fn compensate(&self, remittance: &mut Remittance) -> Result<(), AppError> {
+        let (source, dex) = self.assets()?;
+        let transfer_id = Self::transfer_id(remittance)?;
+
+        let (transfer, _, mut transactions) = self.bank_service.get_transfer(&transfer_id).await?;
+        match transfer.transfer_status().map(TransferOutcome::of) {
+            Some(TransferOutcome::Failed) => {}
+            Some(TransferOutcome::Waiting) => {
+                let reason = remittance.failure_reason.as_deref().unwrap_or("Remittance is compensating");
+                if let Err(e) = self.bank_service.cancel_remittance_transfer(&transfer_id, reason).await {
+                    tracing::warn!("Transfer {} of remittance {} not cancelled: {}", transfer_id, remittance.id, e);
+                    return Ok(());
+                }
+                (_, _, transactions) = self.bank_service.get_transfer(&transfer_id).await?;
+            }
+            Some(TransferOutcome::PayingOut) => {
+                tracing::info!(
+                    "Remittance {} waits for the payout of transfer {} ({}) before compensating",
+                    remittance.id,
+                    transfer_id,
+                    transfer.status
+                );
+                return Ok(());
+            }
+            Some(TransferOutcome::Settled) => return self.complete_paid_out(remittance, &transfer).await,
+            None => return Ok(()),
+        }
+
+        let steps = self.remittance_repo.find_steps(&remittance.id).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        let unwound = steps.iter().any(|step| step.step == SagaStep::UnwindConvert.as_str() && step.status == "completed");
+
+        let mut new_steps = Vec::new();
+        if let (Some(dex_amount), false) = (remittance.dex_amount, unwound) {
+            // Whatever the swap back returns is kept; the wallet is refunded
+            // its full escrow either way.
+            match self.swap(&dex, dex_amount, &source, Amount::ZERO).await {
+                Ok((received, tx_hash)) => new_steps.push(RemittanceStep {
+                    simulated: true,
+                    ..Self::step(
+                        remittance,
+                        SagaStep::UnwindConvert,
+                        true,
+                        format!(
+                            "Swapped {} {} back into {} {}; simulated, not submitted",
+                            dex_amount, remittance.dex_asset, received, remittance.source_asset
+                        ),
+                        Some(tx_hash),
+                    )
+                }),
+                Err(reason) => {
+                    tracing::warn!("Conversion of remittance {} not unwound yet: {}", remittance.id, reason);
+                    return Ok(());
+                }
+            }
+        }
+
+        let release = transactions
+            .iter()
+            .find(|tx| tx.tx_type == TransactionType::Release.to_string());
+        new_steps.push(match release {
+            Some(release) => Self::step(
+                remittance,
+                SagaStep::Refund,
+                true,
+                format!("Refunded {} {} to the wallet", release.amount, release.asset),
+                Some(release.tx_hash.clone()),
+            ),
+            None => Self::step(
+                remittance,
+                SagaStep::Refund,
+                false,
+                format!("No escrow refund recorded for transfer {}; needs an operator", transfer_id),
+                None,
+            ),
+        });
+
+        remittance.status = RemittanceStatus::Failed.to_string();
+        self.advance(remittance, RemittanceStatus::Compensating, new_steps).await?;
+        Ok(())
+    }
+
+    /// The transfer was paid out before it could be cancelled, so the
+    /// beneficiary has the money and nothing can be unwound: the remittance
+    /// completes, keeping its failure reason for an operator to reconcile
+    /// the conversion.
+    async fn complete_paid_out(&self, remittance: &mut Remittance, transfer: &BankTransfer) -> Result<(), AppError> {
+        let reason = remittance.failure_reason.clone().unwrap_or_default();
+        tracing::error!(
+            "Remittance {} was compensating ({}) but transfer {} was paid out; needs an operator",
+            remittance.id,
+            reason,
+            transfer.id
+        );
+
+        let settled = Self::step(
+            remittance,
+            SagaStep::Settle,
+            true,
+            format!(
+                "{} paid {} {} to {} while the remittance was compensating ({}); needs an operator",
+                transfer.payout_provider.as_deref().unwrap_or("The provider"),
+                transfer.amount_fiat,
+                remittance.currency,
+                transfer.bank_account_masked,
+                reason
+            ),
+            None,
+        );
+        remittance.status = RemittanceStatus::Completed.to_string();
+        remittance.completed_at = transfer.completed_at.or(Some(Utc::now()));
+        self.advance(remittance, RemittanceStatus::Compensating, vec![settled]).await?;
+        Ok(())
+    }
+
+    /// Best strict send path from `amount` of `from` into `to`, refused below
+    /// `min`. As with wallet conversions, submission is simulated: the hash
+    /// returned does not exist on the network and the steps recording the
+    /// swap are marked `simulated`.
+    async fn swap(&self, from: &QuoteAsset, amount: Amount, to: &QuoteAsset, min: Amount) -> Result<(Amount, String), String> {
+        let route = self.stellar_service
+            .find_strict_send_path(from, amount, to)
+            .await
+            .map_err(|e| format!("DEX path finding failed: {}", e))?
+            .ok_or_else(|| format!("No path from {} to {} on the Stellar DEX", from, to))?;
+
+        if route.destination_amount < min {
+            return Err(format!(
+                "Best path delivers {} {}, below the minimum of {}",
+                route.destination_amount,
+                to.symbol().to_uppercase(),
+                min
+            ));
+        }
+
+        Ok((route.destination_amount, format!("tx_{}", uuid::Uuid::new_v4())))
+    }
+
+    /// Stores the remittance's new state if it is still `from`, then tells
+    /// webhook subscribers. Returns false when something else moved it first.
+    async fn advance(
+        &self,
+        remittance: &mut Remittance,
+        from: RemittanceStatus,
+        steps: Vec<RemittanceStep>,
+    ) -> Result<bool, AppError> {
+        remittance.updated_at = Utc::now();
+
+        let saved = self.remittance_repo.save(remittance, from, &steps).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        if !saved {
+            tracing::info!("Remittance {} was no longer {}", remittance.id, from);
+            return Ok(false);
+        }
+
+        tracing::info!("Remittance {} {} -> {}", remittance.id, from, remittance.status);
+        if remittance.status != from.as_str() {
+            self.publish(remittance, &steps).await;
+        }
+        Ok(true)
+    }
+
+    async fn publish(&self, remittance: &Remittance, steps: &[RemittanceStep]) {
+        let Some(status) = remittance.remittance_status() else { return };
+        self.webhook_service
+            .publish(&remittance_event(status), json!({ "remittance": remittance, "steps": steps }))
+            .await;
+    }
+
+    async fn find(&self, id: &str) -> Result<Remittance, AppError> {
+        self.remittance_repo.find_by_id(id).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .ok_or_else(|| AppError::RemittanceNotFound(id.to_string()))
+    }
+
+    fn assets(&self) -> Result<(QuoteAsset, QuoteAsset), AppError> {
+        let dex_asset = self.dex_asset.as_deref().ok_or_else(|| {
+            AppError::NotImplemented("Remittances need REMITTANCES_DEX_ASSET".to_string())
+        })?;
+
+        let resolve = |symbol: &str| {
+            let token = self.tokens.resolve_stellar(symbol)?;
+            QuoteAsset::from_token(token).ok_or_else(|| AppError::UnsupportedAsset(symbol.to_string()))
+        };
+
+        Ok((resolve(&self.source_asset)?, resolve(dex_asset)?))
+    }
+
+    fn transfer_id(remittance: &Remittance) -> Result<String, AppError> {
+        remittance.transfer_id.clone().ok_or_else(|| {
+            AppError::InternalError(format!("Remittance {} has no bank transfer", remittance.id))
+        })
+    }
+
+    fn describe_payout(transfer: &BankTransfer) -> String {
+        format!(
+            "Submitted to {} (reference {})",
+            transfer.payout_provider.as_deref().unwrap_or("the payout provider"),
+            transfer.provider_reference.as_deref().unwrap_or("pending")
+        )
+    }
+
+    fn step(
+        remittance: &Remittance,
+        step: SagaStep,
+        completed: bool,
+        detail: String,
+        tx_hash: Option<String>,
+    ) -> RemittanceStep {
+        RemittanceStep {
+            id: uuid::Uuid::new_v4().to_string(),
+            remittance_id: remittance.id.clone(),
+            step: step.to_string(),
+            status: if completed { "completed" } else { "failed" }.to_string(),
+            detail,
+            tx_hash,
+            simulated: false,
+            created_at: Utc::now(),
+        }
+    }
+}
\ No newline at end of file
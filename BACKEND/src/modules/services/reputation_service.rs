@@ -2,27 +2,39 @@ use anyhow::{Context, Result};
 use std::sync::Arc;
 use chrono::Utc;
 
+use crate::modules::models::amount::{Amount, FixedPoint};
+use crate::modules::models::bank::TransferStatus;
 use crate::modules::models::reputation::{Reputation, ReputationDetails, ReputationResponse};
-use crate::modules::repositories::transaction_repo::TransactionRepository;
+use crate::modules::repositories::{
+    bank_transfer_repo::BankTransferRepository,
+    transaction_repo::TransactionRepository,
+};
 use crate::modules::services::stellar_service::StellarService;
 
 #[derive(Clone)]
 pub struct ReputationService {
     transaction_repo: Arc<TransactionRepository>,
+    bank_transfer_repo: Arc<BankTransferRepository>,
     stellar_service: Arc<StellarService>,
     threshold: u8,
+    /// Points taken off per reversed bank transfer.
+    reversal_penalty: u8,
 }
 
 impl ReputationService {
     pub fn new(
         transaction_repo: Arc<TransactionRepository>,
+        bank_transfer_repo: Arc<BankTransferRepository>,
         stellar_service: Arc<StellarService>,
         threshold: u8,
+        reversal_penalty: u8,
     ) -> Self {
         Self {
             transaction_repo,
+            bank_transfer_repo,
             stellar_service,
             threshold,
+            reversal_penalty,
         }
     }
 
@@ -33,24 +45,28 @@ impl ReputationService {
     pub async fn calculate_reputation(&self, public_key: &str, wallet_id: Option<&str>) -> Result<Reputation> {
         let account_age_days = self.get_account_age(public_key).await?;
         
-        let (tx_count, total_volume) = if let Some(wid) = wallet_id {
+        let (tx_count, total_volume, reversed_transfers) = if let Some(wid) = wallet_id {
             let count = self.transaction_repo.count_by_wallet_id(wid).await? as u32;
             let volume = self.transaction_repo.sum_volume_by_wallet_id(wid).await?;
-            (count, volume)
+            let reversed = self.bank_transfer_repo
+                .count_by_wallet_and_status(wid, TransferStatus::Reversed)
+                .await? as u32;
+            (count, volume, reversed)
         } else {
-            (0, 0.0)
+            (0, Amount::ZERO, 0)
         };
 
-        let trust_score = self.calculate_trust_score(tx_count, total_volume, account_age_days);
+        let trust_score = trust_score(tx_count, &total_volume, account_age_days, reversed_transfers, self.reversal_penalty);
         let level = self.get_trust_level(trust_score);
 
         tracing::debug!(
-            "Reputation calculated for {}: score={}, tx_count={}, volume={}, age_days={}",
+            "Reputation calculated for {}: score={}, tx_count={}, volume={}, age_days={}, reversed={}",
             public_key,
             trust_score,
             tx_count,
             total_volume,
-            account_age_days
+            account_age_days,
+            reversed_transfers
         );
 
         Ok(Reputation {
@@ -59,6 +75,7 @@ impl ReputationService {
             level,
             tx_count,
             total_volume,
+            reversed_transfers,
             last_calculated: Utc::now(),
         })
     }
@@ -90,30 +107,13 @@ impl ReputationService {
             details: ReputationDetails {
                 tx_count: reputation.tx_count,
                 total_volume: reputation.total_volume,
+                reversed_transfers: reputation.reversed_transfers,
                 account_age_days,
                 last_activity,
             },
         })
     }
 
-    fn calculate_trust_score(&self, tx_count: u32, total_volume: f64, age_days: i64) -> u8 {
-        let base_score: f64 = 10.0;
-        
-        let tx_bonus = (tx_count as f64 * 2.0).min(40.0);
-        
-        let volume_bonus = if total_volume > 0.0 {
-            (total_volume.log10() * 10.0).min(30.0)
-        } else {
-            0.0
-        };
-        
-        let age_bonus = (age_days as f64 / 10.0).min(20.0);
-        
-        let total_score = base_score + tx_bonus + volume_bonus + age_bonus;
-        
-        total_score.min(100.0) as u8
-    }
-
     fn get_trust_level(&self, score: u8) -> String {
         match score {
             0..=30 => "Unverified".to_string(),
@@ -133,4 +133,42 @@ impl ReputationService {
         
         Ok(30)
     }
+}
+
+fn trust_score(tx_count: u32, total_volume: &Amount, age_days: i64, reversals: u32, reversal_penalty: u8) -> u8 {
+    let base_score: f64 = 10.0;
+    
+    let tx_bonus = (tx_count as f64 * 2.0).min(40.0);
+    
+    // The score is a heuristic, so f64 is fine here.
+    let volume_bonus = if !total_volume.is_zero() {
+        (total_volume.to_f64().log10() * 10.0).min(30.0)
+    } else {
+        0.0
+    };
+    
+    let age_bonus = (age_days as f64 / 10.0).min(20.0);
+    
+    // Disputed transfers outweigh the activity they added.
+    let reversal_penalty = reversals as f64 * reversal_penalty as f64;
+    
+    let total_score = base_score + tx_bonus + volume_bonus + age_bonus - reversal_penalty;
+    
+    total_score.clamp(0.0, 100.0) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reversals_lower_the_trust_score() {
+        let volume: Amount = "1000".parse().unwrap();
+        let clean = trust_score(10, &volume, 100, 0, 15);
+
+        assert_eq!(trust_score(10, &volume, 100, 1, 15), clean - 15);
+        assert_eq!(trust_score(10, &volume, 100, 2, 15), clean - 30);
+        assert_eq!(trust_score(10, &volume, 100, 20, 15), 0);
+        assert_eq!(trust_score(10, &volume, 100, 3, 0), clean);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,319 @@
+use std::sync::Arc;
+use chrono::{Duration, Utc};
+
+use crate::error::AppError;
+use crate::modules::models::amount::Amount;
+use crate::modules::models::bank::TransferStatus;
+use crate::modules::models::review::{ReviewNote, ReviewStatus, ReviewSummary, TransferReview};
+use crate::modules::repositories::review_repo::{DecideOutcome, ReviewRepository, TransferMove};
+
+/// Manual review queue for bank transfers held in the `review` status. The
+/// final decision moves the transfer in the same write; `BankService` then
+/// handles its escrow and notifications.
+#[derive(Clone)]
+pub struct ReviewService {
+    review_repo: Arc<ReviewRepository>,
+    sla: Duration,
+    /// Reviews for at least this much need a second, different approver.
+    second_approval_usd: Option<Amount>,
+}
+
+impl ReviewService {
+    pub fn new(review_repo: Arc<ReviewRepository>, sla_minutes: i64, second_approval_usd: Option<Amount>) -> Self {
+        Self {
+            review_repo,
+            sla: Duration::minutes(sla_minutes),
+            second_approval_usd,
+        }
+    }
+
+    /// The review a transfer is held for. It is stored together with the
+    /// transfer, see `BankTransferRepository::create_with_escrow`.
+    pub fn prepare(&self, transfer_id: &str, reason: &str, amount_usd: Option<Amount>) -> TransferReview {
+        // An amount that could not be valued gets the stricter treatment.
+        let requires_second_approval = match (self.second_approval_usd, amount_usd) {
+            (Some(threshold), Some(amount)) => amount >= threshold,
+            (Some(_), None) => true,
+            (None, _) => false,
+        };
+
+        let now = Utc::now();
+        TransferReview {
+            id: uuid::Uuid::new_v4().to_string(),
+            transfer_id: transfer_id.to_string(),
+            status: ReviewStatus::Open.to_string(),
+            reason: reason.to_string(),
+            amount_usd,
+            requires_second_approval,
+            assigned_to: None,
+            claimed_at: None,
+            first_approved_by: None,
+            first_approved_at: None,
+            decided_by: None,
+            decided_at: None,
+            sla_due_at: now + self.sla,
+            created_at: now,
+        }
+    }
+
+    /// Reviews in `status`, or every undecided one, the closest to their SLA first.
+    pub async fn list(&self, status: Option<&str>) -> Result<Vec<ReviewSummary>, AppError> {
+        let statuses = match status {
+            Some(status) => vec![ReviewStatus::parse(status)
+                .ok_or_else(|| AppError::BadRequest(format!("Unknown review status: {}", status)))?],
+            None => vec![ReviewStatus::Open, ReviewStatus::AwaitingSecondApproval],
+        };
+
+        let mut reviews = Vec::new();
+        for status in statuses {
+            reviews.extend(
+                self.review_repo.find_by_status(status).await
+                    .map_err(|e| AppError::DatabaseError(e.to_string()))?,
+            );
+        }
+        reviews.sort_by_key(|review| review.sla_due_at);
+
+        Ok(reviews.into_iter().map(Self::summarize).collect())
+    }
+
+    pub async fn get(&self, id: &str) -> Result<(TransferReview, Vec<ReviewNote>), AppError> {
+        let review = self.find(id).await?;
+        let notes = self.review_repo.find_notes(id).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        Ok((review, notes))
+    }
+
+    pub fn summarize(review: TransferReview) -> ReviewSummary {
+        let overdue = review.is_overdue(Utc::now());
+        ReviewSummary { review, overdue }
+    }
+
+    pub async fn claim(&self, id: &str, reviewer: &str) -> Result<TransferReview, AppError> {
+        let reviewer = Self::reviewer(reviewer)?;
+        let review = self.find(id).await?;
+
+        let claimed = self.review_repo.claim(id, reviewer, Utc::now()).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        if !claimed {
+            let reason = if review.review_status().is_some_and(|status| status.is_decided()) {
+                format!("Review {} is already {}", id, review.status)
+            } else if review.first_approved_by.as_deref() == Some(reviewer) {
+                format!("Review {} needs a second approver other than {}", id, reviewer)
+            } else {
+                format!(
+                    "Review {} is claimed by {}",
+                    id,
+                    review.assigned_to.as_deref().unwrap_or("another reviewer")
+                )
+            };
+            return Err(AppError::ReviewConflict(reason));
+        }
+
+        self.find(id).await
+    }
+
+    /// Approves a review claimed by `reviewer`. A review that needs two
+    /// approvals goes back to the queue after the first one; the final
+    /// approval moves the transfer from `review` to `approved_to`.
+    pub async fn approve(
+        &self,
+        id: &str,
+        reviewer: &str,
+        note: Option<&str>,
+        approved_to: TransferStatus,
+    ) -> Result<TransferReview, AppError> {
+        let reviewer = Self::reviewer(reviewer)?;
+        let review = self.find_claimed(id, reviewer).await?;
+        let now = Utc::now();
+
+        let outcome = match review.review_status() {
+            Some(ReviewStatus::Open) if review.requires_second_approval => {
+                self.review_repo.approve_first(id, reviewer, note, now).await
+                    .map(|approved| if approved { DecideOutcome::Decided } else { DecideOutcome::ReviewChanged })
+            }
+            Some(status @ (ReviewStatus::Open | ReviewStatus::AwaitingSecondApproval)) => {
+                let reason = format!("Approved in review by {}", reviewer);
+                let transfer = TransferMove {
+                    transfer_id: &review.transfer_id,
+                    from: TransferStatus::Review,
+                    to: approved_to,
+                    reason: &reason,
+                };
+                self.review_repo.decide(id, status, ReviewStatus::Approved, reviewer, note, &transfer, now).await
+            }
+            _ => return Err(AppError::ReviewConflict(format!("Review {} is already {}", id, review.status))),
+        }
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        Self::check_decided(outcome, &review, "approved")?;
+        self.find(id).await
+    }
+
+    /// Rejects a review claimed by `reviewer`, rejecting its transfer with it.
+    pub async fn reject(&self, id: &str, reviewer: &str, note: Option<&str>) -> Result<TransferReview, AppError> {
+        let reviewer = Self::reviewer(reviewer)?;
+        let note = note
+            .map(str::trim)
+            .filter(|note| !note.is_empty())
+            .ok_or_else(|| AppError::BadRequest("A note is required to reject a review".to_string()))?;
+        let review = self.find_claimed(id, reviewer).await?;
+
+        let status = review
+            .review_status()
+            .filter(|status| !status.is_decided())
+            .ok_or_else(|| AppError::ReviewConflict(format!("Review {} is already {}", id, review.status)))?;
+
+        let reason = Self::rejection_reason(reviewer, note);
+        let transfer = TransferMove {
+            transfer_id: &review.transfer_id,
+            from: TransferStatus::Review,
+            to: TransferStatus::Rejected,
+            reason: &reason,
+        };
+        let outcome = self.review_repo
+            .decide(id, status, ReviewStatus::Rejected, reviewer, Some(note), &transfer, Utc::now())
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        Self::check_decided(outcome, &review, "rejected")?;
+        self.find(id).await
+    }
+
+    /// What a transfer rejected in review records as its reason.
+    pub fn rejection_reason(reviewer: &str, note: &str) -> String {
+        format!("Rejected in review by {}: {}", reviewer, note.trim())
+    }
+
+    fn check_decided(outcome: DecideOutcome, review: &TransferReview, action: &str) -> Result<(), AppError> {
+        match outcome {
+            DecideOutcome::Decided => Ok(()),
+            DecideOutcome::ReviewChanged => Err(AppError::ReviewConflict(format!(
+                "Review {} changed before it could be {}",
+                review.id, action
+            ))),
+            DecideOutcome::TransferChanged => Err(AppError::InvalidTransition(format!(
+                "Transfer {} is no longer held for review",
+                review.transfer_id
+            ))),
+        }
+    }
+
+    /// Takes the review of a cancelled transfer out of the queue.
+    pub async fn cancel_for_transfer(&self, transfer_id: &str, actor: &str, note: Option<&str>) -> Result<(), AppError> {
+        let cancelled = self.review_repo.cancel_for_transfer(transfer_id, actor, note, Utc::now()).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        if cancelled {
+            tracing::info!("Review of bank transfer {} cancelled by {}", transfer_id, actor);
+        }
+        Ok(())
+    }
+
+    async fn find(&self, id: &str) -> Result<TransferReview, AppError> {
+        self.review_repo.find_by_id(id).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .ok_or_else(|| AppError::ReviewNotFound(id.to_string()))
+    }
+
+    async fn find_claimed(&self, id: &str, reviewer: &str) -> Result<TransferReview, AppError> {
+        let review = self.find(id).await?;
+        if review.assigned_to.as_deref() != Some(reviewer) {
+            return Err(AppError::ReviewConflict(format!("Review {} must be claimed by {} first", id, reviewer)));
+        }
+        Ok(review)
+    }
+
+    fn reviewer(reviewer: &str) -> Result<&str, AppError> {
+        let reviewer = reviewer.trim();
+        if reviewer.is_empty() {
+            return Err(AppError::BadRequest("reviewer is required".to_string()));
+        }
+        Ok(reviewer)
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::repositories::review_repo::tests::pool_with_transfer;
+
+    async fn review_service(second_approval_usd: Option<&str>) -> (ReviewService, sqlx::SqlitePool) {
+        let pool = pool_with_transfer("review").await;
+        let repo = Arc::new(ReviewRepository::new(pool.clone()));
+        let threshold = second_approval_usd.map(|usd| usd.parse::<Amount>().unwrap());
+        (ReviewService::new(repo, 60, threshold), pool)
+    }
+
+    async fn open(service: &ReviewService, pool: &sqlx::SqlitePool, amount_usd: Option<&str>) -> TransferReview {
+        let review = service.prepare("t1", "Flagged by risk rules", amount_usd.map(|usd| usd.parse().unwrap()));
+        ReviewRepository::create_with(&mut pool.acquire().await.unwrap(), &review).await.unwrap();
+        review
+    }
+
+    async fn transfer_status(pool: &sqlx::SqlitePool) -> String {
+        sqlx::query_scalar("SELECT status FROM bank_transfers WHERE id = 't1'")
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_prepare_requires_second_approval_above_the_threshold() {
+        let (service, _) = review_service(Some("1000")).await;
+        let second = |usd: Option<&str>| {
+            service
+                .prepare("t1", "reason", usd.map(|usd| usd.parse().unwrap()))
+                .requires_second_approval
+        };
+        assert!(!second(Some("999.99")));
+        assert!(second(Some("1000")));
+        // An amount that could not be valued needs both approvals.
+        assert!(second(None));
+
+        let (service, _) = review_service(None).await;
+        assert!(!service.prepare("t1", "reason", None).requires_second_approval);
+    }
+
+    #[tokio::test]
+    async fn test_second_approval_needs_another_reviewer() {
+        let (service, pool) = review_service(Some("1000")).await;
+        let review = open(&service, &pool, Some("5000")).await;
+
+        service.claim(&review.id, "alice").await.unwrap();
+        let first = service.approve(&review.id, "alice", None, TransferStatus::Pending).await.unwrap();
+        assert_eq!(first.status, "awaiting_second_approval");
+        assert_eq!(transfer_status(&pool).await, "review");
+
+        assert!(matches!(service.claim(&review.id, "alice").await, Err(AppError::ReviewConflict(_))));
+        service.claim(&review.id, "bob").await.unwrap();
+        let approved = service.approve(&review.id, "bob", None, TransferStatus::Pending).await.unwrap();
+        assert_eq!(approved.status, "approved");
+        assert_eq!(transfer_status(&pool).await, "pending");
+    }
+
+    #[tokio::test]
+    async fn test_reject_requires_a_claim_and_a_note() {
+        let (service, pool) = review_service(None).await;
+        let review = open(&service, &pool, Some("10")).await;
+
+        assert!(matches!(service.reject(&review.id, "alice", Some("no")).await, Err(AppError::ReviewConflict(_))));
+        service.claim(&review.id, "alice").await.unwrap();
+        assert!(matches!(service.reject(&review.id, "alice", Some(" ")).await, Err(AppError::BadRequest(_))));
+
+        let rejected = service.reject(&review.id, "alice", Some("mule account")).await.unwrap();
+        assert_eq!(rejected.status, "rejected");
+        assert_eq!(transfer_status(&pool).await, "rejected");
+    }
+
+    #[tokio::test]
+    async fn test_decision_fails_once_the_transfer_left_review() {
+        let (service, pool) = review_service(None).await;
+        let review = open(&service, &pool, Some("10")).await;
+        service.claim(&review.id, "alice").await.unwrap();
+        sqlx::query("UPDATE bank_transfers SET status = 'cancelled'").execute(&pool).await.unwrap();
+
+        let result = service.approve(&review.id, "alice", None, TransferStatus::Pending).await;
+        assert!(matches!(result, Err(AppError::InvalidTransition(_))));
+        assert_eq!(service.get(&review.id).await.unwrap().0.status, "open");
+    }
+}
\ No newline at end of file
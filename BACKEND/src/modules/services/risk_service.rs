@@ -0,0 +1,289 @@
+use anyhow::{Context, Result};
+use std::sync::Arc;
+use chrono::{Duration, Utc};
+use sha2::{Digest, Sha256};
+
+use crate::error::AppError;
+use crate::modules::models::amount::{Amount, FixedPoint, Rate, Rounding};
+use crate::modules::models::risk::{
+    self, FiredRule, RiskAction, RiskAssessment, RiskDecision, RiskRule, RiskRuleKind, RiskRuleSet,
+    RiskSubject, VelocityScope,
+};
+use crate::modules::repositories::{risk_repo::RiskRepository, transaction_repo::TransactionRepository};
+use crate::modules::services::convert_service::ConvertService;
+use crate::utils::bank_account::BankAccount;
+
+/// Evaluates the configured velocity and fraud rules for bank transfers and
+/// sends, and stores every decision with the rules that fired.
+#[derive(Clone)]
+pub struct RiskService {
+    risk_repo: Arc<RiskRepository>,
+    transaction_repo: Arc<TransactionRepository>,
+    convert_service: Arc<ConvertService>,
+    rules: Vec<RiskRule>,
+}
+
+impl RiskService {
+    pub fn new(
+        risk_repo: Arc<RiskRepository>,
+        transaction_repo: Arc<TransactionRepository>,
+        convert_service: Arc<ConvertService>,
+        rules: Vec<RiskRule>,
+    ) -> Result<Self> {
+        Self::check_rules(&rules)?;
+
+        Ok(Self {
+            risk_repo,
+            transaction_repo,
+            convert_service,
+            rules,
+        })
+    }
+
+    pub fn load_rules(path: &str) -> Result<Vec<RiskRule>> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read risk rules file {}", path))?;
+
+        let set: RiskRuleSet = serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse risk rules file {}", path))?;
+
+        Ok(set.rules)
+    }
+
+    pub fn default_rules() -> Vec<RiskRule> {
+        let rule = |id: &str, action, kind| RiskRule {
+            id: id.to_string(),
+            operations: Vec::new(),
+            action,
+            kind,
+        };
+        let velocity = |scope, window_seconds, max_count| RiskRuleKind::Velocity {
+            scope,
+            window_seconds,
+            max_count: Some(max_count),
+            max_amount_usd: None,
+        };
+
+        vec![
+            rule("wallet_burst", RiskAction::Block, velocity(VelocityScope::Wallet, 60, 3)),
+            rule("wallet_hourly", RiskAction::Review, velocity(VelocityScope::Wallet, 3600, 10)),
+            rule("bank_account_hourly", RiskAction::Review, velocity(VelocityScope::BankAccount, 3600, 5)),
+            rule("ip_burst", RiskAction::Block, velocity(VelocityScope::Ip, 60, 10)),
+            rule(
+                "amount_spike",
+                RiskAction::Review,
+                RiskRuleKind::AmountSpike {
+                    multiplier: Rate::ONE.scale_by(5, 1, Rounding::Down).unwrap_or(Rate::ONE),
+                    min_history: 3,
+                    lookback_days: 30,
+                },
+            ),
+            rule("fresh_funds", RiskAction::Review, RiskRuleKind::FundingCooldown { cooldown_seconds: 3600 }),
+        ]
+    }
+
+    fn check_rules(rules: &[RiskRule]) -> Result<()> {
+        let mut ids = std::collections::HashSet::new();
+        for rule in rules {
+            if !ids.insert(rule.id.as_str()) {
+                anyhow::bail!("Duplicate risk rule id: {}", rule.id);
+            }
+            let valid = match &rule.kind {
+                RiskRuleKind::Velocity { window_seconds, max_count, max_amount_usd, .. } => {
+                    *window_seconds > 0 && (max_count.is_some() || max_amount_usd.is_some())
+                }
+                RiskRuleKind::AmountSpike { lookback_days, .. } => *lookback_days > 0,
+                RiskRuleKind::FundingCooldown { cooldown_seconds } => *cooldown_seconds > 0,
+            };
+            if !valid {
+                anyhow::bail!("Risk rule {} needs a positive window and at least one limit", rule.id);
+            }
+        }
+        Ok(())
+    }
+
+    /// Stable key for counting transfers to the same bank account without
+    /// storing the account number.
+    pub fn bank_account_hash(account: &BankAccount) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(account.country.as_bytes());
+        hasher.update(b":");
+        hasher.update(account.routing_number.as_deref().unwrap_or_default().as_bytes());
+        hasher.update(b":");
+        hasher.update(account.account.as_bytes());
+        hex::encode(hasher.finalize())
+    }
+
+    /// USD value of `amount` of a registered asset; `None` if it cannot be priced.
+    pub async fn usd_value(&self, symbol: &str, amount: Amount) -> Option<Amount> {
+        let leg = match self.convert_service.leg(symbol, "usd").await {
+            Ok(leg) => leg,
+            Err(e) => {
+                tracing::warn!("Risk rules could not price {} in USD: {}", symbol, e);
+                return None;
+            }
+        };
+        leg.rate.apply(amount, Rounding::Down).ok()
+    }
+
+    pub async fn assess(&self, subject: &RiskSubject) -> Result<RiskAssessment, AppError> {
+        let mut fired = Vec::new();
+        for rule in self.rules.iter().filter(|rule| rule.applies_to(subject.operation)) {
+            let reason = self.evaluate(rule, subject).await
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+            if let Some(reason) = reason {
+                fired.push(FiredRule {
+                    rule_id: rule.id.clone(),
+                    action: rule.action,
+                    reason,
+                });
+            }
+        }
+
+        let action = risk::combine(&fired);
+        let decision = RiskDecision {
+            id: uuid::Uuid::new_v4().to_string(),
+            operation: subject.operation.as_str().to_string(),
+            wallet_id: subject.wallet_id.clone(),
+            public_key: subject.public_key.clone(),
+            reference: subject.reference.clone(),
+            amount_usd: subject.amount_usd,
+            bank_account_hash: subject.bank_account_hash.clone(),
+            ip_address: subject.ip_address.clone(),
+            action: action.to_string(),
+            rules: serde_json::to_string(&fired).map_err(|e| AppError::InternalError(e.to_string()))?,
+            created_at: Utc::now(),
+        };
+
+        self.risk_repo.create(&decision).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let assessment = RiskAssessment {
+            decision_id: decision.id,
+            action,
+            fired,
+        };
+
+        if action != RiskAction::Allow {
+            tracing::warn!(
+                "Risk decision {} for {} by {}: {} ({})",
+                assessment.decision_id,
+                subject.operation.as_str(),
+                subject.public_key,
+                action,
+                assessment.rule_ids()
+            );
+        }
+
+        Ok(assessment)
+    }
+
+    /// Links a decision to what it allowed once that exists, e.g. a send's hash.
+    pub async fn set_reference(&self, decision_id: &str, reference: &str) {
+        if let Err(e) = self.risk_repo.set_reference(decision_id, reference).await {
+            tracing::warn!("Failed to link risk decision {} to {}: {}", decision_id, reference, e);
+        }
+    }
+
+    /// Why `rule` fires for `subject`, or `None` if it does not.
+    async fn evaluate(&self, rule: &RiskRule, subject: &RiskSubject) -> Result<Option<String>> {
+        let now = Utc::now();
+
+        match &rule.kind {
+            RiskRuleKind::Velocity { scope, window_seconds, max_count, max_amount_usd } => {
+                let key = match scope {
+                    VelocityScope::Wallet => Some(&subject.wallet_id),
+                    VelocityScope::BankAccount => subject.bank_account_hash.as_ref(),
+                    VelocityScope::Ip => subject.ip_address.as_ref(),
+                };
+                let Some(key) = key else {
+                    return Ok(None);
+                };
+
+                let history = self
+                    .risk_repo
+                    .history(*scope, key, now - Duration::seconds(*window_seconds))
+                    .await?
+                    .into_iter()
+                    .filter(|entry| Self::counts_for(rule, &entry.operation))
+                    .collect::<Vec<_>>();
+
+                let count = history.len() + 1;
+                if let Some(max_count) = max_count.filter(|max_count| count > *max_count) {
+                    return Ok(Some(format!(
+                        "{} operations in {}s (max {})",
+                        count, window_seconds, max_count
+                    )));
+                }
+
+                if let (Some(max_amount), Some(amount)) = (max_amount_usd, subject.amount_usd) {
+                    let total = history
+                        .iter()
+                        .filter_map(|entry| entry.amount_usd)
+                        .try_fold(amount, |total, value| total.checked_add(value))?;
+                    if total > *max_amount {
+                        return Ok(Some(format!(
+                            "{} USD in {}s (max {} USD)",
+                            total.format_with(2),
+                            window_seconds,
+                            max_amount.format_with(2)
+                        )));
+                    }
+                }
+
+                Ok(None)
+            }
+            RiskRuleKind::AmountSpike { multiplier, min_history, lookback_days } => {
+                let Some(amount) = subject.amount_usd else {
+                    return Ok(None);
+                };
+
+                let history = self
+                    .risk_repo
+                    .history(VelocityScope::Wallet, &subject.wallet_id, now - Duration::days(*lookback_days))
+                    .await?
+                    .into_iter()
+                    .filter(|entry| Self::counts_for(rule, &entry.operation))
+                    .filter_map(|entry| entry.amount_usd)
+                    .collect::<Vec<_>>();
+
+                if history.len() < *min_history {
+                    return Ok(None);
+                }
+
+                Ok(risk::spike_average(amount, &history, *multiplier).map(|average| {
+                    format!(
+                        "{} USD is more than {}x the {} USD average of the last {} operations",
+                        amount.format_with(2),
+                        multiplier.format_with(2),
+                        average.format_with(2),
+                        history.len()
+                    )
+                }))
+            }
+            RiskRuleKind::FundingCooldown { cooldown_seconds } => {
+                let Some(funded_at) = self.transaction_repo.last_funded_at(&subject.wallet_id).await? else {
+                    return Ok(None);
+                };
+
+                let elapsed = now - funded_at;
+                if elapsed >= Duration::seconds(*cooldown_seconds) {
+                    return Ok(None);
+                }
+
+                let outgoing = self.transaction_repo.count_outgoing_since(&subject.wallet_id, funded_at).await?;
+                Ok((outgoing == 0).then(|| {
+                    format!(
+                        "First outgoing operation {}s after funding (cooldown {}s)",
+                        elapsed.num_seconds(),
+                        cooldown_seconds
+                    )
+                }))
+            }
+        }
+    }
+
+    fn counts_for(rule: &RiskRule, operation: &str) -> bool {
+        rule.operations.is_empty() || rule.operations.iter().any(|op| op.as_str() == operation)
+    }
+}
\ No newline at end of file
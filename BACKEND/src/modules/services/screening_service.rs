@@ -0,0 +1,241 @@
+use anyhow::{Context, Result};
+use std::sync::{Arc, RwLock};
+use chrono::Utc;
+use sha2::{Digest, Sha256};
+
+use crate::error::AppError;
+use crate::modules::models::risk::RiskAction;
+use crate::modules::models::screening::{
+    ScreeningList, ScreeningMatch, ScreeningOutcome, ScreeningResult, ScreeningSubject,
+};
+use crate::modules::repositories::screening_repo::ScreeningRepository;
+use crate::utils::encryption::FieldCipher;
+use crate::utils::watchlist::{self, MatchType, Watchlist};
+
+/// A list file and how to parse it.
+#[derive(Debug, Clone)]
+pub struct ListSource {
+    /// `sdn` for OFAC SDN-style CSV or XML, `internal` for our JSON blocklist.
+    pub source: String,
+    pub path: String,
+}
+
+struct LoadedList {
+    record: ScreeningList,
+    list: Arc<Watchlist>,
+}
+
+/// Screens the parties of transfers and sends against the configured
+/// sanctions and internal lists, and stores every list version and result.
+pub struct ScreeningService {
+    screening_repo: Arc<ScreeningRepository>,
+    /// Encrypts the screened names and accounts stored with each result.
+    cipher: FieldCipher,
+    sources: Vec<ListSource>,
+    lists: RwLock<Vec<LoadedList>>,
+    fuzzy_threshold: f64,
+    exact_match_action: RiskAction,
+    fuzzy_match_action: RiskAction,
+}
+
+impl ScreeningService {
+    pub fn new(
+        screening_repo: Arc<ScreeningRepository>,
+        cipher: FieldCipher,
+        sources: Vec<ListSource>,
+        fuzzy_threshold: f64,
+        exact_match_action: RiskAction,
+        fuzzy_match_action: RiskAction,
+    ) -> Self {
+        Self {
+            screening_repo,
+            cipher,
+            sources,
+            lists: RwLock::new(Vec::new()),
+            fuzzy_threshold,
+            exact_match_action,
+            fuzzy_match_action,
+        }
+    }
+
+    /// Loads every list whose file changed since it was last loaded. A list
+    /// that fails to load keeps its previous version.
+    pub async fn reload(&self) -> Result<()> {
+        for source in &self.sources {
+            let contents = tokio::fs::read(&source.path)
+                .await
+                .with_context(|| format!("Failed to read {} list {}", source.source, source.path))?;
+            let version = hex::encode(Sha256::digest(&contents));
+
+            if self.current_version(&source.source).as_deref() == Some(version.as_str()) {
+                continue;
+            }
+
+            let parsed = {
+                let source = source.clone();
+                tokio::task::spawn_blocking(move || Self::parse(&source, &contents))
+                    .await
+                    .context("List parser panicked")??
+            };
+
+            if parsed.is_empty() {
+                tracing::warn!("{} list {} has no entries", source.source, source.path);
+            }
+
+            let record = match self.screening_repo.find_list(&source.source, &version).await? {
+                Some(record) => record,
+                None => {
+                    let record = ScreeningList {
+                        id: uuid::Uuid::new_v4().to_string(),
+                        source: source.source.clone(),
+                        path: source.path.clone(),
+                        version,
+                        entry_count: parsed.len() as i64,
+                        loaded_at: Utc::now(),
+                    };
+                    self.screening_repo.create_list(&record).await?;
+                    record
+                }
+            };
+
+            tracing::info!(
+                "Loaded {} list {} ({} entries, version {})",
+                record.source,
+                record.path,
+                record.entry_count,
+                &record.version[..12]
+            );
+
+            let mut lists = self.lists.write().unwrap_or_else(|e| e.into_inner());
+            lists.retain(|loaded| loaded.record.source != source.source);
+            lists.push(LoadedList { record, list: Arc::new(parsed) });
+        }
+
+        Ok(())
+    }
+
+    pub fn spawn_reloader(self: Arc<Self>, every: std::time::Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(every);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                if let Err(e) = self.reload().await {
+                    tracing::warn!("Screening list reload failed: {:#}", e);
+                }
+            }
+        })
+    }
+
+    /// Checks `subject` against every loaded list and stores the result. With
+    /// no lists configured nothing is screened or stored.
+    pub async fn screen(&self, subject: &ScreeningSubject) -> Result<ScreeningOutcome, AppError> {
+        let lists = {
+            let lists = self.lists.read().unwrap_or_else(|e| e.into_inner());
+            lists
+                .iter()
+                .map(|loaded| (loaded.record.clone(), loaded.list.clone()))
+                .collect::<Vec<_>>()
+        };
+
+        if lists.is_empty() {
+            return Ok(ScreeningOutcome {
+                result_id: None,
+                action: RiskAction::Allow,
+                matches: Vec::new(),
+            });
+        }
+
+        let mut matches = Vec::new();
+        for (record, list) in &lists {
+            let hits = list.screen(
+                &subject.names,
+                &subject.stellar_addresses,
+                &subject.bank_accounts,
+                self.fuzzy_threshold,
+            );
+            matches.extend(hits.into_iter().map(|hit| ScreeningMatch {
+                list_id: record.id.clone(),
+                source: record.source.clone(),
+                action: match hit.match_type {
+                    MatchType::FuzzyName => self.fuzzy_match_action,
+                    _ => self.exact_match_action,
+                },
+                entry_id: hit.entry_id,
+                entry_name: hit.entry_name,
+                match_type: hit.match_type,
+                score: hit.score,
+            }));
+        }
+
+        let action = matches.iter().map(|hit| hit.action).max().unwrap_or(RiskAction::Allow);
+        let list_ids = lists.iter().map(|(record, _)| record.id.as_str()).collect::<Vec<_>>();
+
+        let result = ScreeningResult {
+            id: uuid::Uuid::new_v4().to_string(),
+            operation: subject.operation.as_str().to_string(),
+            wallet_id: subject.wallet_id.clone(),
+            reference: subject.reference.clone(),
+            action: action.to_string(),
+            subject: serde_json::to_string(subject)
+                .map_err(anyhow::Error::from)
+                .and_then(|json| self.cipher.encrypt(&json))
+                .map_err(|e| AppError::InternalError(e.to_string()))?,
+            matches: serde_json::to_string(&matches).map_err(|e| AppError::InternalError(e.to_string()))?,
+            list_ids: serde_json::to_string(&list_ids).map_err(|e| AppError::InternalError(e.to_string()))?,
+            created_at: Utc::now(),
+        };
+
+        self.screening_repo.create_result(&result).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let outcome = ScreeningOutcome {
+            result_id: Some(result.id),
+            action,
+            matches,
+        };
+
+        if action != RiskAction::Allow {
+            tracing::warn!(
+                "Screening result {} for {} by wallet {}: {} ({})",
+                outcome.result_id.as_deref().unwrap_or_default(),
+                subject.operation.as_str(),
+                subject.wallet_id,
+                action,
+                outcome.describe()
+            );
+        }
+
+        Ok(outcome)
+    }
+
+    /// Links a result to what it allowed once that exists, e.g. a send's hash.
+    pub async fn set_reference(&self, result_id: &str, reference: &str) {
+        if let Err(e) = self.screening_repo.set_reference(result_id, reference).await {
+            tracing::warn!("Failed to link screening result {} to {}: {}", result_id, reference, e);
+        }
+    }
+
+    fn current_version(&self, source: &str) -> Option<String> {
+        let lists = self.lists.read().unwrap_or_else(|e| e.into_inner());
+        lists
+            .iter()
+            .find(|loaded| loaded.record.source == source)
+            .map(|loaded| loaded.record.version.clone())
+    }
+
+    fn parse(source: &ListSource, contents: &[u8]) -> Result<Watchlist> {
+        let contents = std::str::from_utf8(contents)
+            .with_context(|| format!("{} is not valid UTF-8", source.path))?;
+
+        let entries = match source.source.as_str() {
+            "sdn" if source.path.to_lowercase().ends_with(".xml") => watchlist::parse_sdn_xml(contents),
+            "sdn" => watchlist::parse_sdn_csv(contents),
+            "internal" => watchlist::parse_internal(contents),
+            other => anyhow::bail!("Unknown screening list source: {}", other),
+        }
+        .with_context(|| format!("Failed to parse {} list {}", source.source, source.path))?;
+
+        Ok(Watchlist::new(entries))
+    }
+}
\ No newline at end of file
@@ -2,6 +2,18 @@ use anyhow::{Context, Result};
 use reqwest::Client;
 use serde_json::Value;
 
+use crate::modules::models::amount::Amount;
+use crate::modules::models::quote::QuoteAsset;
+use crate::modules::models::wallet::Balance;
+
+/// Best route found by Horizon for a strict send path payment.
+#[derive(Debug, Clone)]
+pub struct StrictSendPath {
+    pub destination_amount: Amount,
+    /// Intermediate assets as `native` or `CODE:ISSUER`.
+    pub path: Vec<String>,
+}
+
 #[derive(Clone)]
 pub struct StellarService {
     horizon_url: String,
@@ -48,7 +60,7 @@ impl StellarService {
         Ok(tx_hash)
     }
 
-    pub async fn get_account_balance(&self, public_key: &str) -> Result<Vec<(String, String)>> {
+    pub async fn get_account_balance(&self, public_key: &str) -> Result<Vec<Balance>> {
         let url = format!("{}/accounts/{}", self.horizon_url, public_key);
         
         let response = self.client
@@ -81,9 +93,18 @@ impl StellarService {
                 balance["asset_code"].as_str().unwrap_or("UNKNOWN").to_string()
             };
             
-            let amount = balance["balance"].as_str().unwrap_or("0").to_string();
+            let amount: Amount = balance["balance"]
+                .as_str()
+                .unwrap_or("0")
+                .parse()
+                .with_context(|| format!("Invalid {} balance from Horizon", asset_code))?;
             
-            result.push((asset_code, amount));
+            result.push(Balance {
+                asset_code,
+                balance: amount,
+                asset_issuer: balance["asset_issuer"].as_str().map(str::to_string),
+                decimals: None,
+            });
         }
 
         Ok(result)
@@ -154,6 +175,98 @@ impl StellarService {
         Ok(result)
     }
 
+    /// Asks Horizon for strict send paths from `source_amount` of `source_asset`
+    /// to `destination_asset` and returns the one that delivers the most.
+    pub async fn find_strict_send_path(
+        &self,
+        source_asset: &QuoteAsset,
+        source_amount: Amount,
+        destination_asset: &QuoteAsset,
+    ) -> Result<Option<StrictSendPath>> {
+        let mut params = Self::asset_params("source", source_asset)?;
+        params.push(("source_amount".to_string(), source_amount.to_string()));
+        params.push(("destination_assets".to_string(), Self::canonical_asset(destination_asset)?));
+
+        let url = format!("{}/paths/strict-send", self.horizon_url);
+
+        let response = self.client
+            .get(&url)
+            .query(&params)
+            .send()
+            .await
+            .context("Failed to fetch strict send paths from Horizon")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!(
+                "Horizon path finding failed with status {}: {}",
+                status,
+                text
+            ));
+        }
+
+        let json: Value = response.json().await.context("Failed to parse paths response")?;
+
+        let records = json["_embedded"]["records"]
+            .as_array()
+            .context("No records in paths response")?;
+
+        let mut best: Option<StrictSendPath> = None;
+        for record in records {
+            let destination_amount: Amount = record["destination_amount"]
+                .as_str()
+                .context("No destination_amount in path record")?
+                .parse()
+                .context("Invalid destination_amount in path record")?;
+
+            if best.as_ref().is_some_and(|b| b.destination_amount >= destination_amount) {
+                continue;
+            }
+
+            let path = record["path"]
+                .as_array()
+                .map(|hops| hops.iter().map(Self::asset_from_json).collect())
+                .unwrap_or_default();
+
+            best = Some(StrictSendPath { destination_amount, path });
+        }
+
+        Ok(best)
+    }
+
+    fn asset_params(prefix: &str, asset: &QuoteAsset) -> Result<Vec<(String, String)>> {
+        match asset {
+            QuoteAsset::Stellar { issuer: None, .. } => {
+                Ok(vec![(format!("{}_asset_type", prefix), "native".to_string())])
+            }
+            QuoteAsset::Stellar { code, issuer: Some(issuer) } => {
+                let asset_type = if code.len() <= 4 { "credit_alphanum4" } else { "credit_alphanum12" };
+                Ok(vec![
+                    (format!("{}_asset_type", prefix), asset_type.to_string()),
+                    (format!("{}_asset_code", prefix), code.clone()),
+                    (format!("{}_asset_issuer", prefix), issuer.clone()),
+                ])
+            }
+            QuoteAsset::Fiat(code) => Err(anyhow::anyhow!("{} is not a Stellar asset", code)),
+        }
+    }
+
+    fn canonical_asset(asset: &QuoteAsset) -> Result<String> {
+        match asset {
+            QuoteAsset::Stellar { issuer: None, .. } => Ok("native".to_string()),
+            QuoteAsset::Stellar { code, issuer: Some(issuer) } => Ok(format!("{}:{}", code, issuer)),
+            QuoteAsset::Fiat(code) => Err(anyhow::anyhow!("{} is not a Stellar asset", code)),
+        }
+    }
+
+    fn asset_from_json(asset: &Value) -> String {
+        match (asset["asset_code"].as_str(), asset["asset_issuer"].as_str()) {
+            (Some(code), Some(issuer)) => format!("{}:{}", code, issuer),
+            _ => "native".to_string(),
+        }
+    }
+
     pub async fn check_account_exists(&self, public_key: &str) -> Result<bool> {
         let url = format!("{}/accounts/{}", self.horizon_url, public_key);
         
@@ -0,0 +1,101 @@
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+
+use crate::error::AppError;
+use crate::modules::models::token::Token;
+
+/// Tokens the service knows how to price and move, loaded from configuration.
+#[derive(Debug, Clone)]
+pub struct TokenRegistry {
+    tokens: Vec<Token>,
+}
+
+impl TokenRegistry {
+    pub fn new(tokens: Vec<Token>) -> Result<Self> {
+        let mut seen = HashSet::new();
+        let mut normalized = Vec::with_capacity(tokens.len());
+
+        for mut token in tokens {
+            token.symbol = token.symbol.trim().to_lowercase();
+            token.stellar_code = token.stellar_code.map(|c| c.trim().to_uppercase());
+
+            if token.symbol.is_empty() {
+                return Err(anyhow::anyhow!("Token registry entry without symbol"));
+            }
+            if !seen.insert(token.symbol.clone()) {
+                return Err(anyhow::anyhow!("Duplicate token symbol in registry: {}", token.symbol));
+            }
+            if token.stellar_issuer.is_some() && token.stellar_code.is_none() {
+                return Err(anyhow::anyhow!("Token {} has a Stellar issuer but no code", token.symbol));
+            }
+
+            normalized.push(token);
+        }
+
+        Ok(Self { tokens: normalized })
+    }
+
+    pub fn from_file(path: &str) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read token registry file {}", path))?;
+
+        let tokens: Vec<Token> = serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse token registry file {}", path))?;
+
+        Self::new(tokens)
+    }
+
+    pub fn default_tokens(usdc_issuer: &str) -> Vec<Token> {
+        let token = |symbol: &str, coingecko_id: &str, stellar: Option<(&str, Option<&str>)>, decimals| Token {
+            symbol: symbol.to_string(),
+            coingecko_id: Some(coingecko_id.to_string()),
+            stellar_code: stellar.map(|(code, _)| code.to_string()),
+            stellar_issuer: stellar.and_then(|(_, issuer)| issuer.map(str::to_string)),
+            decimals,
+            enabled: true,
+        };
+
+        vec![
+            token("xlm", "stellar", Some(("XLM", None)), 7),
+            token("usdc", "usd-coin", Some(("USDC", Some(usdc_issuer))), 2),
+            token("eth", "ethereum", None, 8),
+            token("btc", "bitcoin", None, 8),
+        ]
+    }
+
+    /// Looks a symbol up regardless of whether it is enabled.
+    pub fn get(&self, symbol: &str) -> Option<&Token> {
+        self.tokens.iter().find(|t| t.symbol.eq_ignore_ascii_case(symbol.trim()))
+    }
+
+    pub fn resolve(&self, symbol: &str) -> Result<&Token, AppError> {
+        let token = self
+            .get(symbol)
+            .ok_or_else(|| AppError::UnsupportedAsset(symbol.to_string()))?;
+
+        if !token.enabled {
+            return Err(AppError::UnsupportedAsset(format!("{} is disabled", token.symbol)));
+        }
+
+        Ok(token)
+    }
+
+    /// Like [`TokenRegistry::resolve`], but the token must exist on Stellar.
+    pub fn resolve_stellar(&self, symbol: &str) -> Result<&Token, AppError> {
+        let token = self.resolve(symbol)?;
+
+        if token.stellar_code.is_none() {
+            return Err(AppError::UnsupportedAsset(format!("{} is not a Stellar asset", token.symbol)));
+        }
+
+        Ok(token)
+    }
+
+    pub fn find_stellar(&self, code: &str, issuer: Option<&str>) -> Option<&Token> {
+        self.tokens.iter().find(|t| t.matches_stellar(code, issuer))
+    }
+
+    pub fn coingecko_id(&self, symbol: &str) -> Option<&str> {
+        self.get(symbol).and_then(|t| t.coingecko_id.as_deref())
+    }
+}
\ No newline at end of file
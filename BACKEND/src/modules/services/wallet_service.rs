@@ -4,9 +4,16 @@ use ed25519_dalek::{Keypair, PublicKey, SecretKey, Signer};
 use rand::rngs::OsRng;
 use sha2::{Sha256, Digest};
 
+use crate::error::AppError;
 use crate::modules::models::{
-    wallet::{GenerateWalletResponse, Wallet},
+    amount::{Amount, FixedPoint, Rate, Rounding},
+    quote::QuoteAsset,
+    risk::{RiskAction, RiskOperation, RiskSubject},
+    screening::ScreeningSubject,
+    token::Token,
+    wallet::{Balance, GenerateWalletResponse, Wallet, WalletConvertResponse},
     transaction::{Transaction, TransactionStatus, TransactionType},
+    webhook::{EVENT_TRANSACTION_CONFIRMED, EVENT_WALLET_FUNDED},
 };
 use crate::modules::repositories::{
     wallet_repo::WalletRepository,
@@ -14,29 +21,111 @@ use crate::modules::repositories::{
 };
 use crate::modules::services::{
     aa_service::AaService,
+    quote_service::QuoteService,
+    risk_service::RiskService,
+    screening_service::ScreeningService,
     stellar_service::StellarService,
+    token_registry::TokenRegistry,
+    webhook_service::WebhookService,
 };
 
+/// Friendbot funds new testnet accounts with 10,000 XLM.
+const FRIENDBOT_AMOUNT: Amount = Amount::from_stroops(100_000_000_000);
+
+/// Fails early when the on-chain balance minus what the wallet's transfers
+/// have committed in escrow does not cover `required`. Returns the on-chain
+/// balance.
+pub async fn check_available(
+    stellar_service: &StellarService,
+    transaction_repo: &TransactionRepository,
+    wallet: &Wallet,
+    asset: &QuoteAsset,
+    required: Amount,
+) -> Result<Amount, AppError> {
+    let QuoteAsset::Stellar { code, issuer } = asset else {
+        return Err(AppError::BadRequest(format!("{} is not a Stellar asset", asset)));
+    };
+
+    let balances = stellar_service.get_account_balance(&wallet.public_key).await
+        .map_err(|e| AppError::StellarNetworkError(e.to_string()))?;
+    let on_chain = balances
+        .iter()
+        .find(|b| b.asset_code.eq_ignore_ascii_case(code) && b.asset_issuer == *issuer)
+        .map(|b| b.balance)
+        .unwrap_or(Amount::ZERO);
+
+    let committed = transaction_repo.sum_committed(&wallet.id, &code.to_uppercase(), issuer.as_deref()).await
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+    check_spendable(on_chain, committed, required, asset)?;
+    Ok(on_chain)
+}
+
+fn check_spendable(on_chain: Amount, committed: Amount, required: Amount, asset: &QuoteAsset) -> Result<(), AppError> {
+    if on_chain < committed.checked_add(required)? {
+        let available = if on_chain > committed { on_chain.checked_sub(committed)? } else { Amount::ZERO };
+        return Err(AppError::InsufficientBalance {
+            required: format!("{} {}", required, asset),
+            available: format!("{} {}", available, asset),
+        });
+    }
+    Ok(())
+}
+
+/// Wallet conversions are never submitted to the network; they are only
+/// recorded when the deployment opted into simulating them.
+fn check_conversion_submission(simulate: bool) -> Result<(), AppError> {
+    if simulate {
+        Ok(())
+    } else {
+        Err(AppError::NotImplemented(
+            "Submitting wallet conversions to the Stellar network is not implemented".to_string(),
+        ))
+    }
+}
+
 #[derive(Clone)]
 pub struct WalletService {
     wallet_repo: Arc<WalletRepository>,
     transaction_repo: Arc<TransactionRepository>,
     aa_service: Arc<AaService>,
     stellar_service: Arc<StellarService>,
+    quote_service: Arc<QuoteService>,
+    risk_service: Arc<RiskService>,
+    screening_service: Arc<ScreeningService>,
+    webhook_service: Arc<WebhookService>,
+    tokens: Arc<TokenRegistry>,
+    max_slippage_bps: u32,
+    simulate_conversions: bool,
 }
 
 impl WalletService {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         wallet_repo: Arc<WalletRepository>,
         transaction_repo: Arc<TransactionRepository>,
         aa_service: Arc<AaService>,
         stellar_service: Arc<StellarService>,
+        quote_service: Arc<QuoteService>,
+        risk_service: Arc<RiskService>,
+        screening_service: Arc<ScreeningService>,
+        webhook_service: Arc<WebhookService>,
+        tokens: Arc<TokenRegistry>,
+        max_slippage_bps: u32,
+        simulate_conversions: bool,
     ) -> Self {
         Self {
             wallet_repo,
             transaction_repo,
             aa_service,
             stellar_service,
+            quote_service,
+            risk_service,
+            screening_service,
+            webhook_service,
+            tokens,
+            max_slippage_bps,
+            simulate_conversions,
         }
     }
 
@@ -87,8 +176,16 @@ impl WalletService {
             tx_type: TransactionType::Receive.to_string(),
             from_address: Some("Friendbot".to_string()),
             to_address: Some(public_key.to_string()),
-            amount: "10000.0000000".to_string(),
+            amount: FRIENDBOT_AMOUNT,
             asset: "XLM".to_string(),
+            asset_issuer: None,
+            dest_amount: None,
+            dest_asset: None,
+            dest_asset_issuer: None,
+            fee_amount: None,
+            fee_asset: None,
+            simulated: false,
+            bank_transfer_id: None,
             status: TransactionStatus::Completed.to_string(),
             created_at: chrono::Utc::now(),
         };
@@ -96,16 +193,24 @@ impl WalletService {
         self.transaction_repo.create(&transaction).await
             .context("Failed to save transaction to database")?;
 
+        self.publish_transaction(EVENT_WALLET_FUNDED, &transaction).await;
+
         Ok(tx_hash)
     }
 
-    pub async fn get_balance(&self, public_key: &str) -> Result<(Vec<(String, String)>, Vec<String>)> {
+    pub async fn get_balance(&self, public_key: &str) -> Result<(Vec<Balance>, Vec<String>)> {
         let wallet = self.wallet_repo.find_by_pubkey(public_key).await?
             .context("Wallet not found")?;
 
-        let balances = self.stellar_service.get_account_balance(public_key).await
+        let mut balances = self.stellar_service.get_account_balance(public_key).await
             .context("Failed to fetch balance from Stellar")?;
 
+        for balance in &mut balances {
+            balance.decimals = self.tokens
+                .find_stellar(&balance.asset_code, balance.asset_issuer.as_deref())
+                .map(|token| token.decimals);
+        }
+
         let recent_txs = self.transaction_repo.find_by_wallet_id(&wallet.id, 10).await?
             .into_iter()
             .map(|tx| tx.tx_hash)
@@ -118,15 +223,59 @@ impl WalletService {
         &self,
         from_pubkey: &str,
         to_pubkey: &str,
-        amount: &str,
-        asset_code: Option<&str>,
-    ) -> Result<String> {
-        let wallet = self.wallet_repo.find_by_pubkey(from_pubkey).await?
-            .context("Wallet not found")?;
+        amount: Amount,
+        token: &Token,
+        client_ip: Option<&str>,
+    ) -> Result<String, AppError> {
+        let asset = QuoteAsset::from_token(token)
+            .ok_or_else(|| AppError::UnsupportedAsset(format!("{} is not a Stellar asset", token.symbol)))?;
+        let asset_code = asset.symbol().to_uppercase();
+
+        let wallet = self.wallet_repo.find_by_pubkey(from_pubkey).await
+            .map_err(|e| AppError::InternalError(e.to_string()))?
+            .ok_or_else(|| AppError::WalletNotFound(from_pubkey.to_string()))?;
 
-        let exists = self.stellar_service.check_account_exists(from_pubkey).await?;
+        let exists = self.stellar_service.check_account_exists(from_pubkey).await
+            .map_err(|e| AppError::StellarNetworkError(e.to_string()))?;
         if !exists {
-            return Err(anyhow::anyhow!("Source account does not exist on Stellar network"));
+            return Err(AppError::InternalError("Source account does not exist on Stellar network".to_string()));
+        }
+
+        // Balances are matched on code and issuer: a USDC from another issuer
+        // is a different asset. Funds escrowed for bank transfers are not
+        // spendable.
+        check_available(&self.stellar_service, &self.transaction_repo, &wallet, &asset, amount).await?;
+
+        let risk = self.risk_service
+            .assess(&RiskSubject {
+                operation: RiskOperation::Send,
+                wallet_id: wallet.id.clone(),
+                public_key: from_pubkey.to_string(),
+                reference: None,
+                amount_usd: self.risk_service.usd_value(&token.symbol, amount).await,
+                bank_account_hash: None,
+                ip_address: client_ip.map(str::to_string),
+            })
+            .await?;
+
+        if risk.action == RiskAction::Block {
+            return Err(AppError::RiskBlocked(risk.decision_id));
+        }
+
+        // A send cannot wait for a reviewer, so only a block stops it.
+        let screening = self.screening_service
+            .screen(&ScreeningSubject {
+                operation: RiskOperation::Send,
+                wallet_id: wallet.id.clone(),
+                reference: None,
+                names: Vec::new(),
+                stellar_addresses: vec![from_pubkey.to_string(), to_pubkey.to_string()],
+                bank_accounts: Vec::new(),
+            })
+            .await?;
+
+        if screening.action == RiskAction::Block {
+            return Err(AppError::ScreeningBlocked(screening.result_id.unwrap_or_default()));
         }
 
         let mock_tx_hash = format!("tx_{}", uuid::Uuid::new_v4());
@@ -138,26 +287,179 @@ impl WalletService {
             tx_type: TransactionType::Send.to_string(),
             from_address: Some(from_pubkey.to_string()),
             to_address: Some(to_pubkey.to_string()),
-            amount: amount.to_string(),
-            asset: asset_code.unwrap_or("XLM").to_string(),
+            amount,
+            asset: asset_code.clone(),
+            asset_issuer: asset.issuer().map(str::to_string),
+            dest_amount: None,
+            dest_asset: None,
+            dest_asset_issuer: None,
+            fee_amount: None,
+            fee_asset: None,
+            simulated: true,
+            bank_transfer_id: None,
             status: TransactionStatus::Completed.to_string(),
             created_at: chrono::Utc::now(),
         };
 
         self.transaction_repo.create(&transaction).await
-            .context("Failed to save transaction to database")?;
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        self.risk_service.set_reference(&risk.decision_id, &mock_tx_hash).await;
+        if let Some(result_id) = &screening.result_id {
+            self.screening_service.set_reference(result_id, &mock_tx_hash).await;
+        }
+
+        self.publish_transaction(EVENT_TRANSACTION_CONFIRMED, &transaction).await;
 
         tracing::info!(
             "Transaction recorded: {} -> {} ({} {})",
             from_pubkey,
             to_pubkey,
             amount,
-            asset_code.unwrap_or("XLM")
+            asset_code
         );
 
         Ok(mock_tx_hash)
     }
 
+    /// Swaps the sell asset of a firm quote into its buy asset with a strict
+    /// send path payment. The path and received amount come from Horizon's path
+    /// finder. Submitting the path payment is not implemented: unless simulated
+    /// conversions are enabled the request is refused before anything is
+    /// redeemed or recorded, and simulated ones say so in the response and
+    /// transaction row.
+    pub async fn convert_assets(
+        &self,
+        public_key: &str,
+        quote_id: &str,
+        slippage_bps: Option<u32>,
+    ) -> Result<WalletConvertResponse, AppError> {
+        check_conversion_submission(self.simulate_conversions)?;
+
+        let wallet = self.wallet_repo.find_by_pubkey(public_key).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .ok_or_else(|| AppError::WalletNotFound(public_key.to_string()))?;
+
+        let slippage_bps = slippage_bps.unwrap_or(self.max_slippage_bps);
+        if slippage_bps > self.max_slippage_bps {
+            return Err(AppError::BadRequest(format!(
+                "slippage_bps cannot exceed {}",
+                self.max_slippage_bps
+            )));
+        }
+
+        let quote = self.quote_service.get_quote(quote_id).await?;
+
+        let (send_asset, dest_asset) = match (
+            QuoteAsset::parse(&quote.sell_asset),
+            QuoteAsset::parse(&quote.buy_asset),
+        ) {
+            (Some(sell @ QuoteAsset::Stellar { .. }), Some(buy @ QuoteAsset::Stellar { .. })) => (sell, buy),
+            _ => {
+                return Err(AppError::BadRequest(
+                    "Wallet conversions require a quote between two Stellar assets".to_string(),
+                ))
+            }
+        };
+        let send_code = send_asset.symbol().to_uppercase();
+        let dest_code = dest_asset.symbol().to_uppercase();
+
+        check_available(&self.stellar_service, &self.transaction_repo, &wallet, &send_asset, quote.sell_amount).await?;
+
+        // The quote's fee is charged in the sell asset: only the rest goes
+        // through the path, and the quoted (post-fee) buy amount is the floor.
+        let path_amount = quote.sell_amount.checked_sub(quote.fee_total)?;
+        let dest_min = quote
+            .buy_amount
+            .scale_by((10_000 - slippage_bps) as i128, 10_000, Rounding::Up)?
+            .round_to(dest_asset.decimals(), Rounding::Up)?;
+
+        let route = self.stellar_service
+            .find_strict_send_path(&send_asset, path_amount, &dest_asset)
+            .await
+            .map_err(|e| AppError::StellarNetworkError(e.to_string()))?
+            .ok_or_else(|| AppError::StellarNetworkError(format!(
+                "No path from {} to {} on the Stellar DEX",
+                quote.sell_asset, quote.buy_asset
+            )))?;
+
+        if route.destination_amount < dest_min {
+            return Err(AppError::QuoteUnavailable(format!(
+                "Best path delivers {} {}, below the minimum of {} at {} bps slippage",
+                route.destination_amount, dest_code, dest_min, slippage_bps
+            )));
+        }
+
+        let transaction_id = uuid::Uuid::new_v4().to_string();
+        let quote = self.quote_service
+            .redeem(quote_id, public_key, &format!("wallet_convert:{}", transaction_id), |_| Ok(()))
+            .await?;
+
+        let mock_tx_hash = format!("tx_{}", uuid::Uuid::new_v4());
+
+        let transaction = Transaction {
+            id: transaction_id,
+            wallet_id: wallet.id.clone(),
+            tx_hash: mock_tx_hash.clone(),
+            tx_type: TransactionType::Convert.to_string(),
+            from_address: Some(public_key.to_string()),
+            to_address: Some(public_key.to_string()),
+            amount: quote.sell_amount,
+            asset: send_code.clone(),
+            asset_issuer: send_asset.issuer().map(str::to_string),
+            dest_amount: Some(route.destination_amount),
+            dest_asset: Some(dest_code.clone()),
+            dest_asset_issuer: dest_asset.issuer().map(str::to_string),
+            fee_amount: Some(quote.fee_total),
+            fee_asset: Some(send_code.clone()),
+            simulated: true,
+            bank_transfer_id: None,
+            status: TransactionStatus::Completed.to_string(),
+            created_at: chrono::Utc::now(),
+        };
+
+        self.transaction_repo.create(&transaction).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        self.publish_transaction(EVENT_TRANSACTION_CONFIRMED, &transaction).await;
+
+        tracing::info!(
+            "Simulated conversion recorded for {}: {} {} (fee {}) -> {} {} (min {}, quote {})",
+            public_key,
+            quote.sell_amount,
+            send_code,
+            quote.fee_total,
+            route.destination_amount,
+            dest_code,
+            dest_min,
+            quote.id
+        );
+
+        Ok(WalletConvertResponse {
+            tx_hash: mock_tx_hash,
+            status: transaction.status,
+            quote_id: quote.id,
+            send_asset: quote.sell_asset,
+            send_amount: quote.sell_amount,
+            dest_asset: quote.buy_asset,
+            dest_amount: route.destination_amount,
+            dest_min,
+            fee_amount: quote.fee_total,
+            fee_asset: quote.fee_asset,
+            path: route.path,
+            quoted_rate: Rate::from_ratio(quote.buy_amount, quote.sell_amount, Rounding::HalfEven)?,
+            realized_rate: Rate::from_ratio(route.destination_amount, quote.sell_amount, Rounding::HalfEven)?,
+            simulated: transaction.simulated,
+        })
+    }
+
+    async fn publish_transaction(&self, event_type: &str, transaction: &Transaction) {
+        match serde_json::to_value(transaction) {
+            Ok(data) => self.webhook_service.publish(event_type, data).await,
+            Err(e) => tracing::error!("Failed to serialize transaction {} for webhooks: {}", transaction.id, e),
+        }
+    }
+
     fn generate_stellar_keypair() -> Result<(String, String)> {
         let mut csprng = OsRng{};
         let keypair: Keypair = Keypair::generate(&mut csprng);
@@ -203,4 +505,62 @@ impl WalletService {
         
         second_hash[..2].to_vec()
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::{sqlite::SqlitePoolOptions, SqlitePool};
+
+    async fn transactions() -> (SqlitePool, TransactionRepository) {
+        let pool = SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap();
+        sqlx::raw_sql(
+            r#"
+            CREATE TABLE transactions (
+                id TEXT PRIMARY KEY NOT NULL,
+                wallet_id TEXT NOT NULL,
+                tx_type TEXT NOT NULL,
+                amount TEXT NOT NULL,
+                asset TEXT NOT NULL,
+                asset_issuer TEXT,
+                bank_transfer_id TEXT,
+                status TEXT NOT NULL
+            );
+            INSERT INTO transactions VALUES ('e1', 'w1', 'escrow', '60.0000000', 'USDC', 'GISSUER', 't1', 'completed');
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        (pool.clone(), TransactionRepository::new(pool))
+    }
+
+    #[tokio::test]
+    async fn test_escrowed_funds_cannot_be_sent() {
+        let (pool, repo) = transactions().await;
+        let usdc = QuoteAsset::Stellar { code: "usdc".to_string(), issuer: Some("GISSUER".to_string()) };
+        let on_chain: Amount = "100".parse().unwrap();
+        let send: Amount = "50".parse().unwrap();
+
+        let committed = repo.sum_committed("w1", "USDC", Some("GISSUER")).await.unwrap();
+        assert!(matches!(
+            check_spendable(on_chain, committed, send, &usdc),
+            Err(AppError::InsufficientBalance { .. })
+        ));
+        assert!(check_spendable(on_chain, committed, "40".parse().unwrap(), &usdc).is_ok());
+
+        // Releasing the escrow makes the funds spendable again.
+        sqlx::query("INSERT INTO transactions VALUES ('r1', 'w1', 'release', '60.0000000', 'USDC', 'GISSUER', 't1', 'completed')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let committed = repo.sum_committed("w1", "USDC", Some("GISSUER")).await.unwrap();
+        assert!(check_spendable(on_chain, committed, send, &usdc).is_ok());
+    }
+
+    #[test]
+    fn test_conversions_are_refused_unless_simulated() {
+        assert!(matches!(check_conversion_submission(false), Err(AppError::NotImplemented(_))));
+        assert!(check_conversion_submission(true).is_ok());
+    }
 }
\ No newline at end of file
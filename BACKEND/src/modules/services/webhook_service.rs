@@ -0,0 +1,528 @@
+use anyhow::{anyhow, Context, Result};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use chrono::{Duration, Utc};
+use hmac::{Hmac, Mac};
+use reqwest::dns::{Name, Resolve, Resolving};
+use reqwest::{redirect, Client, Url};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use tokio::task::JoinSet;
+
+use crate::error::AppError;
+use crate::modules::models::webhook::{
+    is_valid_pattern, CreateWebhookRequest, DeliveryStatus, WebhookDelivery, WebhookEvent, WebhookSubscription,
+    WebhookSubscriptionResponse,
+};
+use crate::modules::repositories::webhook_repo::WebhookRepository;
+use crate::utils::encryption::FieldCipher;
+
+/// Deliveries attempted on each worker pass.
+const WORKER_BATCH_SIZE: i64 = 50;
+
+const MIN_SECRET_LENGTH: usize = 16;
+
+/// Stored with a delivery's error, longer endpoint answers are cut.
+const MAX_ERROR_LENGTH: usize = 500;
+
+/// Outgoing webhooks. Published events are stored with one delivery per
+/// subscription wanting them; a background worker sends them signed and
+/// retries failures with exponential backoff until they run out of attempts
+/// and land in the dead-letter queue.
+#[derive(Clone)]
+pub struct WebhookService {
+    webhook_repo: Arc<WebhookRepository>,
+    /// Encrypts the signing secrets at rest.
+    cipher: FieldCipher,
+    client: Client,
+    timeout: std::time::Duration,
+    max_attempts: u32,
+    initial_backoff_seconds: i64,
+    max_backoff_seconds: i64,
+    /// Accept `http://` endpoints, for local development.
+    allow_http: bool,
+    /// Accept endpoints on loopback, private or link-local addresses, for
+    /// local development.
+    allow_private_hosts: bool,
+    /// Deliveries sent at the same time on each worker pass.
+    concurrency: usize,
+}
+
+impl WebhookService {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        webhook_repo: Arc<WebhookRepository>,
+        cipher: FieldCipher,
+        timeout: std::time::Duration,
+        max_attempts: u32,
+        initial_backoff_seconds: i64,
+        max_backoff_seconds: i64,
+        allow_http: bool,
+        allow_private_hosts: bool,
+        concurrency: usize,
+    ) -> Self {
+        // Endpoints are checked when they are created, but DNS can change
+        // after that, so every connection resolves through the same check.
+        // Redirects are not followed: they could point anywhere.
+        let mut client = Client::builder().redirect(redirect::Policy::none());
+        if !allow_private_hosts {
+            client = client.dns_resolver(Arc::new(PublicResolver));
+        }
+
+        Self {
+            webhook_repo,
+            cipher,
+            client: client.build().expect("Failed to build the webhook HTTP client"),
+            timeout,
+            max_attempts,
+            initial_backoff_seconds,
+            max_backoff_seconds,
+            allow_http,
+            allow_private_hosts,
+            concurrency: concurrency.max(1),
+        }
+    }
+
+    pub async fn create_subscription(
+        &self,
+        request: &CreateWebhookRequest,
+    ) -> Result<WebhookSubscriptionResponse, AppError> {
+        let url = Url::parse(request.url.trim())
+            .map_err(|e| AppError::BadRequest(format!("Invalid webhook URL: {}", e)))?;
+        match url.scheme() {
+            "https" => {}
+            "http" if self.allow_http => {}
+            scheme => {
+                return Err(AppError::BadRequest(format!("Webhook URLs must use https, got {}", scheme)));
+            }
+        }
+        if !self.allow_private_hosts {
+            check_public_endpoint(&url).await.map_err(|e| AppError::BadRequest(e.to_string()))?;
+        }
+
+        let event_types: Vec<&str> = request.event_types.iter().map(|t| t.trim()).filter(|t| !t.is_empty()).collect();
+        if event_types.is_empty() {
+            return Err(AppError::BadRequest("At least one event type is required".to_string()));
+        }
+        if let Some(unknown) = event_types.iter().find(|t| !is_valid_pattern(t)) {
+            return Err(AppError::BadRequest(format!("Unknown webhook event type: {}", unknown)));
+        }
+
+        let secret = match request.secret.as_deref().map(str::trim) {
+            Some(secret) if secret.len() < MIN_SECRET_LENGTH => {
+                return Err(AppError::BadRequest(format!(
+                    "Webhook secrets must be at least {} characters",
+                    MIN_SECRET_LENGTH
+                )));
+            }
+            Some(secret) => secret.to_string(),
+            None => format!("whsec_{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple()),
+        };
+        let encrypted = self.cipher.encrypt(&secret)
+            .map_err(|e| AppError::InternalError(e.to_string()))?;
+
+        let now = Utc::now();
+        let subscription = WebhookSubscription {
+            id: uuid::Uuid::new_v4().to_string(),
+            url: url.to_string(),
+            event_types: event_types.join(","),
+            secret: encrypted,
+            description: request.description.as_deref().map(str::trim).filter(|d| !d.is_empty()).map(str::to_string),
+            active: true,
+            created_at: now,
+            updated_at: now,
+        };
+
+        self.webhook_repo.create_subscription(&subscription).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        tracing::info!(
+            "Webhook subscription {} created for {} ({})",
+            subscription.id,
+            subscription.url,
+            subscription.event_types
+        );
+
+        Ok(WebhookSubscriptionResponse {
+            subscription,
+            secret: Some(secret),
+        })
+    }
+
+    pub async fn list_subscriptions(&self) -> Result<Vec<WebhookSubscriptionResponse>, AppError> {
+        let subscriptions = self.webhook_repo.find_active_subscriptions().await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        Ok(subscriptions
+            .into_iter()
+            .map(|subscription| WebhookSubscriptionResponse { subscription, secret: None })
+            .collect())
+    }
+
+    pub async fn delete_subscription(&self, id: &str) -> Result<(), AppError> {
+        let deactivated = self.webhook_repo.deactivate(id, Utc::now()).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        if !deactivated {
+            return Err(AppError::WebhookNotFound(id.to_string()));
+        }
+
+        tracing::info!("Webhook subscription {} deactivated", id);
+        Ok(())
+    }
+
+    /// Stores an event for every active subscription wanting it. Failing to
+    /// publish never fails the operation the event is about.
+    pub async fn publish(&self, event_type: &str, data: Value) {
+        if let Err(e) = self.try_publish(event_type, data).await {
+            tracing::error!("Failed to publish webhook event {}: {}", event_type, e);
+        }
+    }
+
+    async fn try_publish(&self, event_type: &str, data: Value) -> Result<()> {
+        let subscriptions: Vec<_> = self.webhook_repo.find_active_subscriptions().await?
+            .into_iter()
+            .filter(|subscription| subscription.wants(event_type))
+            .collect();
+        if subscriptions.is_empty() {
+            return Ok(());
+        }
+
+        let now = Utc::now();
+        let id = uuid::Uuid::new_v4().to_string();
+        let payload = json!({
+            "id": id,
+            "type": event_type,
+            "created_at": now,
+            "data": data,
+        });
+        let event = WebhookEvent {
+            id,
+            event_type: event_type.to_string(),
+            payload: payload.to_string(),
+            created_at: now,
+        };
+
+        let deliveries: Vec<_> = subscriptions
+            .iter()
+            .map(|subscription| Self::new_delivery(&event.id, &subscription.id))
+            .collect();
+
+        self.webhook_repo.create_event(&event, &deliveries).await?;
+
+        tracing::debug!("Webhook event {} {} queued for {} subscriptions", event.id, event_type, deliveries.len());
+        Ok(())
+    }
+
+    /// Deliveries that ran out of attempts, the most recent first.
+    pub async fn dead_letters(&self) -> Result<Vec<WebhookDelivery>, AppError> {
+        self.webhook_repo.find_deliveries_by_status(DeliveryStatus::Dead).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))
+    }
+
+    /// Sends an event again to one subscription, or to every active one
+    /// wanting it. Earlier deliveries, dead or not, are queued with a fresh
+    /// set of attempts.
+    pub async fn replay(&self, event_id: &str, subscription_id: Option<&str>) -> Result<Vec<WebhookDelivery>, AppError> {
+        let event = self.webhook_repo.find_event(event_id).await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .ok_or_else(|| AppError::WebhookEventNotFound(event_id.to_string()))?;
+
+        let subscriptions = match subscription_id {
+            Some(id) => {
+                let subscription = self.webhook_repo.find_subscription(id).await
+                    .map_err(|e| AppError::DatabaseError(e.to_string()))?
+                    .filter(|subscription| subscription.active)
+                    .ok_or_else(|| AppError::WebhookNotFound(id.to_string()))?;
+                vec![subscription]
+            }
+            None => self.webhook_repo.find_active_subscriptions().await
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?
+                .into_iter()
+                .filter(|subscription| subscription.wants(&event.event_type))
+                .collect(),
+        };
+
+        let mut deliveries = Vec::new();
+        for subscription in subscriptions {
+            self.webhook_repo.requeue(&Self::new_delivery(&event.id, &subscription.id)).await
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+            if let Some(delivery) = self.webhook_repo.find_delivery(&event.id, &subscription.id).await
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            {
+                deliveries.push(delivery);
+            }
+        }
+
+        tracing::info!("Webhook event {} replayed to {} subscriptions", event.id, deliveries.len());
+        Ok(deliveries)
+    }
+
+    pub fn spawn_worker(self: Arc<Self>, every: std::time::Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(every);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = self.deliver_due().await {
+                    tracing::warn!("Webhook worker failed: {}", e);
+                }
+            }
+        })
+    }
+
+    /// Sends a batch of due deliveries, `concurrency` at a time, so one slow
+    /// endpoint holds up at most one slot for its timeout.
+    pub async fn deliver_due(&self) -> Result<()> {
+        let mut sending = JoinSet::new();
+        for delivery in self.webhook_repo.find_due_deliveries(Utc::now(), WORKER_BATCH_SIZE).await? {
+            if sending.len() >= self.concurrency {
+                sending.join_next().await;
+            }
+            let service = self.clone();
+            sending.spawn(async move {
+                if let Err(e) = service.deliver(&delivery).await {
+                    tracing::warn!("Failed to record webhook delivery {}: {}", delivery.id, e);
+                }
+            });
+        }
+        while sending.join_next().await.is_some() {}
+        Ok(())
+    }
+
+    async fn deliver(&self, delivery: &WebhookDelivery) -> Result<()> {
+        let attempts = delivery.attempts + 1;
+        let (status_code, error) = match self.send(delivery).await {
+            Ok(status_code) => {
+                self.webhook_repo.mark_delivered(&delivery.id, attempts, status_code, Utc::now()).await?;
+                tracing::debug!("Webhook delivery {} succeeded after {} attempts", delivery.id, attempts);
+                return Ok(());
+            }
+            Err(failure) => failure,
+        };
+
+        let now = Utc::now();
+        let (status, next_attempt_at) = if attempts >= self.max_attempts as i64 {
+            tracing::warn!(
+                "Webhook delivery {} moved to the dead-letter queue after {} attempts: {}",
+                delivery.id,
+                attempts,
+                error
+            );
+            (DeliveryStatus::Dead, now)
+        } else {
+            let delay = retry_delay(self.initial_backoff_seconds, self.max_backoff_seconds, attempts);
+            (DeliveryStatus::Pending, now + Duration::seconds(delay))
+        };
+
+        self.webhook_repo
+            .mark_failed(&delivery.id, status, attempts, next_attempt_at, status_code, &error, now)
+            .await
+    }
+
+    /// Posts the delivery's event. Errors carry the HTTP status, if the
+    /// endpoint answered, and what went wrong.
+    async fn send(&self, delivery: &WebhookDelivery) -> Result<i64, (Option<i64>, String)> {
+        let (subscription, event) = self.load(delivery).await.map_err(|e| (None, e.to_string()))?;
+        let secret = self.cipher.decrypt(&subscription.secret).map_err(|e| (None, e.to_string()))?;
+        // Host names are checked by the resolver on connect; addresses in the
+        // URL never reach it.
+        if !self.allow_private_hosts {
+            let url = Url::parse(&subscription.url).map_err(|e| (None, e.to_string()))?;
+            if let Some(ip) = ip_literal(&url).filter(|ip| !is_public_ip(*ip)) {
+                return Err((None, format!("Webhook endpoint {} is not a public address", ip)));
+            }
+        }
+
+        let timestamp = Utc::now().timestamp();
+        let response = self.client
+            .post(&subscription.url)
+            .timeout(self.timeout)
+            .header("Content-Type", "application/json")
+            .header("X-Webhook-Id", &event.id)
+            .header("X-Webhook-Event", &event.event_type)
+            .header("X-Webhook-Timestamp", timestamp.to_string())
+            .header("X-Webhook-Signature", format!("v1={}", sign(&secret, timestamp, &event.payload)))
+            .body(event.payload.clone())
+            .send()
+            .await
+            .map_err(|e| (None, format!("Request failed: {}", e)))?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(status.as_u16() as i64);
+        }
+
+        let body = response.text().await.unwrap_or_default();
+        let body: String = body.chars().take(MAX_ERROR_LENGTH).collect();
+        Err((Some(status.as_u16() as i64), format!("Endpoint returned {}: {}", status, body)))
+    }
+
+    async fn load(&self, delivery: &WebhookDelivery) -> Result<(WebhookSubscription, WebhookEvent)> {
+        let subscription = self.webhook_repo.find_subscription(&delivery.subscription_id).await?
+            .ok_or_else(|| anyhow!("Subscription {} no longer exists", delivery.subscription_id))?;
+        let event = self.webhook_repo.find_event(&delivery.event_id).await?
+            .with_context(|| format!("Event {} no longer exists", delivery.event_id))?;
+        Ok((subscription, event))
+    }
+
+    fn new_delivery(event_id: &str, subscription_id: &str) -> WebhookDelivery {
+        let now = Utc::now();
+        WebhookDelivery {
+            id: uuid::Uuid::new_v4().to_string(),
+            event_id: event_id.to_string(),
+            subscription_id: subscription_id.to_string(),
+            status: DeliveryStatus::Pending.to_string(),
+            attempts: 0,
+            next_attempt_at: now,
+            last_status_code: None,
+            last_error: None,
+            delivered_at: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+/// Resolves webhook hosts, refusing any that points at an address
+/// [`is_public_ip`] rejects.
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addrs = public_addrs(name.as_str(), 0).await?;
+            Ok(Box::new(addrs.into_iter()) as Box<dyn Iterator<Item = SocketAddr> + Send>)
+        })
+    }
+}
+
+/// The addresses of `host`, as long as every one of them is public.
+async fn public_addrs(host: &str, port: u16) -> Result<Vec<SocketAddr>> {
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port))
+        .await
+        .with_context(|| format!("Cannot resolve webhook host {}", host))?
+        .collect();
+    if addrs.is_empty() {
+        return Err(anyhow!("Webhook host {} has no addresses", host));
+    }
+    if let Some(addr) = addrs.iter().find(|addr| !is_public_ip(addr.ip())) {
+        return Err(anyhow!("Webhook host {} resolves to {}, which is not a public address", host, addr.ip()));
+    }
+    Ok(addrs)
+}
+
+async fn check_public_endpoint(url: &Url) -> Result<()> {
+    if let Some(ip) = ip_literal(url) {
+        if !is_public_ip(ip) {
+            return Err(anyhow!("Webhook endpoint {} is not a public address", ip));
+        }
+        return Ok(());
+    }
+    let host = url.host_str().ok_or_else(|| anyhow!("Webhook URLs need a host"))?;
+    public_addrs(host, url.port_or_known_default().unwrap_or(443)).await?;
+    Ok(())
+}
+
+/// The address in `url`, when it has one instead of a host name.
+fn ip_literal(url: &Url) -> Option<IpAddr> {
+    url.host_str()?.trim_start_matches('[').trim_end_matches(']').parse().ok()
+}
+
+/// Whether webhooks may be sent to `ip`: not loopback, private, link-local,
+/// shared (CGNAT), unspecified, broadcast, multicast or documentation space.
+fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || ip.is_documentation()
+                || a == 0
+                || (a == 100 && (64..128).contains(&b)))
+        }
+        IpAddr::V6(ip) => {
+            if let Some(v4) = ip.to_ipv4_mapped() {
+                return is_public_ip(IpAddr::V4(v4));
+            }
+            let first = ip.segments()[0];
+            !(ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                || (first & 0xfe00) == 0xfc00
+                || (first & 0xffc0) == 0xfe80)
+        }
+    }
+}
+
+/// Hex HMAC-SHA256 of `{timestamp}.{payload}`. Receivers recompute it from
+/// the raw body and `X-Webhook-Timestamp`, and reject old timestamps.
+pub fn sign(secret: &str, timestamp: i64, payload: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(format!("{}.{}", timestamp, payload).as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Seconds to wait after the `attempts`-th failure: the initial backoff,
+/// doubled per further attempt, capped at the maximum.
+fn retry_delay(initial_seconds: i64, max_seconds: i64, attempts: i64) -> i64 {
+    let doublings = (attempts - 1).clamp(0, 30) as u32;
+    initial_seconds.saturating_mul(1_i64 << doublings).min(max_seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign() {
+        assert_eq!(
+            sign("whsec_test", 1_700_000_000, r#"{"id":"evt_1"}"#),
+            "c89214b5b5da833daed6f0b8c5bb6bd58cea9022bd80ccc78230f3942d632925"
+        );
+    }
+
+    #[test]
+    fn test_is_public_ip() {
+        for ip in ["8.8.8.8", "1.1.1.1", "2606:4700:4700::1111"] {
+            assert!(is_public_ip(ip.parse().unwrap()), "{}", ip);
+        }
+        for ip in [
+            "127.0.0.1",
+            "10.0.0.5",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "::",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+            "::ffff:169.254.169.254",
+        ] {
+            assert!(!is_public_ip(ip.parse().unwrap()), "{}", ip);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_private_endpoints_are_rejected() {
+        for url in ["https://127.0.0.1/hook", "https://[::1]/hook", "https://169.254.169.254/latest", "http://localhost:8080/"] {
+            assert!(check_public_endpoint(&Url::parse(url).unwrap()).await.is_err(), "{}", url);
+        }
+        assert!(check_public_endpoint(&Url::parse("https://8.8.8.8/hook").unwrap()).await.is_ok());
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        assert_eq!(retry_delay(30, 3600, 1), 30);
+        assert_eq!(retry_delay(30, 3600, 2), 60);
+        assert_eq!(retry_delay(30, 3600, 4), 240);
+        assert_eq!(retry_delay(30, 3600, 10), 3600);
+        assert_eq!(retry_delay(30, 3600, 100), 3600);
+    }
+}
\ No newline at end of file
@@ -1,12 +1,19 @@
 use axum::{
-    routing::{get, post},
+    middleware::{from_fn, from_fn_with_state},
+    routing::{delete, get, post},
     Router,
 };
 use tower_http::cors::{Any, CorsLayer};
 use tower_http::trace::TraceLayer;
 
 use crate::modules::controllers::{
-    admin, bank, convert, health, reputation, wallet,
+    admin, bank, beneficiary, convert, health, kyc, quotes, reconciliation, remittance, reputation, review, wallet,
+    webhook,
+};
+use crate::middleware::{
+    admin_auth::admin_auth,
+    idempotency::idempotency,
+    wallet_auth::{optional_wallet_auth, wallet_auth},
 };
 use crate::state::AppState;
 
@@ -16,27 +23,99 @@ pub fn create_router(state: AppState) -> Router {
         .allow_methods(Any)
         .allow_headers(Any);
 
+    let idempotent = from_fn_with_state(state.clone(), idempotency);
+    let signed = from_fn(wallet_auth);
+    let maybe_signed = from_fn(optional_wallet_auth);
+    let operator = from_fn_with_state(state.clone(), admin_auth);
+
     let api_routes = Router::new()
         .route("/health", get(health::health_check))
         
         .route("/wallet/generate", post(wallet::generate_wallet))
         .route("/wallet/fund", post(wallet::fund_wallet))
         .route("/wallet/:pubkey/balance", get(wallet::get_balance))
-        .route("/wallet/:pubkey/send", post(wallet::send_transaction))
+        .route("/wallet/:pubkey/send", post(wallet::send_transaction).layer(idempotent.clone()))
+        .route("/wallet/:pubkey/convert", post(wallet::convert_assets).layer(signed.clone()))
+        .route(
+            "/wallet/:pubkey/beneficiaries",
+            get(beneficiary::list_beneficiaries)
+                .post(beneficiary::create_beneficiary)
+                .layer(signed.clone()),
+        )
+        .route(
+            "/wallet/:pubkey/beneficiaries/:id",
+            get(beneficiary::get_beneficiary)
+                .delete(beneficiary::delete_beneficiary)
+                .layer(signed.clone()),
+        )
         
         .route("/reputation/:pubkey", get(reputation::get_reputation))
+
+        .route("/kyc/customer", get(kyc::get_customer).put(kyc::put_customer).layer(signed.clone()))
+        .route("/kyc/customer/:account", delete(kyc::delete_customer).layer(signed.clone()))
         
-        .route("/convert/to-usdc", post(convert::convert_to_usdc))
+        .route("/convert/to-usdc", post(convert::convert_to_usdc).layer(maybe_signed))
         .route("/rates", get(convert::get_rates))
+        .route("/rates/history", get(convert::get_rate_history))
+        .route("/rates/stream", get(convert::stream_rates))
+        
+        .route("/quotes", post(quotes::create_quote).layer(signed.clone()))
+        .route("/quotes/:id", get(quotes::get_quote).layer(signed.clone()))
         
-        .route("/bank/transfer", post(bank::create_transfer))
-        .route("/admin/transfers", get(bank::list_transfers))
+        .route(
+            "/bank/transfer",
+            post(bank::create_transfer).layer(idempotent.clone()).layer(signed.clone()),
+        )
+        .route("/bank/transfers", get(bank::list_wallet_transfers).layer(signed.clone()))
+        .route("/bank/transfers/:id", get(bank::get_transfer).layer(signed.clone()))
+        .route("/bank/transfers/:id/cancel", post(bank::cancel_transfer).layer(signed.clone()))
+        .route(
+            "/remittances",
+            post(remittance::create_remittance)
+                .layer(idempotent.clone())
+                .get(remittance::list_remittances)
+                .layer(signed.clone()),
+        )
+        .route("/remittances/:id", get(remittance::get_remittance).layer(signed.clone()))
+        .route("/admin/transfers", get(bank::list_transfers).layer(operator.clone()))
+        .route("/admin/transfers/:id/reverse", post(bank::reverse_transfer).layer(operator.clone()))
+        .route("/admin/reviews", get(review::list_reviews).layer(operator.clone()))
+        .route("/admin/reviews/:id", get(review::get_review).layer(operator.clone()))
+        .route("/admin/reviews/:id/claim", post(review::claim_review).layer(operator.clone()))
+        .route("/admin/reviews/:id/approve", post(review::approve_review).layer(operator.clone()))
+        .route("/admin/reviews/:id/reject", post(review::reject_review).layer(operator.clone()))
+        .route("/admin/kyc/customers", get(kyc::list_customers).layer(operator.clone()))
+        .route("/admin/kyc/customers/:id/decision", post(kyc::decide_customer).layer(operator.clone()))
+        .route(
+            "/admin/beneficiaries/:id/verification",
+            post(beneficiary::verify_beneficiary).layer(operator.clone()),
+        )
+        .route(
+            "/admin/webhooks",
+            get(webhook::list_webhooks).post(webhook::create_webhook).layer(operator.clone()),
+        )
+        .route("/admin/webhooks/dead-letters", get(webhook::list_dead_letters).layer(operator.clone()))
+        .route("/admin/webhooks/events/:id/replay", post(webhook::replay_event).layer(operator.clone()))
+        .route("/admin/webhooks/:id", delete(webhook::delete_webhook).layer(operator.clone()))
+        .route(
+            "/admin/reconciliation/statements",
+            post(reconciliation::import_statement).layer(operator.clone()),
+        )
+        .route(
+            "/admin/reconciliation/runs",
+            get(reconciliation::list_runs).post(reconciliation::run_reconciliation).layer(operator.clone()),
+        )
+        .route("/admin/reconciliation/runs/:id", get(reconciliation::get_run).layer(operator.clone()))
+        .route(
+            "/admin/reconciliation/runs/:id/report.csv",
+            get(reconciliation::download_report).layer(operator.clone()),
+        )
         
         .route("/admin/stats", get(admin::get_stats))
         .route("/admin/health-details", get(admin::health_details))
         .route("/admin/aa-accounts", get(admin::list_aa_accounts))
         
-        .route("/aa/relayer", post(wallet::aa_relay_transaction))
+        .route("/aa/relayer", post(wallet::aa_relay_transaction).layer(idempotent))
         
         .with_state(state);
 
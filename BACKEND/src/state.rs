@@ -1,19 +1,52 @@
+use anyhow::{Context, Result};
 use sqlx::SqlitePool;
 use std::sync::Arc;
 
 use crate::config::Config;
+use crate::modules::models::risk::RiskRule;
+use crate::utils::encryption::FieldCipher;
 use crate::modules::services::{
     aa_service::AaService,
     bank_service::BankService,
+    beneficiary_service::BeneficiaryService,
     convert_service::ConvertService,
+    idempotency_service::IdempotencyService,
+    kyc_service::KycService,
+    payout_provider::{
+        CirclePayoutProvider, MockPayoutProvider, PayoutProvider, PayoutRouter, SpeiPayoutProvider,
+        StripePayoutProvider,
+    },
+    price_oracle::PriceOracle,
+    price_sources::{CoinGeckoSource, PriceSource, ScriptedPriceSource, StaticPriceSource, StellarDexSource},
+    quote_service::QuoteService,
+    rate_history_service::RateHistoryService,
+    rate_stream_service::RateStreamService,
+    reconciliation_service::ReconciliationService,
+    remittance_service::RemittanceService,
     reputation_service::ReputationService,
+    review_service::ReviewService,
+    risk_service::RiskService,
+    screening_service::{ListSource, ScreeningService},
     stellar_service::StellarService,
+    token_registry::TokenRegistry,
     wallet_service::WalletService,
+    webhook_service::WebhookService,
 };
 use crate::modules::repositories::{
     bank_transfer_repo::BankTransferRepository,
+    beneficiary_repo::BeneficiaryRepository,
+    idempotency_repo::IdempotencyRepository,
+    kyc_repo::KycRepository,
+    quote_repo::QuoteRepository,
+    rate_history_repo::RateHistoryRepository,
+    reconciliation_repo::ReconciliationRepository,
+    remittance_repo::RemittanceRepository,
+    review_repo::ReviewRepository,
+    risk_repo::RiskRepository,
+    screening_repo::ScreeningRepository,
     transaction_repo::TransactionRepository,
     wallet_repo::WalletRepository,
+    webhook_repo::WebhookRepository,
 };
 
 #[derive(Clone)]
@@ -27,15 +60,38 @@ pub struct AppState {
     pub reputation_service: Arc<ReputationService>,
     pub convert_service: Arc<ConvertService>,
     pub bank_service: Arc<BankService>,
+    pub quote_service: Arc<QuoteService>,
+    pub rate_history_service: Arc<RateHistoryService>,
+    pub rate_stream_service: Arc<RateStreamService>,
+    pub idempotency_service: Arc<IdempotencyService>,
+    pub review_service: Arc<ReviewService>,
+    pub screening_service: Arc<ScreeningService>,
+    pub kyc_service: Arc<KycService>,
+    pub beneficiary_service: Arc<BeneficiaryService>,
+    pub webhook_service: Arc<WebhookService>,
+    pub reconciliation_service: Arc<ReconciliationService>,
+    pub remittance_service: Arc<RemittanceService>,
+    pub token_registry: Arc<TokenRegistry>,
 }
 
 impl AppState {
-    pub async fn new(config: Config, db_pool: SqlitePool) -> Self {
+    pub async fn new(config: Config, db_pool: SqlitePool) -> Result<Self> {
         let config_arc = Arc::new(config.clone());
         
         let wallet_repo = Arc::new(WalletRepository::new(db_pool.clone()));
         let transaction_repo = Arc::new(TransactionRepository::new(db_pool.clone()));
         let bank_transfer_repo = Arc::new(BankTransferRepository::new(db_pool.clone()));
+        let quote_repo = Arc::new(QuoteRepository::new(db_pool.clone()));
+        let rate_history_repo = Arc::new(RateHistoryRepository::new(db_pool.clone()));
+        let idempotency_repo = Arc::new(IdempotencyRepository::new(db_pool.clone()));
+        let risk_repo = Arc::new(RiskRepository::new(db_pool.clone()));
+        let review_repo = Arc::new(ReviewRepository::new(db_pool.clone()));
+        let screening_repo = Arc::new(ScreeningRepository::new(db_pool.clone()));
+        let kyc_repo = Arc::new(KycRepository::new(db_pool.clone()));
+        let beneficiary_repo = Arc::new(BeneficiaryRepository::new(db_pool.clone()));
+        let webhook_repo = Arc::new(WebhookRepository::new(db_pool.clone()));
+        let reconciliation_repo = Arc::new(ReconciliationRepository::new(db_pool.clone()));
+        let remittance_repo = Arc::new(RemittanceRepository::new(db_pool.clone()));
 
         let aa_service = Arc::new(AaService::new());
         let stellar_service = Arc::new(StellarService::new(
@@ -43,30 +99,162 @@ impl AppState {
             config.stellar.friendbot_url.clone(),
         ));
         
-        let wallet_service = Arc::new(WalletService::new(
-            wallet_repo.clone(),
+        let reputation_service = Arc::new(ReputationService::new(
             transaction_repo.clone(),
-            aa_service.clone(),
+            bank_transfer_repo.clone(),
             stellar_service.clone(),
+            config.reputation.threshold,
+            config.reputation.reversal_penalty,
         ));
 
-        let reputation_service = Arc::new(ReputationService::new(
+        let token_registry = Arc::new(Self::build_token_registry(&config)?);
+
+        let price_oracle = Arc::new(Self::build_price_oracle(&config, token_registry.clone())?);
+
+        let rate_history_service = Arc::new(RateHistoryService::new(
+            rate_history_repo.clone(),
+            config.rate_history.snapshot_retention_days,
+        ));
+
+        let convert_service = Arc::new(ConvertService::new(
+            price_oracle.clone(),
+            rate_history_service.clone(),
+            token_registry.clone(),
+            config.convert.supported_fiats(),
+            config.convert.default_fiat.clone(),
+        ));
+
+        let rate_stream_service = Arc::new(RateStreamService::new(
+            convert_service.clone(),
+            std::time::Duration::from_secs(config.rate_stream.poll_interval_seconds),
+            config.rate_stream.max_pairs_per_client,
+        ));
+
+        let risk_service = Arc::new(RiskService::new(
+            risk_repo.clone(),
+            transaction_repo.clone(),
+            convert_service.clone(),
+            Self::build_risk_rules(&config)?,
+        )?);
+
+        let cipher = Self::build_cipher(&config)?;
+
+        let screening_service = Arc::new(ScreeningService::new(
+            screening_repo.clone(),
+            cipher.clone(),
+            Self::build_screening_sources(&config),
+            config.screening.fuzzy_threshold,
+            config.screening.exact_match_action,
+            config.screening.fuzzy_match_action,
+        ));
+        screening_service.reload().await.context("Failed to load screening lists")?;
+
+        let kyc_service = Arc::new(KycService::new(
+            kyc_repo.clone(),
+            wallet_repo.clone(),
+            bank_transfer_repo.clone(),
+            cipher.clone(),
+            config.kyc.unverified_limit_usd,
+            config.kyc.accepted_limit_usd,
+            config.kyc.limit_window_hours,
+        ));
+
+        let beneficiary_service = Arc::new(BeneficiaryService::new(
+            beneficiary_repo.clone(),
+            wallet_repo.clone(),
+            cipher.clone(),
+            config.beneficiaries.cooling_off_hours,
+        ));
+
+        let webhook_service = Arc::new(WebhookService::new(
+            webhook_repo.clone(),
+            cipher.clone(),
+            std::time::Duration::from_secs(config.webhooks.timeout_seconds),
+            config.webhooks.max_attempts,
+            config.webhooks.initial_backoff_seconds,
+            config.webhooks.max_backoff_seconds,
+            config.webhooks.allow_http,
+            config.webhooks.allow_private_hosts,
+            config.webhooks.concurrency,
+        ));
+
+        let quote_service = Arc::new(QuoteService::new(
+            quote_repo.clone(),
+            convert_service.clone(),
+            token_registry.clone(),
+            config.quotes.ttl_seconds,
+            config.quotes.fee_bps,
+        ));
+
+        let wallet_service = Arc::new(WalletService::new(
+            wallet_repo.clone(),
             transaction_repo.clone(),
+            aa_service.clone(),
             stellar_service.clone(),
-            config.reputation.threshold,
+            quote_service.clone(),
+            risk_service.clone(),
+            screening_service.clone(),
+            webhook_service.clone(),
+            token_registry.clone(),
+            config.convert.max_slippage_bps,
+            config.convert.simulate_wallet_conversions,
         ));
 
-        let convert_service = Arc::new(ConvertService::new(
-            config.external_apis.coingecko_api_url.clone(),
+        let review_service = Arc::new(ReviewService::new(
+            review_repo.clone(),
+            config.review.sla_minutes,
+            config.review.second_approval_usd,
         ));
 
         let bank_service = Arc::new(BankService::new(
             bank_transfer_repo.clone(),
             wallet_repo.clone(),
+            transaction_repo.clone(),
+            remittance_repo.clone(),
             reputation_service.clone(),
+            risk_service.clone(),
+            review_service.clone(),
+            screening_service.clone(),
+            kyc_service.clone(),
+            beneficiary_service.clone(),
+            webhook_service.clone(),
+            stellar_service.clone(),
+            quote_service.clone(),
+            Self::build_payout_router(&config)?,
+            cipher,
+            token_registry.clone(),
+            config.bank.settlement_account.clone(),
+            config.bank.reject_fallback_rates,
+            config.review.reputation_margin,
         ));
 
-        Self {
+        let reconciliation_service = Arc::new(ReconciliationService::new(
+            reconciliation_repo.clone(),
+            bank_transfer_repo.clone(),
+            config.bank.enabled_payout_providers(),
+        ));
+
+        let remittance_service = Arc::new(RemittanceService::new(
+            remittance_repo.clone(),
+            wallet_repo.clone(),
+            bank_service.clone(),
+            quote_service.clone(),
+            stellar_service.clone(),
+            webhook_service.clone(),
+            token_registry.clone(),
+            config.remittances.source_asset.clone(),
+            config.remittances.currency.clone(),
+            config.remittances.dex_asset.clone(),
+            config.convert.max_slippage_bps,
+        ));
+
+        let idempotency_service = Arc::new(IdempotencyService::new(
+            idempotency_repo.clone(),
+            config.idempotency.ttl_hours,
+            config.idempotency.lease_seconds,
+        ));
+
+        Ok(Self {
             config: config_arc,
             db_pool,
             wallet_service,
@@ -75,6 +263,195 @@ impl AppState {
             reputation_service,
             convert_service,
             bank_service,
+            quote_service,
+            rate_history_service,
+            rate_stream_service,
+            idempotency_service,
+            review_service,
+            screening_service,
+            kyc_service,
+            beneficiary_service,
+            webhook_service,
+            reconciliation_service,
+            remittance_service,
+            token_registry,
+        })
+    }
+
+    fn build_payout_router(config: &Config) -> Result<PayoutRouter> {
+        let apis = &config.external_apis;
+        let mut providers: Vec<Arc<dyn PayoutProvider>> = Vec::new();
+
+        for name in config.bank.enabled_payout_providers() {
+            let provider: Arc<dyn PayoutProvider> = match name.as_str() {
+                "spei" => Arc::new(SpeiPayoutProvider::new(
+                    apis.spei_api_url.clone().context("SPEI_API_URL is required for spei payouts")?,
+                    apis.spei_api_key.clone().context("SPEI_API_KEY is required for spei payouts")?,
+                )),
+                "circle" => Arc::new(CirclePayoutProvider::new(
+                    apis.circle_api_url.clone(),
+                    apis.circle_api_key.clone().context("CIRCLE_API_KEY is required for circle payouts")?,
+                )),
+                "stripe" => Arc::new(StripePayoutProvider::new(
+                    apis.stripe_api_url.clone(),
+                    apis.stripe_secret_key.clone().context("STRIPE_SECRET_KEY is required for stripe payouts")?,
+                    apis.stripe_financial_account
+                        .clone()
+                        .context("STRIPE_FINANCIAL_ACCOUNT is required for stripe payouts")?,
+                )),
+                "mock" => Arc::new(MockPayoutProvider::new(
+                    std::time::Duration::from_secs(config.bank.mock_settle_seconds),
+                    config.bank.mock_payout_url.clone(),
+                )),
+                other => anyhow::bail!("Unknown payout provider: {}", other),
+            };
+            providers.push(provider);
+        }
+
+        Ok(PayoutRouter::new(providers))
+    }
+
+    fn build_risk_rules(config: &Config) -> Result<Vec<RiskRule>> {
+        match &config.risk.rules_file {
+            Some(path) => RiskService::load_rules(path).context("Failed to load risk rules"),
+            None => Ok(RiskService::default_rules()),
         }
     }
+
+    fn build_screening_sources(config: &Config) -> Vec<ListSource> {
+        let files = [
+            ("sdn", &config.screening.sdn_file),
+            ("internal", &config.screening.internal_list_file),
+        ];
+
+        files
+            .into_iter()
+            .filter_map(|(source, path)| {
+                path.as_ref().map(|path| ListSource {
+                    source: source.to_string(),
+                    path: path.clone(),
+                })
+            })
+            .collect()
+    }
+
+    fn build_cipher(config: &Config) -> Result<FieldCipher> {
+        match &config.security.encryption_key {
+            Some(key) => FieldCipher::from_hex(key),
+            None => {
+                tracing::warn!(
+                    "SECURITY_ALLOW_EPHEMERAL_KEY is set; encrypted data will not survive a restart"
+                );
+                Ok(FieldCipher::ephemeral())
+            }
+        }
+    }
+
+    fn build_token_registry(config: &Config) -> Result<TokenRegistry> {
+        let registry = match &config.tokens.registry_file {
+            Some(path) => TokenRegistry::from_file(path).context("Failed to load token registry")?,
+            None => TokenRegistry::new(TokenRegistry::default_tokens(&config.oracle.usdc_issuer))?,
+        };
+
+        Ok(registry)
+    }
+
+    fn build_price_oracle(config: &Config, tokens: Arc<TokenRegistry>) -> Result<PriceOracle> {
+        let stale_after = chrono::Duration::seconds(config.oracle.stale_after_seconds);
+
+        if config.oracle.mode == "offline" {
+            let source = match &config.oracle.offline_rates_file {
+                Some(path) => ScriptedPriceSource::from_file(path, chrono::Utc::now())
+                    .context("Failed to load offline rates")?,
+                None => ScriptedPriceSource::default(),
+            };
+
+            tracing::warn!("Price oracle running in offline mode; no external rate sources are used");
+            return Ok(PriceOracle::new(vec![Arc::new(source)], config.oracle.max_deviation, stale_after));
+        }
+
+        let mut sources: Vec<Arc<dyn PriceSource>> = Vec::new();
+
+        for name in config.oracle.enabled_sources() {
+            let source: Arc<dyn PriceSource> = match name.as_str() {
+                "coingecko" => Arc::new(CoinGeckoSource::new(
+                    config.external_apis.coingecko_api_url.clone(),
+                    tokens.clone(),
+                )),
+                "stellar_dex" => Arc::new(StellarDexSource::new(
+                    config.stellar.horizon_url.clone(),
+                    tokens.clone(),
+                )),
+                "static" => match &config.oracle.static_rates_file {
+                    Some(path) => Arc::new(
+                        StaticPriceSource::from_file(path).context("Failed to load static rates")?,
+                    ),
+                    None => Arc::new(StaticPriceSource::default()),
+                },
+                other => return Err(anyhow::anyhow!("Unknown price source: {}", other)),
+            };
+            sources.push(source);
+        }
+
+        tracing::info!("Price oracle sources: {}", config.oracle.sources);
+
+        Ok(PriceOracle::new(sources, config.oracle.max_deviation, stale_after))
+    }
+}
+
+#[cfg(test)]
+impl AppState {
+    /// Default configuration over a fresh in-memory database with every
+    /// migration applied, for tests that drive services end to end.
+    pub async fn for_tests() -> Self {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        // The base tables predate the migrations (001 is empty), so they are
+        // created here as deployed databases already have them.
+        sqlx::raw_sql(
+            r#"
+            CREATE TABLE wallets (
+                id TEXT PRIMARY KEY NOT NULL,
+                public_key TEXT NOT NULL UNIQUE,
+                is_aa_wallet BOOLEAN NOT NULL,
+                created_at DATETIME NOT NULL,
+                updated_at DATETIME NOT NULL
+            );
+            CREATE TABLE transactions (
+                id TEXT PRIMARY KEY NOT NULL,
+                wallet_id TEXT NOT NULL,
+                tx_hash TEXT NOT NULL,
+                tx_type TEXT NOT NULL,
+                from_address TEXT,
+                to_address TEXT,
+                amount TEXT NOT NULL,
+                asset TEXT NOT NULL,
+                status TEXT NOT NULL,
+                created_at DATETIME NOT NULL
+            );
+            CREATE TABLE bank_transfers (
+                id TEXT PRIMARY KEY NOT NULL,
+                wallet_id TEXT NOT NULL,
+                public_key TEXT NOT NULL,
+                amount_fiat REAL NOT NULL,
+                currency TEXT NOT NULL,
+                bank_account_masked TEXT NOT NULL,
+                status TEXT NOT NULL,
+                rejection_reason TEXT,
+                reputation_score INTEGER,
+                created_at DATETIME NOT NULL,
+                completed_at DATETIME
+            );
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        Self::new(Config::load().unwrap(), pool).await.unwrap()
+    }
 }
\ No newline at end of file
@@ -0,0 +1,361 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::FieldError;
+
+/// Account number format a corridor pays out to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountScheme {
+    /// Mexican CLABE, 18 digits.
+    Clabe,
+    Iban,
+    /// US routing (ABA) number plus account number.
+    Aba,
+    /// No check-digit scheme known for the corridor; only basic format checks.
+    Other,
+}
+
+impl std::fmt::Display for AccountScheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AccountScheme::Clabe => write!(f, "clabe"),
+            AccountScheme::Iban => write!(f, "iban"),
+            AccountScheme::Aba => write!(f, "aba"),
+            AccountScheme::Other => write!(f, "other"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BankAccount {
+    pub scheme: AccountScheme,
+    pub country: String,
+    /// Account number without spaces or dashes.
+    pub account: String,
+    pub routing_number: Option<String>,
+    pub bank_name: Option<String>,
+}
+
+/// CLABE bank codes (first three digits) from the Banxico participant list.
+const CLABE_BANKS: &[(&str, &str)] = &[
+    ("002", "BANAMEX"),
+    ("006", "BANCOMEXT"),
+    ("009", "BANOBRAS"),
+    ("012", "BBVA MEXICO"),
+    ("014", "SANTANDER"),
+    ("019", "BANJERCITO"),
+    ("021", "HSBC"),
+    ("030", "BAJIO"),
+    ("032", "IXE"),
+    ("036", "INBURSA"),
+    ("042", "MIFEL"),
+    ("044", "SCOTIABANK"),
+    ("058", "BANREGIO"),
+    ("059", "INVEX"),
+    ("060", "BANSI"),
+    ("062", "AFIRME"),
+    ("072", "BANORTE"),
+    ("106", "BANK OF AMERICA"),
+    ("127", "AZTECA"),
+    ("130", "COMPARTAMOS"),
+    ("137", "BANCOPPEL"),
+    ("638", "NU MEXICO"),
+    ("646", "STP"),
+    ("722", "MERCADO PAGO"),
+];
+
+/// IBAN length per country.
+const IBAN_LENGTHS: &[(&str, usize)] = &[
+    ("AD", 24), ("AT", 20), ("BE", 16), ("BG", 22), ("BR", 29), ("CH", 21), ("CY", 28),
+    ("CZ", 24), ("DE", 22), ("DK", 18), ("EE", 20), ("ES", 24), ("FI", 18), ("FR", 27),
+    ("GB", 22), ("GI", 23), ("GR", 27), ("HR", 21), ("HU", 28), ("IE", 22), ("IS", 26),
+    ("IT", 27), ("LI", 21), ("LT", 20), ("LU", 20), ("LV", 21), ("MC", 27), ("MT", 31),
+    ("NL", 18), ("NO", 15), ("PL", 28), ("PT", 25), ("RO", 24), ("SE", 24), ("SI", 19),
+    ("SK", 24), ("SM", 27),
+];
+
+/// Bank identifiers at the start of the BBAN, per country.
+const IBAN_BANKS: &[(&str, &str, &str)] = &[
+    ("DE", "37040044", "COMMERZBANK"),
+    ("DE", "50070010", "DEUTSCHE BANK"),
+    ("ES", "0049", "BANCO SANTANDER"),
+    ("ES", "0182", "BBVA"),
+    ("ES", "2100", "CAIXABANK"),
+    ("FR", "20041", "LA BANQUE POSTALE"),
+    ("FR", "30003", "SOCIETE GENERALE"),
+    ("FR", "30004", "BNP PARIBAS"),
+    ("GB", "BARC", "BARCLAYS"),
+    ("GB", "HBUK", "HSBC UK"),
+    ("GB", "NWBK", "NATWEST"),
+    ("GB", "WEST", "WESTMINSTER BANK"),
+    ("NL", "ABNA", "ABN AMRO"),
+    ("NL", "INGB", "ING"),
+    ("NL", "RABO", "RABOBANK"),
+];
+
+const ABA_BANKS: &[(&str, &str)] = &[
+    ("011000015", "FEDERAL RESERVE BANK OF BOSTON"),
+    ("021000021", "JPMORGAN CHASE"),
+    ("021000089", "CITIBANK"),
+    ("026009593", "BANK OF AMERICA"),
+    ("121000248", "WELLS FARGO"),
+];
+
+/// Country a currency pays out to when the request does not say.
+fn default_country(currency: &str) -> Option<&'static str> {
+    match currency.to_uppercase().as_str() {
+        "MXN" => Some("MX"),
+        "USD" => Some("US"),
+        "BRL" => Some("BR"),
+        "ARS" => Some("AR"),
+        _ => None,
+    }
+}
+
+fn iban_length(country: &str) -> Option<usize> {
+    IBAN_LENGTHS.iter().find(|(c, _)| *c == country).map(|(_, len)| *len)
+}
+
+fn field_error(field: &str, code: &str, message: impl Into<String>) -> Vec<FieldError> {
+    vec![FieldError::new(field, code, message)]
+}
+
+/// Account number without spaces or dashes, uppercased.
+pub fn normalize(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .collect::<String>()
+        .to_uppercase()
+}
+
+/// Validates a payout account for the corridor given by `currency` and, when
+/// set, the destination `country`. An account that looks like an IBAN picks
+/// its own country.
+pub fn validate(
+    currency: &str,
+    country: Option<&str>,
+    account: &str,
+    routing_number: Option<&str>,
+) -> Result<BankAccount, Vec<FieldError>> {
+    let account = normalize(account);
+    if account.is_empty() {
+        return Err(field_error("bank_account", "required", "Bank account is required"));
+    }
+
+    // `get` rather than slicing: the first two bytes may split a multi-byte character.
+    let iban_country = account
+        .get(..2)
+        .filter(|prefix| account.len() > 4 && prefix.chars().all(|c| c.is_ascii_alphabetic()))
+        .filter(|prefix| iban_length(prefix).is_some());
+
+    let country = match (country.map(|c| c.trim().to_uppercase()), iban_country) {
+        (Some(country), _) => country,
+        (None, Some(prefix)) => prefix.to_string(),
+        (None, None) => match default_country(currency) {
+            Some(country) => country.to_string(),
+            None => {
+                return Err(field_error(
+                    "bank_country",
+                    "required",
+                    format!("Destination country is required for {} transfers", currency.to_uppercase()),
+                ))
+            }
+        },
+    };
+
+    if country.len() != 2 || !country.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(field_error("bank_country", "invalid", format!("Invalid ISO 3166 country code: {}", country)));
+    }
+
+    match country.as_str() {
+        "MX" => validate_clabe(&account).map(|bank_name| BankAccount {
+            scheme: AccountScheme::Clabe,
+            country,
+            account,
+            routing_number: None,
+            bank_name,
+        }),
+        "US" => validate_aba(&account, routing_number).map(|(routing_number, bank_name)| BankAccount {
+            scheme: AccountScheme::Aba,
+            country,
+            account,
+            routing_number: Some(routing_number),
+            bank_name,
+        }),
+        // Brazil has IBANs, but payouts usually go to branch + account numbers.
+        _ if iban_length(&country).is_some() && (iban_country.is_some() || country != "BR") => {
+            validate_iban(&account, &country).map(|bank_name| BankAccount {
+                scheme: AccountScheme::Iban,
+                country,
+                account,
+                routing_number: None,
+                bank_name,
+            })
+        }
+        _ => {
+            if !(4..=34).contains(&account.len()) || !account.chars().all(|c| c.is_ascii_alphanumeric()) {
+                return Err(field_error(
+                    "bank_account",
+                    "invalid_format",
+                    "Bank account must be 4 to 34 letters or digits",
+                ));
+            }
+            Ok(BankAccount {
+                scheme: AccountScheme::Other,
+                country,
+                account,
+                routing_number: None,
+                bank_name: None,
+            })
+        }
+    }
+}
+
+/// Checks length and the weighted (3, 7, 1) check digit. Returns the bank
+/// name when the bank code is known.
+fn validate_clabe(account: &str) -> Result<Option<String>, Vec<FieldError>> {
+    if account.len() != 18 || !account.chars().all(|c| c.is_ascii_digit()) {
+        return Err(field_error("bank_account", "invalid_length", "CLABE must be exactly 18 digits"));
+    }
+
+    let digits: Vec<u32> = account.chars().filter_map(|c| c.to_digit(10)).collect();
+    let sum: u32 = digits[..17]
+        .iter()
+        .zip([3, 7, 1].iter().cycle())
+        .map(|(d, w)| (d * w) % 10)
+        .sum();
+    if (10 - sum % 10) % 10 != digits[17] {
+        return Err(field_error("bank_account", "invalid_checksum", "CLABE check digit does not match"));
+    }
+
+    Ok(CLABE_BANKS
+        .iter()
+        .find(|(code, _)| account.starts_with(code))
+        .map(|(_, name)| name.to_string()))
+}
+
+/// Checks the country length and the mod-97 check digits.
+fn validate_iban(account: &str, country: &str) -> Result<Option<String>, Vec<FieldError>> {
+    if !account.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(field_error("bank_account", "invalid_format", "IBAN may only contain letters and digits"));
+    }
+
+    if !account.starts_with(country) {
+        return Err(field_error(
+            "bank_account",
+            "country_mismatch",
+            format!("{} transfers need a {} IBAN", country, country),
+        ));
+    }
+
+    let expected = iban_length(country).unwrap_or_default();
+    if account.len() != expected {
+        return Err(field_error(
+            "bank_account",
+            "invalid_length",
+            format!("{} IBAN must be {} characters", country, expected),
+        ));
+    }
+
+    let rearranged = format!("{}{}", &account[4..], &account[..4]);
+    let remainder = rearranged.chars().fold(0u32, |rem, c| {
+        let value = c.to_digit(36).unwrap_or_default();
+        if value >= 10 {
+            (rem * 100 + value) % 97
+        } else {
+            (rem * 10 + value) % 97
+        }
+    });
+    if remainder != 1 {
+        return Err(field_error("bank_account", "invalid_checksum", "IBAN check digits do not match"));
+    }
+
+    let bban = &account[4..];
+    Ok(IBAN_BANKS
+        .iter()
+        .find(|(c, code, _)| *c == country && bban.starts_with(code))
+        .map(|(_, _, name)| name.to_string()))
+}
+
+/// Checks the routing number's ABA checksum and the account number's
+/// format. Returns the routing number and the bank name when known.
+fn validate_aba(account: &str, routing_number: Option<&str>) -> Result<(String, Option<String>), Vec<FieldError>> {
+    let mut errors = Vec::new();
+
+    if !(4..=17).contains(&account.len()) || !account.chars().all(|c| c.is_ascii_digit()) {
+        errors.push(FieldError::new("bank_account", "invalid_format", "US account number must be 4 to 17 digits"));
+    }
+
+    let routing = routing_number.map(normalize).unwrap_or_default();
+    if routing.is_empty() {
+        errors.push(FieldError::new("routing_number", "required", "Routing number is required for US transfers"));
+    } else if routing.len() != 9 || !routing.chars().all(|c| c.is_ascii_digit()) {
+        errors.push(FieldError::new("routing_number", "invalid_length", "Routing number must be exactly 9 digits"));
+    } else {
+        let d: Vec<u32> = routing.chars().filter_map(|c| c.to_digit(10)).collect();
+        let sum = 3 * (d[0] + d[3] + d[6]) + 7 * (d[1] + d[4] + d[7]) + (d[2] + d[5] + d[8]);
+        if !sum.is_multiple_of(10) {
+            errors.push(FieldError::new("routing_number", "invalid_checksum", "Routing number checksum does not match"));
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let bank_name = ABA_BANKS
+        .iter()
+        .find(|(code, _)| *code == routing)
+        .map(|(_, name)| name.to_string());
+    Ok((routing, bank_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn codes(result: Result<BankAccount, Vec<FieldError>>) -> Vec<String> {
+        result.unwrap_err().into_iter().map(|e| format!("{}:{}", e.field, e.code)).collect()
+    }
+
+    #[test]
+    fn test_clabe() {
+        let account = validate("MXN", None, "032 180 000118359719", None).unwrap();
+        assert_eq!(account.scheme, AccountScheme::Clabe);
+        assert_eq!(account.account, "032180000118359719");
+        assert_eq!(account.bank_name.as_deref(), Some("IXE"));
+
+        assert_eq!(codes(validate("MXN", None, "032180000118359718", None)), ["bank_account:invalid_checksum"]);
+        assert_eq!(codes(validate("MXN", None, "03218000011835971", None)), ["bank_account:invalid_length"]);
+    }
+
+    #[test]
+    fn test_iban() {
+        let account = validate("EUR", None, "DE89 3704 0044 0532 0130 00", None).unwrap();
+        assert_eq!(account.scheme, AccountScheme::Iban);
+        assert_eq!(account.country, "DE");
+        assert_eq!(account.bank_name.as_deref(), Some("COMMERZBANK"));
+
+        assert!(validate("EUR", None, "NL91ABNA0417164300", None).is_ok());
+        assert_eq!(codes(validate("EUR", None, "GB82WEST12345698765431", None)), ["bank_account:invalid_checksum"]);
+        assert_eq!(codes(validate("EUR", None, "DE8937040044053201300", None)), ["bank_account:invalid_length"]);
+        assert_eq!(codes(validate("EUR", Some("FR"), "DE89370400440532013000", None)), ["bank_account:country_mismatch"]);
+        assert_eq!(codes(validate("EUR", None, "12345678", None)), ["bank_country:required"]);
+        // A multi-byte first character must not be sliced through.
+        assert_eq!(codes(validate("EUR", None, "É12345678", None)), ["bank_country:required"]);
+    }
+
+    #[test]
+    fn test_aba() {
+        let account = validate("USD", None, "123456789", Some("021000021")).unwrap();
+        assert_eq!(account.scheme, AccountScheme::Aba);
+        assert_eq!(account.bank_name.as_deref(), Some("JPMORGAN CHASE"));
+
+        assert_eq!(codes(validate("USD", None, "123456789", Some("123456789"))), ["routing_number:invalid_checksum"]);
+        assert_eq!(
+            codes(validate("USD", None, "12", None)),
+            ["bank_account:invalid_format", "routing_number:required"]
+        );
+    }
+}
\ No newline at end of file
@@ -38,6 +38,34 @@ pub fn calculate_checksum(data: &[u8]) -> Vec<u8> {
     second_hash[..2].to_vec()
 }
 
+/// Raw ed25519 key of a `G...` account address, or `None` when the address
+/// is malformed or its checksum does not match.
+pub fn decode_stellar_public(address: &str) -> Option<[u8; 32]> {
+    let data = base32::decode(base32::Alphabet::RFC4648 { padding: false }, address)?;
+    if data.len() != 35 || data[0] != 6 << 3 {
+        return None;
+    }
+
+    let checksum = crc16_xmodem(&data[..33]).to_le_bytes();
+    if data[33..] != checksum {
+        return None;
+    }
+
+    data[1..33].try_into().ok()
+}
+
+/// StrKey checksum (CRC16-XModem), as used by Stellar addresses.
+pub fn crc16_xmodem(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0;
+    for byte in data {
+        crc ^= (*byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 { (crc << 1) ^ 0x1021 } else { crc << 1 };
+        }
+    }
+    crc
+}
+
 pub fn mask_string(s: &str, visible_chars: usize) -> String {
     if s.len() <= visible_chars {
         return "*".repeat(s.len());
@@ -69,6 +97,16 @@ mod tests {
         assert_eq!(mask_string("123", 4), "***");
     }
 
+    #[test]
+    fn test_decode_stellar_public() {
+        let key = decode_stellar_public("GBRPYHIL2CI3FNQ4BXLFMNHLFJUNPU2HY3ZMFSHONUCEOAVW7QC7O7CR").unwrap();
+        assert_eq!(hex::encode(key), "62fc1d0bd091b2b61c0dd65634eb2a68d7d347c6f2c2c8ee6d044702b6fc05f7");
+
+        assert!(decode_stellar_public("GBRPYHIL2CI3FNQ4BXLFMNHLFJUNPU2HY3ZMFSHONUCEOAVW7QC7O7CA").is_none());
+        assert!(decode_stellar_public("SBRPYHIL2CI3FNQ4BXLFMNHLFJUNPU2HY3ZMFSHONUCEOAVW7QC7O7CR").is_none());
+        assert!(decode_stellar_public("not an address").is_none());
+    }
+
     #[test]
     fn test_validate_stellar_address() {
         assert!(validate_stellar_address("GABC...").is_ok());
@@ -0,0 +1,76 @@
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use anyhow::{anyhow, Context, Result};
+
+const NONCE_LEN: usize = 12;
+
+/// AES-256-GCM for sensitive columns. Values are stored as hex of
+/// `nonce || ciphertext`, with a fresh random nonce per value.
+#[derive(Clone)]
+pub struct FieldCipher {
+    cipher: Aes256Gcm,
+}
+
+impl FieldCipher {
+    /// `key` is 32 bytes, hex encoded.
+    pub fn from_hex(key: &str) -> Result<Self> {
+        let bytes = hex::decode(key.trim()).context("Encryption key must be hex")?;
+        if bytes.len() != 32 {
+            return Err(anyhow!("Encryption key must be 32 bytes, got {}", bytes.len()));
+        }
+
+        Ok(Self {
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&bytes)),
+        })
+    }
+
+    /// Random key that only lives as long as the process.
+    pub fn ephemeral() -> Self {
+        Self {
+            cipher: Aes256Gcm::new(&Aes256Gcm::generate_key(&mut OsRng)),
+        }
+    }
+
+    pub fn encrypt(&self, plaintext: &str) -> Result<String> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, plaintext.as_bytes())
+            .map_err(|_| anyhow!("Encryption failed"))?;
+
+        let mut out = nonce.to_vec();
+        out.extend_from_slice(&ciphertext);
+        Ok(hex::encode(out))
+    }
+
+    pub fn decrypt(&self, encoded: &str) -> Result<String> {
+        let bytes = hex::decode(encoded).context("Encrypted value is not hex")?;
+        if bytes.len() <= NONCE_LEN {
+            return Err(anyhow!("Encrypted value is too short"));
+        }
+
+        let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+        let plaintext = self
+            .cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| anyhow!("Decryption failed; wrong key or corrupted value"))?;
+
+        String::from_utf8(plaintext).context("Decrypted value is not UTF-8")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let cipher = FieldCipher::from_hex(&"ab".repeat(32)).unwrap();
+        let encrypted = cipher.encrypt("032180000118359719").unwrap();
+
+        assert_ne!(encrypted, cipher.encrypt("032180000118359719").unwrap());
+        assert_eq!(cipher.decrypt(&encrypted).unwrap(), "032180000118359719");
+        assert!(FieldCipher::ephemeral().decrypt(&encrypted).is_err());
+        assert!(FieldCipher::from_hex("abcd").is_err());
+    }
+}
\ No newline at end of file
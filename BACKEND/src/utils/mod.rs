@@ -1,2 +1,5 @@
+pub mod bank_account;
 pub mod crypto;
-pub mod stellar_client;
\ No newline at end of file
+pub mod encryption;
+pub mod stellar_client;
+pub mod watchlist;
\ No newline at end of file
@@ -2,6 +2,9 @@ use anyhow::{Context, Result};
 use reqwest::Client;
 use serde_json::Value;
 
+use crate::modules::models::amount::{Amount, FixedPoint};
+use crate::modules::models::wallet::Balance;
+
 pub struct StellarClient {
     client: Client,
     horizon_url: String,
@@ -103,7 +106,7 @@ impl StellarClient {
     }
 }
 
-pub fn extract_balances(account_json: &Value) -> Vec<(String, String)> {
+pub fn extract_balances(account_json: &Value) -> Vec<Balance> {
     let balances = account_json["balances"]
         .as_array()
         .unwrap_or(&vec![]);
@@ -116,9 +119,17 @@ pub fn extract_balances(account_json: &Value) -> Vec<(String, String)> {
             balance["asset_code"].as_str().unwrap_or("UNKNOWN").to_string()
         };
         
-        let amount = balance["balance"].as_str().unwrap_or("0").to_string();
+        let amount = balance["balance"]
+            .as_str()
+            .and_then(|b| b.parse().ok())
+            .unwrap_or(Amount::ZERO);
         
-        result.push((asset_code, amount));
+        result.push(Balance {
+            asset_code,
+            balance: amount,
+            asset_issuer: balance["asset_issuer"].as_str().map(str::to_string),
+            decimals: None,
+        });
     }
 
     result
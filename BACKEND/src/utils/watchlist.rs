@@ -0,0 +1,399 @@
+//! Sanctions and internal watchlists: parsers for OFAC SDN-style CSV and XML
+//! files and our own JSON blocklist, and the exact and fuzzy matching used to
+//! screen names, Stellar addresses and bank accounts against them.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::utils::bank_account;
+
+/// OFAC marks digital currency addresses with this id type; XLM ones are Stellar accounts.
+const XLM_ADDRESS_ID_TYPE: &str = "Digital Currency Address - XLM";
+
+/// Names shorter than this (normalized) only match exactly.
+const MIN_FUZZY_NAME_LENGTH: usize = 4;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WatchlistEntry {
+    pub id: String,
+    /// Primary name first, then aliases.
+    pub names: Vec<String>,
+    pub stellar_addresses: Vec<String>,
+    pub bank_accounts: Vec<String>,
+    pub program: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchType {
+    StellarAddress,
+    BankAccount,
+    Name,
+    FuzzyName,
+}
+
+impl MatchType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MatchType::StellarAddress => "stellar_address",
+            MatchType::BankAccount => "bank_account",
+            MatchType::Name => "name",
+            MatchType::FuzzyName => "fuzzy_name",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchlistHit {
+    pub entry_id: String,
+    pub entry_name: Option<String>,
+    pub match_type: MatchType,
+    /// 1.0 for exact matches, Jaro-Winkler similarity for fuzzy names.
+    pub score: f64,
+}
+
+/// A parsed list indexed for screening.
+#[derive(Debug, Clone)]
+pub struct Watchlist {
+    entries: Vec<WatchlistEntry>,
+    names: Vec<(usize, String)>,
+    addresses: HashMap<String, usize>,
+    accounts: HashMap<String, usize>,
+}
+
+impl Watchlist {
+    pub fn new(entries: Vec<WatchlistEntry>) -> Self {
+        let mut names = Vec::new();
+        let mut addresses = HashMap::new();
+        let mut accounts = HashMap::new();
+
+        for (index, entry) in entries.iter().enumerate() {
+            for name in &entry.names {
+                let normalized = normalize_name(name);
+                if !normalized.is_empty() {
+                    names.push((index, normalized));
+                }
+            }
+            for address in &entry.stellar_addresses {
+                addresses.insert(address.trim().to_uppercase(), index);
+            }
+            for account in &entry.bank_accounts {
+                accounts.insert(bank_account::normalize(account), index);
+            }
+        }
+
+        Self { entries, names, addresses, accounts }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Every entry matching one of the values, at most one hit per entry and
+    /// value, keeping the strongest match.
+    pub fn screen(
+        &self,
+        names: &[String],
+        addresses: &[String],
+        accounts: &[String],
+        fuzzy_threshold: f64,
+    ) -> Vec<WatchlistHit> {
+        let mut hits = Vec::new();
+
+        for address in addresses {
+            if let Some(&index) = self.addresses.get(&address.trim().to_uppercase()) {
+                hits.push(self.hit(index, MatchType::StellarAddress, 1.0));
+            }
+        }
+
+        for account in accounts {
+            if let Some(&index) = self.accounts.get(&bank_account::normalize(account)) {
+                hits.push(self.hit(index, MatchType::BankAccount, 1.0));
+            }
+        }
+
+        for name in names {
+            let name = normalize_name(name);
+            if name.is_empty() {
+                continue;
+            }
+
+            let mut best: HashMap<usize, f64> = HashMap::new();
+            for (index, listed) in &self.names {
+                let score = if *listed == name {
+                    1.0
+                } else if name.len() >= MIN_FUZZY_NAME_LENGTH && listed.len() >= MIN_FUZZY_NAME_LENGTH {
+                    name_similarity(&name, listed)
+                } else {
+                    continue;
+                };
+                if score >= fuzzy_threshold {
+                    let best_score = best.entry(*index).or_default();
+                    *best_score = best_score.max(score);
+                }
+            }
+
+            for (index, score) in best {
+                let match_type = if score >= 1.0 { MatchType::Name } else { MatchType::FuzzyName };
+                hits.push(self.hit(index, match_type, score));
+            }
+        }
+
+        hits
+    }
+
+    fn hit(&self, index: usize, match_type: MatchType, score: f64) -> WatchlistHit {
+        let entry = &self.entries[index];
+        WatchlistHit {
+            entry_id: entry.id.clone(),
+            entry_name: entry.names.first().cloned(),
+            match_type,
+            score,
+        }
+    }
+}
+
+/// OFAC `sdn.csv`: no header; entity number, name, type, program, ... and
+/// remarks in the twelfth column. `-0-` marks an empty field.
+pub fn parse_sdn_csv(contents: &str) -> Result<Vec<WatchlistEntry>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(contents.as_bytes());
+
+    let mut entries = Vec::new();
+    for (line, record) in reader.records().enumerate() {
+        let record = record.with_context(|| format!("Invalid SDN CSV record {}", line + 1))?;
+        let field = |index: usize| record.get(index).map(str::trim).filter(|value| !value.is_empty() && *value != "-0-");
+
+        let (Some(id), Some(name)) = (field(0), field(1)) else {
+            continue;
+        };
+
+        entries.push(WatchlistEntry {
+            id: id.to_string(),
+            names: vec![name.to_string()],
+            stellar_addresses: field(11).map(stellar_addresses_in).unwrap_or_default(),
+            bank_accounts: Vec::new(),
+            program: field(3).map(str::to_string),
+        });
+    }
+
+    Ok(entries)
+}
+
+/// OFAC `sdn.xml`: `sdnEntry` elements with names, `akaList` aliases and
+/// `idList` ids, where XLM digital currency addresses are listed.
+pub fn parse_sdn_xml(contents: &str) -> Result<Vec<WatchlistEntry>> {
+    let document = roxmltree::Document::parse(contents).context("Invalid SDN XML")?;
+
+    let child = |node: roxmltree::Node, name: &str| {
+        node.children()
+            .find(|child| child.has_tag_name(name))
+            .and_then(|child| child.text())
+            .map(str::trim)
+            .filter(|text| !text.is_empty())
+            .map(str::to_string)
+    };
+    let full_name = |node: roxmltree::Node| {
+        let parts = [child(node, "firstName"), child(node, "lastName")];
+        let name = parts.into_iter().flatten().collect::<Vec<_>>().join(" ");
+        (!name.is_empty()).then_some(name)
+    };
+
+    let mut entries = Vec::new();
+    for node in document.descendants().filter(|node| node.has_tag_name("sdnEntry")) {
+        let Some(id) = child(node, "uid") else {
+            continue;
+        };
+
+        let mut entry = WatchlistEntry {
+            id,
+            program: node
+                .descendants()
+                .find(|child| child.has_tag_name("program"))
+                .and_then(|program| program.text())
+                .map(|program| program.trim().to_string()),
+            ..Default::default()
+        };
+
+        entry.names.extend(full_name(node));
+        for aka in node.descendants().filter(|child| child.has_tag_name("aka")) {
+            entry.names.extend(full_name(aka));
+        }
+
+        for id in node.descendants().filter(|child| child.has_tag_name("id")) {
+            if child(id, "idType").as_deref() == Some(XLM_ADDRESS_ID_TYPE) {
+                entry.stellar_addresses.extend(child(id, "idNumber"));
+            }
+        }
+        if let Some(remarks) = child(node, "remarks") {
+            for address in stellar_addresses_in(&remarks) {
+                if !entry.stellar_addresses.contains(&address) {
+                    entry.stellar_addresses.push(address);
+                }
+            }
+        }
+
+        if !entry.names.is_empty() || !entry.stellar_addresses.is_empty() {
+            entries.push(entry);
+        }
+    }
+
+    Ok(entries)
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct InternalList {
+    #[serde(default)]
+    stellar_addresses: Vec<String>,
+    #[serde(default)]
+    bank_accounts: Vec<String>,
+    #[serde(default)]
+    names: Vec<String>,
+}
+
+/// Our own blocklist: `{"stellar_addresses": [...], "bank_accounts": [...], "names": [...]}`.
+pub fn parse_internal(contents: &str) -> Result<Vec<WatchlistEntry>> {
+    let list: InternalList = serde_json::from_str(contents).context("Invalid internal watchlist JSON")?;
+
+    let entry = |kind: &str, index: usize| WatchlistEntry {
+        id: format!("{}:{}", kind, index + 1),
+        ..Default::default()
+    };
+
+    let mut entries = Vec::new();
+    for (index, address) in list.stellar_addresses.into_iter().enumerate() {
+        entries.push(WatchlistEntry { stellar_addresses: vec![address], ..entry("stellar_address", index) });
+    }
+    for (index, account) in list.bank_accounts.into_iter().enumerate() {
+        entries.push(WatchlistEntry { bank_accounts: vec![account], ..entry("bank_account", index) });
+    }
+    for (index, name) in list.names.into_iter().enumerate() {
+        entries.push(WatchlistEntry { names: vec![name], ..entry("name", index) });
+    }
+
+    Ok(entries)
+}
+
+/// Uppercase ASCII letters and digits separated by single spaces, with
+/// common Latin accents folded.
+pub fn normalize_name(name: &str) -> String {
+    name.chars()
+        .map(fold_accent)
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Jaro-Winkler similarity of two normalized names, also comparing them with
+/// their words sorted so "DOE, JOHN" matches "JOHN DOE".
+pub fn name_similarity(a: &str, b: &str) -> f64 {
+    let sorted = |name: &str| {
+        let mut words = name.split(' ').collect::<Vec<_>>();
+        words.sort_unstable();
+        words.join(" ")
+    };
+    strsim::jaro_winkler(a, b).max(strsim::jaro_winkler(&sorted(a), &sorted(b)))
+}
+
+/// Stellar account ids (`G` + 55 base32 characters) mentioned in free text.
+pub fn stellar_addresses_in(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|token| {
+            token.len() == 56
+                && token.starts_with('G')
+                && token.chars().all(|c| c.is_ascii_uppercase() || ('2'..='7').contains(&c))
+        })
+        .map(str::to_string)
+        .collect()
+}
+
+fn fold_accent(c: char) -> char {
+    match c {
+        'á' | 'à' | 'â' | 'ä' | 'ã' | 'å' | 'Á' | 'À' | 'Â' | 'Ä' | 'Ã' | 'Å' => 'A',
+        'é' | 'è' | 'ê' | 'ë' | 'É' | 'È' | 'Ê' | 'Ë' => 'E',
+        'í' | 'ì' | 'î' | 'ï' | 'Í' | 'Ì' | 'Î' | 'Ï' => 'I',
+        'ó' | 'ò' | 'ô' | 'ö' | 'õ' | 'Ó' | 'Ò' | 'Ô' | 'Ö' | 'Õ' => 'O',
+        'ú' | 'ù' | 'û' | 'ü' | 'Ú' | 'Ù' | 'Û' | 'Ü' => 'U',
+        'ñ' | 'Ñ' => 'N',
+        'ç' | 'Ç' => 'C',
+        _ => c,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDRESS: &str = "GCKFBEIYV2U22IO2BJ4KVJOIP7XPWQGQFKKWXR6DOSJBV7STMAQSMTGG";
+
+    #[test]
+    fn test_parse_sdn_csv() {
+        let csv = format!(
+            "36,\"AEROCARIBBEAN AIRLINES\",-0- ,\"CUBA\",-0- ,-0- ,-0- ,-0- ,-0- ,-0- ,-0- ,-0- \n\
+             306,\"DOE, John\",\"individual\",\"CYBER2\",-0- ,-0- ,-0- ,-0- ,-0- ,-0- ,-0- ,\"Digital Currency Address - XLM {};\"\n",
+            ADDRESS
+        );
+        let entries = parse_sdn_csv(&csv).unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].names, vec!["AEROCARIBBEAN AIRLINES"]);
+        assert_eq!(entries[0].program.as_deref(), Some("CUBA"));
+        assert!(entries[0].stellar_addresses.is_empty());
+        assert_eq!(entries[1].stellar_addresses, vec![ADDRESS]);
+    }
+
+    #[test]
+    fn test_parse_sdn_xml() {
+        let xml = format!(
+            r#"<sdnList xmlns="http://tempuri.org/sdnList.xsd">
+              <sdnEntry>
+                <uid>306</uid><firstName>John</firstName><lastName>Doe</lastName>
+                <programList><program>CYBER2</program></programList>
+                <akaList><aka><uid>1</uid><lastName>Johnny D</lastName></aka></akaList>
+                <idList><id><idType>Digital Currency Address - XLM</idType><idNumber>{}</idNumber></id></idList>
+              </sdnEntry>
+            </sdnList>"#,
+            ADDRESS
+        );
+        let entries = parse_sdn_xml(&xml).unwrap();
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].names, vec!["John Doe", "Johnny D"]);
+        assert_eq!(entries[0].stellar_addresses, vec![ADDRESS]);
+        assert_eq!(entries[0].program.as_deref(), Some("CYBER2"));
+    }
+
+    #[test]
+    fn test_screen() {
+        let mut entries = parse_sdn_csv("306,\"DOE, John\",\"individual\",\"CYBER2\"\n").unwrap();
+        entries.extend(
+            parse_internal(&format!(
+                r#"{{"stellar_addresses": ["{}"], "bank_accounts": ["0321 8000 0118 359719"]}}"#,
+                ADDRESS
+            ))
+            .unwrap(),
+        );
+        let list = Watchlist::new(entries);
+        let screen = |name: &str| list.screen(&[name.to_string()], &[], &[], 0.9);
+
+        assert_eq!(screen("doe, john")[0].match_type, MatchType::Name);
+        assert_eq!(screen("John Doe")[0].match_type, MatchType::Name);
+        assert_eq!(screen("Jon Doé")[0].match_type, MatchType::FuzzyName);
+        assert!(screen("Jane Smith").is_empty());
+
+        let hits = list.screen(&[], &[ADDRESS.to_lowercase()], &["032180000118359719".to_string()], 0.9);
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].entry_id, "stellar_address:1");
+        assert_eq!(hits[1].match_type, MatchType::BankAccount);
+    }
+}
\ No newline at end of file